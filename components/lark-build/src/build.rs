@@ -1,13 +1,17 @@
+use crate::source_map::{GeneratedSource, SourceMap};
+use crate::BuildError;
 use crate::CodegenType;
+use lark_error::Diagnostic;
 
 /// Build a source file using the default tools on the given platform
 pub fn build(
     target_filename: &str,
-    src: &String,
+    src: &GeneratedSource,
     codegen_type: CodegenType,
-) -> std::io::Result<()> {
+) -> Result<(), BuildError> {
     match codegen_type {
         CodegenType::Rust => build_rust(target_filename, src),
+        CodegenType::C => Ok(build_c(target_filename, &src.text)?),
     }
 }
 
@@ -20,35 +24,125 @@ fn create_src_file(codegen_type: CodegenType) -> tempfile::NamedTempFile {
             .rand_bytes(6)
             .create()
             .unwrap(),
-        /*
         CodegenType::C => tempfile::NamedTempFileOptions::new()
             .prefix("lark")
             .suffix(".c")
             .rand_bytes(6)
             .create()
             .unwrap(),
-        */
     };
 
     temp_file
 }
 
-/// Invoke the Rust compiler to build the source file
-fn build_rust(target_filename: &str, src: &String) -> std::io::Result<()> {
+/// Invoke the Rust compiler to build the source file. If it fails,
+/// its errors are reported against the Lark code that the offending
+/// Rust code came from.
+fn build_rust(target_filename: &str, src: &GeneratedSource) -> Result<(), BuildError> {
     use std::io::Write;
     use std::process::Command;
 
     let mut src_file = create_src_file(CodegenType::Rust);
-    src_file.write_all(src.as_bytes()).unwrap();
+    src_file.write_all(src.text.as_bytes()).unwrap();
     let src_file_name = src_file.path().to_string_lossy().to_string();
 
     let output = Command::new(r"rustc")
+        .arg("--error-format=json")
+        // Arithmetic that overflows panics, just as it fails in the
+        // evaluator, no matter how the code is optimized.
+        .arg("-C")
+        .arg("overflow-checks=on")
+        // Arithmetic that rustc can see will always fail must still
+        // compile, so that it fails when it runs, like in the evaluator.
+        .arg("-A")
+        .arg("arithmetic_overflow")
+        .arg("-A")
+        .arg("unconditional_panic")
         .arg(src_file_name)
         .arg("-o")
         .arg(target_filename)
         .output()
         .expect("Failed to run Rust compiler");
 
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let compile_stderr = String::from_utf8_lossy(&output.stderr);
+    let diagnostics = rustc_diagnostics(&compile_stderr, &src.source_map);
+    if diagnostics.is_empty() {
+        // Nothing we could map back to Lark code, so just pass along
+        // whatever the compiler said
+        Ok(compiler_result(output)?)
+    } else {
+        Err(BuildError::Diagnostics(diagnostics))
+    }
+}
+
+/// Converts the errors in the JSON output of rustc into diagnostics on
+/// the Lark code the erroneous Rust was generated from. Errors that
+/// cannot be traced back to Lark code are skipped.
+fn rustc_diagnostics(compile_stderr: &str, source_map: &SourceMap) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+
+    for line in compile_stderr.lines() {
+        let message: serde_json::Value = match serde_json::from_str(line) {
+            Ok(message) => message,
+            Err(_) => continue,
+        };
+
+        if message["level"] != "error" {
+            continue;
+        }
+
+        // rustc's line numbers start at 1
+        let primary_line = message["spans"]
+            .as_array()
+            .and_then(|spans| spans.iter().find(|span| span["is_primary"] == true))
+            .and_then(|span| span["line_start"].as_u64());
+
+        let span = match primary_line.and_then(|line| source_map.span_for_line(line as usize - 1)) {
+            Some(span) => span,
+            None => continue,
+        };
+
+        let label = format!(
+            "generated Rust code failed to compile: {}",
+            message["message"].as_str().unwrap_or("unknown error")
+        );
+        diagnostics.push(Diagnostic::new(label, span));
+    }
+
+    diagnostics
+}
+
+/// Invoke the C compiler to build the source file. Like most build
+/// tools, we use `cc` unless the `CC` environment variable names a
+/// different compiler.
+fn build_c(target_filename: &str, src: &String) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::process::Command;
+
+    let mut src_file = create_src_file(CodegenType::C);
+    src_file.write_all(src.as_bytes()).unwrap();
+    let src_file_name = src_file.path().to_string_lossy().to_string();
+
+    let compiler = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+
+    let output = Command::new(compiler)
+        .arg("-std=c99")
+        .arg(src_file_name)
+        .arg("-o")
+        .arg(target_filename)
+        .output()
+        .expect("Failed to run C compiler");
+
+    compiler_result(output)
+}
+
+/// Converts the output of a compiler run into an error holding the
+/// compiler's messages, if it failed.
+fn compiler_result(output: std::process::Output) -> std::io::Result<()> {
     if output.status.success() {
         Ok(())
     } else {
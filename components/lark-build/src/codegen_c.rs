@@ -0,0 +1,1283 @@
+use lark_debug_with::DebugWith;
+use lark_entity::{Entity, EntityData, ItemKind, LangItem, MemberKind};
+use lark_error::{Diagnostic, ErrorReported, WithError};
+use lark_intern::{Intern, Untern};
+use lark_mir::{
+    BasicBlock, BinOp, FnBytecode, MirDatabase, Operand, OperandData, Place, PlaceData, Rvalue,
+    RvalueData, Statement, StatementKind, Terminator, UnOp, Variable,
+};
+use lark_parser::ParserDatabase;
+use lark_query_system::LarkDatabase;
+use lark_span::{FileName, Span};
+use lark_ty::base_inferred::BaseInferred;
+use lark_ty::declaration::Declaration;
+use lark_ty::{BaseKind, BoundVarOr, Ty};
+
+/// Headers and runtime support that every generated C file starts with.
+/// Arithmetic goes through these helpers so that overflow and division
+/// by zero fail the same way they do in the evaluator.
+const PRELUDE: &str = r#"#include <inttypes.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+static void lark_rt_panic(const char *message) {
+    fprintf(stderr, "error: %s\n", message);
+    exit(101);
+}
+
+static uint32_t lark_rt_uint_add(uint32_t left, uint32_t right) {
+    if (left > UINT32_MAX - right) lark_rt_panic("attempt to add with overflow");
+    return left + right;
+}
+
+static uint32_t lark_rt_uint_sub(uint32_t left, uint32_t right) {
+    if (left < right) lark_rt_panic("attempt to subtract with overflow");
+    return left - right;
+}
+
+static uint32_t lark_rt_uint_mul(uint32_t left, uint32_t right) {
+    if (right != 0 && left > UINT32_MAX / right) lark_rt_panic("attempt to multiply with overflow");
+    return left * right;
+}
+
+static uint32_t lark_rt_uint_div(uint32_t left, uint32_t right) {
+    if (right == 0) lark_rt_panic("attempt to divide by zero");
+    return left / right;
+}
+
+static int32_t lark_rt_int_add(int32_t left, int32_t right) {
+    if ((right > 0 && left > INT32_MAX - right) || (right < 0 && left < INT32_MIN - right))
+        lark_rt_panic("attempt to add with overflow");
+    return left + right;
+}
+
+static int32_t lark_rt_int_sub(int32_t left, int32_t right) {
+    if ((right < 0 && left > INT32_MAX + right) || (right > 0 && left < INT32_MIN + right))
+        lark_rt_panic("attempt to subtract with overflow");
+    return left - right;
+}
+
+static int32_t lark_rt_int_mul(int32_t left, int32_t right) {
+    int64_t result = (int64_t) left * (int64_t) right;
+    if (result > INT32_MAX || result < INT32_MIN) lark_rt_panic("attempt to multiply with overflow");
+    return (int32_t) result;
+}
+
+static int32_t lark_rt_int_div(int32_t left, int32_t right) {
+    if (right == 0) lark_rt_panic("attempt to divide by zero");
+    if (left == INT32_MIN && right == -1) lark_rt_panic("attempt to divide with overflow");
+    return left / right;
+}
+
+static int32_t lark_rt_int_neg(int32_t value) {
+    if (value == INT32_MIN) lark_rt_panic("attempt to negate with overflow");
+    return -value;
+}
+
+"#;
+
+/// Name of the local that holds the value of the last statement
+/// executed, which is what a function returns.
+const RESULT_VARIABLE: &str = "lark_result";
+
+/// The C type used for values of the named type `entity`. Returns
+/// `None` for the unit type, which has no values worth storing.
+fn build_named_type(db: &LarkDatabase, entity: Entity) -> Option<String> {
+    match entity.untern(db) {
+        EntityData::LangItem(LangItem::Boolean) => Some("bool".into()),
+        EntityData::LangItem(LangItem::Int) => Some("int32_t".into()),
+        EntityData::LangItem(LangItem::Uint) => Some("uint32_t".into()),
+        EntityData::LangItem(LangItem::String) => Some("const char *".into()),
+        EntityData::LangItem(LangItem::Tuple(0)) => None,
+        EntityData::ItemName {
+            kind: ItemKind::Struct,
+            ..
+        }
+        | EntityData::ItemName {
+            kind: ItemKind::Enum,
+            ..
+        } => Some(format!("struct {}", build_struct_name(db, entity))),
+        x => unreachable!(
+            "values of type {:#?} are rejected by `unsupported_items`",
+            x.debug_with(db)
+        ),
+    }
+}
+
+/// The C return type for functions returning the named type `entity`.
+fn build_return_type(db: &LarkDatabase, entity: Entity) -> String {
+    build_named_type(db, entity).unwrap_or_else(|| "void".into())
+}
+
+fn declaration_type_entity(db: &LarkDatabase, ty: &Ty<Declaration>) -> Entity {
+    match ty.base.untern(db) {
+        BoundVarOr::BoundVar(_) => {
+            unreachable!("generic items are rejected by `unsupported_items`")
+        }
+        BoundVarOr::Known(ty) => match ty.kind {
+            BaseKind::Named(entity) => entity,
+            BaseKind::Placeholder(_) | BaseKind::Error => {
+                unreachable!("only programs without errors are generated")
+            }
+        },
+    }
+}
+
+fn inferred_type_entity(db: &LarkDatabase, ty: Ty<BaseInferred>) -> Entity {
+    match ty.base.untern(db).kind {
+        BaseKind::Named(entity) => entity,
+        BaseKind::Placeholder(_) | BaseKind::Error => {
+            unreachable!("only programs without errors are generated")
+        }
+    }
+}
+
+fn lang_item(db: &LarkDatabase, item: LangItem) -> Entity {
+    EntityData::LangItem(item).intern(db)
+}
+
+fn is_unit(db: &LarkDatabase, entity: Entity) -> bool {
+    entity == lang_item(db, LangItem::Tuple(0))
+}
+
+/// The named type of the value stored in `place`.
+fn place_type_entity(db: &LarkDatabase, fn_bytecode: &FnBytecode, place: Place) -> Entity {
+    match &fn_bytecode.tables[place] {
+        PlaceData::Variable(variable) => inferred_type_entity(db, fn_bytecode.tables[*variable].ty),
+        PlaceData::Entity(entity) => match entity.untern(db) {
+            EntityData::LangItem(LangItem::True) | EntityData::LangItem(LangItem::False) => {
+                lang_item(db, LangItem::Boolean)
+            }
+            x => unreachable!(
+                "function values are rejected by `unsupported_items`: {:#?}",
+                x.debug_with(db)
+            ),
+        },
+        PlaceData::Field { owner, name } => {
+            let owner_entity = place_type_entity(db, fn_bytecode, *owner);
+            let name = fn_bytecode.tables[*name].text;
+            let members = db.members(owner_entity).unwrap();
+            let member = members
+                .iter()
+                .find(|member| member.kind == MemberKind::Field && member.name == name)
+                .unwrap();
+            declaration_type_entity(db, &db.ty(member.entity).into_value())
+        }
+        PlaceData::VariantField { variant, name, .. } => {
+            let name = fn_bytecode.tables[*name].text;
+            let members = db.members(*variant).unwrap();
+            let member = members
+                .iter()
+                .find(|member| member.kind == MemberKind::Field && member.name == name)
+                .unwrap();
+            declaration_type_entity(db, &db.ty(member.entity).into_value())
+        }
+        PlaceData::Index { .. } => unreachable!("lists are rejected by `unsupported_items`"),
+        PlaceData::Error(_) => {
+            unreachable!("Cannot generate code for a place that failed to lower")
+        }
+    }
+}
+
+/// The named type of the value of `operand`.
+fn operand_type_entity(db: &LarkDatabase, fn_bytecode: &FnBytecode, operand: Operand) -> Entity {
+    match &fn_bytecode.tables[operand] {
+        OperandData::ConstantInt(_) => lang_item(db, LangItem::Int),
+        OperandData::ConstantUint(_) => lang_item(db, LangItem::Uint),
+        OperandData::ConstantString(_) => lang_item(db, LangItem::String),
+        OperandData::Copy(place) | OperandData::Move(place) => {
+            place_type_entity(db, fn_bytecode, *place)
+        }
+        OperandData::Error(_) => {
+            unreachable!("Cannot generate code for an operand that failed to lower")
+        }
+    }
+}
+
+/// The named type of the value of `rvalue`.
+fn rvalue_type_entity(db: &LarkDatabase, fn_bytecode: &FnBytecode, rvalue: Rvalue) -> Entity {
+    match &fn_bytecode.tables[rvalue] {
+        RvalueData::Use(operand) => operand_type_entity(db, fn_bytecode, *operand),
+        RvalueData::BinaryOp(BinOp::Eq, ..)
+        | RvalueData::BinaryOp(BinOp::Ne, ..)
+        | RvalueData::BinaryOp(BinOp::Lt, ..)
+        | RvalueData::BinaryOp(BinOp::Le, ..)
+        | RvalueData::BinaryOp(BinOp::Gt, ..)
+        | RvalueData::BinaryOp(BinOp::Ge, ..)
+        | RvalueData::UnaryOp(UnOp::Not, _) => lang_item(db, LangItem::Boolean),
+        RvalueData::BinaryOp(_, left, _) => operand_type_entity(db, fn_bytecode, *left),
+        RvalueData::UnaryOp(UnOp::Neg, operand) => operand_type_entity(db, fn_bytecode, *operand),
+        RvalueData::Call(entity, _) => match entity.untern(db) {
+            EntityData::LangItem(LangItem::Debug) => lang_item(db, LangItem::Tuple(0)),
+            _ => {
+                let signature = db.signature(*entity).into_value().unwrap();
+                declaration_type_entity(db, &signature.output)
+            }
+        },
+        RvalueData::Aggregate(entity, _) => match entity.untern(db) {
+            EntityData::MemberName {
+                kind: MemberKind::Variant,
+                base,
+                ..
+            } => base,
+            _ => *entity,
+        },
+    }
+}
+
+/// The variants of the enum `entity`, in the order in which they are
+/// declared; a variant is represented by its index in this list.
+fn enum_variants(db: &LarkDatabase, entity: Entity) -> Vec<lark_hir::Member> {
+    db.members(entity)
+        .unwrap()
+        .iter()
+        .filter(|m| m.kind == MemberKind::Variant)
+        .cloned()
+        .collect()
+}
+
+/// The tag that represents the variant `variant` of an enum.
+fn variant_tag(db: &LarkDatabase, variant: Entity) -> usize {
+    let enum_entity = match variant.untern(db) {
+        EntityData::MemberName { base, .. } => base,
+        x => unreachable!("not a variant: {:#?}", x.debug_with(db)),
+    };
+
+    enum_variants(db, enum_entity)
+        .iter()
+        .position(|m| m.entity == variant)
+        .unwrap()
+}
+
+fn build_variable_name(db: &LarkDatabase, fn_bytecode: &FnBytecode, variable: Variable) -> String {
+    let variable_data = fn_bytecode.tables[variable];
+    let identifier = fn_bytecode.tables[variable_data.name];
+
+    // C has no shadowing within a function, so make each variable's
+    // name unique.
+    format!("{}_{}", identifier.text.untern(db), variable.as_u32())
+}
+
+fn build_function_name(db: &LarkDatabase, entity: Entity) -> String {
+    format!("lark_fn_{}", crate::item_name(db, entity))
+}
+
+fn build_struct_name(db: &LarkDatabase, entity: Entity) -> String {
+    format!("lark_ty_{}", crate::item_name(db, entity))
+}
+
+/// Fields (and the payloads of enum variants) are prefixed too, so
+/// that they can't clash with C keywords like `int`.
+fn build_field_name(name: impl std::fmt::Display) -> String {
+    format!("lark_field_{}", name)
+}
+
+fn build_block_label(basic_block: BasicBlock) -> String {
+    format!("bb{}", basic_block.as_u32())
+}
+
+/// Formats `s` as a C string literal.
+fn build_string_literal(s: &str) -> String {
+    let mut output = String::from("\"");
+
+    for c in s.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\t' => output.push_str("\\t"),
+            '\r' => output.push_str("\\r"),
+            // Avoid accidentally forming a trigraph
+            '?' => output.push_str("\\?"),
+            c if c.is_ascii_control() => output.push_str(&format!("\\{:03o}", c as u32)),
+            c => output.push(c),
+        }
+    }
+
+    output.push('"');
+    output
+}
+
+/// Builds an expression comparing `left` and `right`, which are both
+/// values of the named type `entity`.
+fn build_equality(db: &LarkDatabase, entity: Entity, left: &str, right: &str) -> String {
+    match entity.untern(db) {
+        EntityData::LangItem(LangItem::String) => format!("(strcmp({}, {}) == 0)", left, right),
+        EntityData::LangItem(LangItem::Tuple(0)) => "true".into(),
+        EntityData::ItemName {
+            kind: ItemKind::Struct,
+            ..
+        }
+        | EntityData::ItemName {
+            kind: ItemKind::Enum,
+            ..
+        } => format!(
+            "lark_eq_{}({}, {})",
+            crate::item_name(db, entity),
+            left,
+            right
+        ),
+        _ => format!("({} == {})", left, right),
+    }
+}
+
+/// Builds an expression printing `value`, of the named type `entity`,
+/// the way the evaluator displays it.
+fn build_print(db: &LarkDatabase, entity: Entity, value: &str) -> String {
+    match entity.untern(db) {
+        EntityData::LangItem(LangItem::Boolean) => {
+            format!("fputs({} ? \"true\" : \"false\", stdout)", value)
+        }
+        EntityData::LangItem(LangItem::Int) => format!("printf(\"%\" PRId32, {})", value),
+        EntityData::LangItem(LangItem::Uint) => format!("printf(\"%\" PRIu32, {})", value),
+        EntityData::LangItem(LangItem::String) => format!("fputs({}, stdout)", value),
+        // Unit values are never stored, so there is nothing to read
+        EntityData::LangItem(LangItem::Tuple(0)) => "fputs(\"()\", stdout)".into(),
+        EntityData::ItemName {
+            kind: ItemKind::Struct,
+            ..
+        }
+        | EntityData::ItemName {
+            kind: ItemKind::Enum,
+            ..
+        } => format!("lark_print_{}({})", crate::item_name(db, entity), value),
+        x => unreachable!(
+            "values of type {:#?} are rejected by `unsupported_items`",
+            x.debug_with(db)
+        ),
+    }
+}
+
+/// Builds an expression printing `value`, of the named type `entity`,
+/// on its own line.
+fn build_debug(db: &LarkDatabase, entity: Entity, value: &str) -> String {
+    format!("({}, putchar('\\n'))", build_print(db, entity, value))
+}
+
+/// Builds the statements printing a struct or variant called `name`
+/// the way it is constructed: `name(field: value, ...)`. Each of the
+/// `fields` is a field name along with an expression printing its
+/// value.
+fn build_fields_print(name: &str, fields: &[(String, String)], indent: &str) -> String {
+    let mut statements = vec![format!(
+        "fputs({}, stdout);",
+        build_string_literal(&format!("{}(", name))
+    )];
+
+    for (index, (field, print)) in fields.iter().enumerate() {
+        let separator = if index == 0 { "" } else { ", " };
+        statements.push(format!(
+            "fputs({}, stdout);",
+            build_string_literal(&format!("{}{}: ", separator, field))
+        ));
+        statements.push(format!("{};", print));
+    }
+
+    statements.push("fputs(\")\", stdout);".into());
+    statements
+        .iter()
+        .map(|statement| format!("{}{}\n", indent, statement))
+        .collect()
+}
+
+fn build_arithmetic(db: &LarkDatabase, op: BinOp, entity: Entity) -> &'static str {
+    if entity == lang_item(db, LangItem::Int) {
+        match op {
+            BinOp::Add => "lark_rt_int_add",
+            BinOp::Sub => "lark_rt_int_sub",
+            BinOp::Mul => "lark_rt_int_mul",
+            BinOp::Div => "lark_rt_int_div",
+            BinOp::Eq
+            | BinOp::Ne
+            | BinOp::Lt
+            | BinOp::Le
+            | BinOp::Gt
+            | BinOp::Ge
+            | BinOp::Concat => unreachable!(),
+        }
+    } else if entity == lang_item(db, LangItem::Uint) {
+        match op {
+            BinOp::Add => "lark_rt_uint_add",
+            BinOp::Sub => "lark_rt_uint_sub",
+            BinOp::Mul => "lark_rt_uint_mul",
+            BinOp::Div => "lark_rt_uint_div",
+            BinOp::Eq
+            | BinOp::Ne
+            | BinOp::Lt
+            | BinOp::Le
+            | BinOp::Gt
+            | BinOp::Ge
+            | BinOp::Concat => unreachable!(),
+        }
+    } else {
+        unreachable!("the type checker only allows arithmetic on `int` and `uint`");
+    }
+}
+
+pub fn build_place(db: &LarkDatabase, fn_bytecode: &FnBytecode, place: Place) -> String {
+    match &fn_bytecode.tables[place] {
+        PlaceData::Variable(variable) => build_variable_name(db, fn_bytecode, *variable),
+        PlaceData::Entity(entity) => match entity.untern(db) {
+            EntityData::LangItem(LangItem::False) => "false".into(),
+            EntityData::LangItem(LangItem::True) => "true".into(),
+            x => unreachable!(
+                "function values are rejected by `unsupported_items`: {:#?}",
+                x.debug_with(db)
+            ),
+        },
+        PlaceData::Field { owner, name } => {
+            let identifier = fn_bytecode.tables[*name];
+
+            format!(
+                "{}.{}",
+                build_place(db, fn_bytecode, *owner),
+                build_field_name(identifier.text.untern(db))
+            )
+        }
+        PlaceData::VariantField {
+            owner,
+            variant,
+            name,
+        } => {
+            let identifier = fn_bytecode.tables[*name];
+            let variant_name = match variant.untern(db) {
+                EntityData::MemberName { id, .. } => id.untern(db),
+                x => unreachable!("not a variant: {:#?}", x.debug_with(db)),
+            };
+
+            format!(
+                "{}.payload.{}.{}",
+                build_place(db, fn_bytecode, *owner),
+                build_field_name(variant_name),
+                build_field_name(identifier.text.untern(db))
+            )
+        }
+        PlaceData::Index { .. } => unreachable!("lists are rejected by `unsupported_items`"),
+        PlaceData::Error(_) => {
+            unreachable!("Cannot generate code for a place that failed to lower")
+        }
+    }
+}
+
+fn build_operand(db: &LarkDatabase, fn_bytecode: &FnBytecode, operand: Operand) -> String {
+    match &fn_bytecode.tables[operand] {
+        // `-2147483648` would negate a literal that is too large for
+        // an `int32_t`
+        OperandData::ConstantInt(i) if *i == i32::min_value() => "INT32_MIN".to_string(),
+        OperandData::ConstantInt(i) => format!("INT32_C({})", i),
+        OperandData::ConstantUint(u) => format!("UINT32_C({})", u),
+        OperandData::ConstantString(s) => build_string_literal(s),
+        OperandData::Copy(place) | OperandData::Move(place) => build_place(db, fn_bytecode, *place),
+        OperandData::Error(_) => {
+            unreachable!("Cannot generate code for an operand that failed to lower")
+        }
+    }
+}
+
+pub fn codegen_struct(db: &LarkDatabase, entity: Entity) -> WithError<String> {
+    let name = crate::item_name(db, entity);
+    let struct_name = build_struct_name(db, entity);
+    let members = db.members(entity).unwrap();
+    let mut output = String::new();
+    let mut errors: Vec<Diagnostic> = vec![];
+    let mut comparisons = vec![];
+    let mut prints = vec![];
+
+    output.push_str(&format!("struct {} {{\n", struct_name));
+
+    for member in members.iter().filter(|m| m.kind == MemberKind::Field) {
+        let member_name = build_field_name(member.name.untern(db));
+        let member_ty = db.ty(member.entity).accumulate_errors_into(&mut errors);
+        let member_entity = declaration_type_entity(db, &member_ty);
+        prints.push((
+            member.name.untern(db).to_string(),
+            build_print(db, member_entity, &format!("value.{}", member_name)),
+        ));
+
+        if let Some(member_type) = build_named_type(db, member_entity) {
+            output.push_str(&format!("    {} {};\n", member_type, member_name));
+            comparisons.push(build_equality(
+                db,
+                member_entity,
+                &format!("left.{}", member_name),
+                &format!("right.{}", member_name),
+            ));
+        }
+    }
+
+    // C forbids empty structs
+    if comparisons.is_empty() {
+        output.push_str("    char unused;\n");
+        comparisons.push("true".into());
+    }
+
+    output.push_str("};\n\n");
+
+    // C has no `==` for structs, so generate the equivalent of a
+    // derived `PartialEq`
+    output.push_str(&format!(
+        "static bool lark_eq_{}(struct {} left, struct {} right) {{\n    return {};\n}}\n\n",
+        name,
+        struct_name,
+        struct_name,
+        comparisons.join(" && ")
+    ));
+
+    output.push_str(&format!(
+        "static void lark_print_{}(struct {} value) {{\n{}}}\n\n",
+        name,
+        struct_name,
+        build_fields_print(&crate::display_name(db, entity), &prints, "    ")
+    ));
+
+    WithError {
+        value: output,
+        errors,
+    }
+}
+
+/// Enums become a struct holding the `tag` of the variant along with
+/// a union of the payloads of the variants that have fields.
+pub fn codegen_enum(db: &LarkDatabase, entity: Entity) -> WithError<String> {
+    let name = crate::item_name(db, entity);
+    let struct_name = build_struct_name(db, entity);
+    let mut output = String::new();
+    let mut errors: Vec<Diagnostic> = vec![];
+    let mut payloads = String::new();
+    let mut comparisons = vec![];
+    let mut prints = String::new();
+
+    for (tag, variant) in enum_variants(db, entity).iter().enumerate() {
+        let variant_name = build_field_name(variant.name.untern(db));
+        let mut fields = String::new();
+        let mut field_comparisons = vec![];
+        let mut field_prints = vec![];
+
+        let members = db.members(variant.entity).unwrap();
+        for member in members.iter().filter(|m| m.kind == MemberKind::Field) {
+            let member_name = build_field_name(member.name.untern(db));
+            let member_ty = db.ty(member.entity).accumulate_errors_into(&mut errors);
+            let member_entity = declaration_type_entity(db, &member_ty);
+            field_prints.push((
+                member.name.untern(db).to_string(),
+                build_print(
+                    db,
+                    member_entity,
+                    &format!("value.payload.{}.{}", variant_name, member_name),
+                ),
+            ));
+
+            if let Some(member_type) = build_named_type(db, member_entity) {
+                fields.push_str(&format!("            {} {};\n", member_type, member_name));
+                field_comparisons.push(build_equality(
+                    db,
+                    member_entity,
+                    &format!("left.payload.{}.{}", variant_name, member_name),
+                    &format!("right.payload.{}.{}", variant_name, member_name),
+                ));
+            }
+        }
+
+        // C forbids empty structs, so variants without fields get no
+        // payload at all
+        if !field_comparisons.is_empty() {
+            payloads.push_str(&format!(
+                "        struct {{\n{}        }} {};\n",
+                fields, variant_name
+            ));
+            comparisons.push(format!(
+                "        case {}: return {};\n",
+                tag,
+                field_comparisons.join(" && ")
+            ));
+        }
+
+        // Like patterns, variants without fields are shown without
+        // parentheses
+        let shown_name = format!(
+            "{}::{}",
+            crate::display_name(db, entity),
+            variant.name.untern(db)
+        );
+        prints.push_str(&format!("        case {}:\n", tag));
+        if field_prints.is_empty() {
+            prints.push_str(&format!(
+                "            fputs({}, stdout);\n",
+                build_string_literal(&shown_name)
+            ));
+        } else {
+            prints.push_str(&build_fields_print(
+                &shown_name,
+                &field_prints,
+                "            ",
+            ));
+        }
+        prints.push_str("            break;\n");
+    }
+
+    output.push_str(&format!("struct {} {{\n    uint32_t tag;\n", struct_name));
+    if !payloads.is_empty() {
+        output.push_str(&format!("    union {{\n{}    }} payload;\n", payloads));
+    }
+    output.push_str("};\n\n");
+
+    output.push_str(&format!(
+        "static bool lark_eq_{}(struct {} left, struct {} right) {{\n",
+        name, struct_name, struct_name
+    ));
+    output.push_str("    if (left.tag != right.tag) return false;\n");
+    output.push_str(&format!(
+        "    switch (left.tag) {{\n{}        default: return true;\n    }}\n}}\n\n",
+        comparisons.join("")
+    ));
+
+    output.push_str(&format!(
+        "static void lark_print_{}(struct {} value) {{\n    switch (value.tag) {{\n{}    }}\n}}\n\n",
+        name, struct_name, prints
+    ));
+
+    WithError {
+        value: output,
+        errors,
+    }
+}
+
+pub fn codegen_rvalue(
+    db: &LarkDatabase,
+    fn_bytecode: &FnBytecode,
+    rvalue: Rvalue,
+    output: &mut String,
+) {
+    match &fn_bytecode.tables[rvalue] {
+        RvalueData::Use(operand) => output.push_str(&build_operand(db, fn_bytecode, *operand)),
+        RvalueData::BinaryOp(op, left, right) => {
+            let entity = operand_type_entity(db, fn_bytecode, *left);
+            let left = build_operand(db, fn_bytecode, *left);
+            let right = build_operand(db, fn_bytecode, *right);
+
+            match op {
+                BinOp::Eq => output.push_str(&build_equality(db, entity, &left, &right)),
+                BinOp::Ne => {
+                    output.push_str("!");
+                    output.push_str(&build_equality(db, entity, &left, &right));
+                }
+                BinOp::Lt => output.push_str(&format!("({} < {})", left, right)),
+                BinOp::Le => output.push_str(&format!("({} <= {})", left, right)),
+                BinOp::Gt => output.push_str(&format!("({} > {})", left, right)),
+                BinOp::Ge => output.push_str(&format!("({} >= {})", left, right)),
+                BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div => output.push_str(&format!(
+                    "{}({}, {})",
+                    build_arithmetic(db, *op, entity),
+                    left,
+                    right
+                )),
+                BinOp::Concat => {
+                    unreachable!("string operations are rejected by `unsupported_items`")
+                }
+            }
+        }
+        RvalueData::UnaryOp(UnOp::Not, operand) => {
+            output.push_str(&format!("!{}", build_operand(db, fn_bytecode, *operand)))
+        }
+        RvalueData::UnaryOp(UnOp::Neg, operand) => output.push_str(&format!(
+            "lark_rt_int_neg({})",
+            build_operand(db, fn_bytecode, *operand)
+        )),
+        RvalueData::Call(entity, args) => match entity.untern(db) {
+            EntityData::LangItem(LangItem::Debug) => {
+                let prints: Vec<_> = args
+                    .iter(fn_bytecode)
+                    .map(|arg| {
+                        build_debug(
+                            db,
+                            operand_type_entity(db, fn_bytecode, arg),
+                            &build_operand(db, fn_bytecode, arg),
+                        )
+                    })
+                    .collect();
+
+                if prints.is_empty() {
+                    output.push_str("(void) 0");
+                } else {
+                    output.push_str(&format!("({})", prints.join(", ")));
+                }
+            }
+            EntityData::ItemName { .. }
+            | EntityData::MemberName {
+                kind: MemberKind::Method,
+                ..
+            } => {
+                let args: Vec<_> = args
+                    .iter(fn_bytecode)
+                    .map(|arg| build_operand(db, fn_bytecode, arg))
+                    .collect();
+
+                output.push_str(&format!(
+                    "{}({})",
+                    build_function_name(db, *entity),
+                    args.join(", ")
+                ));
+            }
+            x => unreachable!(
+                "calls to {:#?} are rejected by `unsupported_items`",
+                x.debug_with(db)
+            ),
+        },
+        RvalueData::Aggregate(entity, args) => {
+            let members = db.members(*entity).unwrap();
+
+            let fields: Vec<_> = members
+                .iter()
+                .filter(|m| m.kind == MemberKind::Field)
+                .zip(args.iter(fn_bytecode))
+                .map(|(member, arg)| {
+                    format!(
+                        ".{} = {}",
+                        build_field_name(member.name.untern(db)),
+                        build_operand(db, fn_bytecode, arg)
+                    )
+                })
+                .collect();
+
+            match entity.untern(db) {
+                EntityData::MemberName {
+                    kind: MemberKind::Variant,
+                    base,
+                    id,
+                } => {
+                    let payload = if fields.is_empty() {
+                        String::new()
+                    } else {
+                        format!(
+                            ", .payload.{} = {{ {} }}",
+                            build_field_name(id.untern(db)),
+                            fields.join(", ")
+                        )
+                    };
+                    output.push_str(&format!(
+                        "({}) {{ .tag = {}{} }}",
+                        build_named_type(db, base).unwrap(),
+                        variant_tag(db, *entity),
+                        payload
+                    ));
+                }
+                _ => output.push_str(&format!(
+                    "({}) {{ {} }}",
+                    build_named_type(db, *entity).unwrap(),
+                    fields.join(", ")
+                )),
+            }
+        }
+    }
+}
+
+/// Generates `statement`. `returns_value` is true if the enclosing
+/// function returns a value, in which case the value of each expression
+/// statement is saved so that it can be returned.
+pub fn codegen_statement(
+    db: &LarkDatabase,
+    statement: Statement,
+    fn_bytecode: &FnBytecode,
+    returns_value: bool,
+    output: &mut String,
+) {
+    let statement_data = &fn_bytecode.tables[statement];
+
+    match &statement_data.kind {
+        StatementKind::Expression(rvalue) => {
+            let is_unit = is_unit(db, rvalue_type_entity(db, fn_bytecode, *rvalue));
+
+            if is_unit {
+                // A unit-typed place holds nothing to evaluate
+                if let RvalueData::Use(_) = fn_bytecode.tables[*rvalue] {
+                    return;
+                }
+            } else if returns_value {
+                output.push_str(&format!("{} = ", RESULT_VARIABLE));
+            }
+
+            codegen_rvalue(db, fn_bytecode, *rvalue, output);
+            output.push_str(";\n");
+        }
+        StatementKind::Assign(lvalue, rvalue) => {
+            if !is_unit(db, place_type_entity(db, fn_bytecode, *lvalue)) {
+                output.push_str(&format!("{} = ", build_place(db, fn_bytecode, *lvalue)));
+            }
+
+            codegen_rvalue(db, fn_bytecode, *rvalue, output);
+            output.push_str(";\n");
+        }
+        // All locals are declared at the top of the function
+        StatementKind::StorageLive(_) | StatementKind::StorageDead(_) => {}
+    }
+}
+
+pub fn codegen_basic_block(
+    db: &LarkDatabase,
+    fn_bytecode: &FnBytecode,
+    basic_block: BasicBlock,
+    returns_value: bool,
+    output: &mut String,
+) {
+    let basic_block_data = &fn_bytecode.tables[basic_block];
+
+    output.push_str(&format!("{}:;\n", build_block_label(basic_block)));
+
+    for statement in basic_block_data.statements.iter(&fn_bytecode) {
+        codegen_statement(db, statement, fn_bytecode, returns_value, output);
+    }
+
+    match &basic_block_data.terminator {
+        Terminator::Return => {
+            if returns_value {
+                output.push_str(&format!("return {};\n", RESULT_VARIABLE));
+            } else {
+                output.push_str("return;\n");
+            }
+        }
+        Terminator::Goto(target) => {
+            output.push_str(&format!("goto {};\n", build_block_label(*target)));
+        }
+        Terminator::If {
+            condition,
+            if_true,
+            if_false,
+        } => {
+            output.push_str(&format!(
+                "if ({}) goto {}; else goto {};\n",
+                build_operand(db, fn_bytecode, *condition),
+                build_block_label(*if_true),
+                build_block_label(*if_false)
+            ));
+        }
+        Terminator::Switch {
+            discriminant,
+            targets,
+        } => {
+            output.push_str(&format!(
+                "switch ({}.tag) {{\n",
+                build_operand(db, fn_bytecode, *discriminant)
+            ));
+            for (tag, (_, target)) in targets.iter().enumerate() {
+                output.push_str(&format!(
+                    "case {}: goto {};\n",
+                    tag,
+                    build_block_label(*target)
+                ));
+            }
+            output.push_str("}\n");
+        }
+    }
+}
+
+/// The structs and enums that are stored by value in the fields of
+/// the struct or enum `entity`.
+fn field_type_items(db: &LarkDatabase, entity: Entity) -> Vec<Entity> {
+    let owners = match entity.untern(db) {
+        EntityData::ItemName {
+            kind: ItemKind::Enum,
+            ..
+        } => enum_variants(db, entity)
+            .iter()
+            .map(|variant| variant.entity)
+            .collect(),
+        _ => vec![entity],
+    };
+
+    let mut items = vec![];
+    for owner in owners {
+        let members = db.members(owner).unwrap();
+        for member in members.iter().filter(|m| m.kind == MemberKind::Field) {
+            let member_entity = declaration_type_entity(db, &db.ty(member.entity).into_value());
+            match member_entity.untern(db) {
+                EntityData::ItemName {
+                    kind: ItemKind::Struct,
+                    ..
+                }
+                | EntityData::ItemName {
+                    kind: ItemKind::Enum,
+                    ..
+                } => items.push(member_entity),
+                _ => {}
+            }
+        }
+    }
+    items
+}
+
+/// Orders the structs and enums `items` so that each one comes after
+/// the types of its fields: C needs those to be complete first.
+fn dependency_order(db: &LarkDatabase, items: Vec<Entity>) -> Vec<Entity> {
+    fn visit(
+        db: &LarkDatabase,
+        entity: Entity,
+        visited: &mut Vec<Entity>,
+        order: &mut Vec<Entity>,
+    ) {
+        if visited.contains(&entity) {
+            return;
+        }
+        visited.push(entity);
+
+        for field_item in field_type_items(db, entity) {
+            visit(db, field_item, visited, order);
+        }
+        order.push(entity);
+    }
+
+    let mut visited = vec![];
+    let mut order = vec![];
+    for entity in items {
+        visit(db, entity, &mut visited, &mut order);
+    }
+    order
+}
+
+/// True if `entity` declares generic parameters of its own.
+fn is_generic(db: &LarkDatabase, entity: Entity) -> bool {
+    match db.generic_declarations(entity).into_value() {
+        Ok(generic_declarations) => !generic_declarations.declarations.is_empty(),
+        Err(ErrorReported(_)) => false,
+    }
+}
+
+/// The kind of values, if any, of the named type `entity` that the C
+/// backend cannot represent.
+fn unsupported_type(db: &LarkDatabase, entity: Entity) -> Option<&'static str> {
+    match entity.untern(db) {
+        EntityData::LangItem(LangItem::Boolean)
+        | EntityData::LangItem(LangItem::Int)
+        | EntityData::LangItem(LangItem::Uint)
+        | EntityData::LangItem(LangItem::String)
+        | EntityData::LangItem(LangItem::Tuple(0))
+        | EntityData::ItemName {
+            kind: ItemKind::Struct,
+            ..
+        }
+        | EntityData::ItemName {
+            kind: ItemKind::Enum,
+            ..
+        } => None,
+        EntityData::LangItem(LangItem::Tuple(_)) => Some("tuples"),
+        EntityData::LangItem(LangItem::List) => Some("lists"),
+        // The type of a function (or built-in) used as a value
+        _ => Some("function values"),
+    }
+}
+
+/// The types that appear in the item `entity`: the types of the fields
+/// of a struct or enum, or of the arguments, result and variables of a
+/// function.
+fn item_types(db: &LarkDatabase, entity: Entity) -> Vec<Entity> {
+    let declaration_entity = |ty: &Ty<Declaration>| match ty.base.untern(db) {
+        BoundVarOr::Known(base) => match base.kind {
+            BaseKind::Named(entity) => Some(entity),
+            BaseKind::Placeholder(_) | BaseKind::Error => None,
+        },
+        BoundVarOr::BoundVar(_) => None,
+    };
+
+    match entity.untern(db) {
+        EntityData::ItemName {
+            kind: ItemKind::Struct,
+            ..
+        }
+        | EntityData::ItemName {
+            kind: ItemKind::Enum,
+            ..
+        } => {
+            let owners: Vec<Entity> = match entity.untern(db) {
+                EntityData::ItemName {
+                    kind: ItemKind::Enum,
+                    ..
+                } => enum_variants(db, entity)
+                    .iter()
+                    .map(|variant| variant.entity)
+                    .collect(),
+                _ => vec![entity],
+            };
+
+            let mut types = vec![];
+            for owner in owners {
+                let members = db.members(owner).unwrap();
+                for member in members.iter().filter(|m| m.kind == MemberKind::Field) {
+                    types.extend(declaration_entity(&db.ty(member.entity).into_value()));
+                }
+            }
+            types
+        }
+        _ => {
+            let mut types = vec![];
+            if let Ok(signature) = db.signature(entity).into_value() {
+                types.extend(signature.inputs.iter().filter_map(&declaration_entity));
+                types.extend(declaration_entity(&signature.output));
+            }
+
+            let fn_bytecode = db.fn_bytecode(entity).into_value();
+            for variable_data in fn_bytecode.tables.variables.iter() {
+                if let BaseKind::Named(entity) = variable_data.value.ty.base.untern(db).kind {
+                    types.push(entity);
+                }
+            }
+            types
+        }
+    }
+}
+
+/// The kinds of operations in the item `entity` that the C backend
+/// cannot generate code for. Its strings are constant, so anything
+/// that builds or takes apart a string is out, and only `true` and
+/// `false` can be used as values without calling them.
+fn unsupported_operations(db: &LarkDatabase, entity: Entity) -> Vec<&'static str> {
+    match entity.untern(db) {
+        EntityData::ItemName {
+            kind: ItemKind::Struct,
+            ..
+        }
+        | EntityData::ItemName {
+            kind: ItemKind::Enum,
+            ..
+        } => vec![],
+        _ => {
+            let fn_bytecode = db.fn_bytecode(entity).into_value();
+            let operations =
+                fn_bytecode
+                    .tables
+                    .rvalues
+                    .iter()
+                    .filter_map(|rvalue| match &rvalue.value {
+                        RvalueData::BinaryOp(BinOp::Concat, ..) => Some("string operations"),
+                        RvalueData::Call(callee, _) => match callee.untern(db) {
+                            EntityData::LangItem(LangItem::StringLen)
+                            | EntityData::LangItem(LangItem::StringSubstring)
+                            | EntityData::LangItem(LangItem::Format) => Some("string operations"),
+                            EntityData::LangItem(LangItem::ListLen)
+                            | EntityData::LangItem(LangItem::ListPush) => Some("lists"),
+                            _ => None,
+                        },
+                        _ => None,
+                    });
+            let values = fn_bytecode
+                .tables
+                .places
+                .iter()
+                .filter_map(|place| match &place.value {
+                    PlaceData::Entity(entity) => match entity.untern(db) {
+                        EntityData::LangItem(LangItem::True)
+                        | EntityData::LangItem(LangItem::False) => None,
+                        _ => Some("function values"),
+                    },
+                    _ => None,
+                });
+            operations.chain(values).collect()
+        }
+    }
+}
+
+/// Reports the parts of the program that the C backend cannot generate
+/// code for. If there are any, no code is generated at all.
+fn unsupported_items(db: &LarkDatabase) -> Vec<Diagnostic> {
+    let mut errors = vec![];
+
+    for entity in crate::program_items(db) {
+        let span = db.parsed_entity(entity).characteristic_span;
+
+        if is_generic(db, entity) {
+            errors.push(unsupported(span, "generic items"));
+            continue;
+        }
+
+        let mut unsupported_kinds: Vec<&'static str> = item_types(db, entity)
+            .into_iter()
+            .filter_map(|ty| unsupported_type(db, ty))
+            .chain(unsupported_operations(db, entity))
+            .collect();
+        unsupported_kinds.sort();
+        unsupported_kinds.dedup();
+        for what in unsupported_kinds {
+            errors.push(unsupported(span, what));
+        }
+    }
+
+    errors
+}
+
+fn unsupported(span: Span<FileName>, what: &str) -> Diagnostic {
+    Diagnostic::new(format!("{} are not supported by the C backend", what), span)
+}
+
+/// Generates the C declaration (without a trailing `;` or body) for
+/// the function `entity`.
+fn build_function_header(
+    db: &LarkDatabase,
+    fn_bytecode: &FnBytecode,
+    entity: Entity,
+    errors: &mut Vec<Diagnostic>,
+) -> String {
+    let signature = db.signature(entity).accumulate_errors_into(errors).unwrap();
+
+    let arguments: Vec<_> = fn_bytecode
+        .arguments
+        .iter(&fn_bytecode)
+        .zip(signature.inputs.iter())
+        .map(|(argument, argument_type)| {
+            let entity = declaration_type_entity(db, argument_type);
+            format!(
+                "{} {}",
+                build_named_type(db, entity).unwrap(),
+                build_variable_name(db, fn_bytecode, argument)
+            )
+        })
+        .collect();
+
+    format!(
+        "static {} {}({})",
+        build_return_type(db, declaration_type_entity(db, &signature.output)),
+        build_function_name(db, entity),
+        if arguments.is_empty() {
+            "void".into()
+        } else {
+            arguments.join(", ")
+        }
+    )
+}
+
+/// Generates the function `entity`, returning its prototype along with
+/// its definition.
+pub fn codegen_function(db: &LarkDatabase, entity: Entity) -> WithError<(String, String)> {
+    let mut output = String::new();
+    let mut errors: Vec<Diagnostic> = vec![];
+
+    let fn_bytecode = db.fn_bytecode(entity).accumulate_errors_into(&mut errors);
+    let header = build_function_header(db, &fn_bytecode, entity, &mut errors);
+    let output_entity =
+        declaration_type_entity(db, &db.signature(entity).into_value().unwrap().output);
+    let returns_value = !is_unit(db, output_entity);
+
+    output.push_str(&header);
+    output.push_str(" {\n");
+
+    if returns_value {
+        output.push_str(&format!(
+            "{} {};\n",
+            build_named_type(db, output_entity).unwrap(),
+            RESULT_VARIABLE
+        ));
+    }
+
+    let arguments: Vec<_> = fn_bytecode.arguments.iter(&fn_bytecode).collect();
+    for (variable, variable_data) in fn_bytecode.tables.variables.iter_enumerated() {
+        if arguments.contains(&variable) {
+            continue;
+        }
+
+        let entity = inferred_type_entity(db, variable_data.value.ty);
+        if let Some(variable_type) = build_named_type(db, entity) {
+            output.push_str(&format!(
+                "{} {};\n",
+                variable_type,
+                build_variable_name(db, &fn_bytecode, variable)
+            ));
+        }
+    }
+
+    for basic_block in fn_bytecode.basic_blocks.iter(&fn_bytecode) {
+        codegen_basic_block(db, &fn_bytecode, basic_block, returns_value, &mut output);
+    }
+
+    output.push_str("}\n\n");
+
+    WithError {
+        value: (format!("{};\n", header), output),
+        errors,
+    }
+}
+
+/// Converts the MIR context of definitions into C source, whose `main`
+/// calls the function named `entry`
+pub fn codegen_c(db: &LarkDatabase, entry: &str) -> WithError<String> {
+    let unsupported_errors = unsupported_items(db);
+    if !unsupported_errors.is_empty() {
+        return WithError {
+            value: String::new(),
+            errors: unsupported_errors,
+        };
+    }
+
+    let mut structs = String::new();
+    let mut prototypes = String::new();
+    let mut functions = String::new();
+    let mut type_items = vec![];
+    let mut main_entity = None;
+    let mut errors: Vec<Diagnostic> = vec![];
+
+    for entity in crate::program_items(db) {
+        match entity.untern(&db) {
+            EntityData::ItemName {
+                kind: ItemKind::Function,
+                ..
+            }
+            | EntityData::MemberName {
+                kind: MemberKind::Method,
+                ..
+            } => {
+                let mut result = codegen_function(db, entity);
+                if result.errors.len() > 0 {
+                    errors.append(&mut result.errors);
+                } else {
+                    let (prototype, definition) = result.value;
+                    prototypes.push_str(&prototype);
+                    functions.push_str(&definition);
+                    if crate::item_name(db, entity) == entry {
+                        main_entity = Some(entity);
+                    }
+                }
+            }
+            EntityData::ItemName {
+                kind: ItemKind::Struct,
+                ..
+            }
+            | EntityData::ItemName {
+                kind: ItemKind::Enum,
+                ..
+            } => type_items.push(entity),
+            x => unreachable!("not a program item: {:#?}", x.debug_with(db)),
+        }
+    }
+
+    for entity in dependency_order(db, type_items) {
+        let mut result = match entity.untern(db) {
+            EntityData::ItemName {
+                kind: ItemKind::Enum,
+                ..
+            } => codegen_enum(db, entity),
+            _ => codegen_struct(db, entity),
+        };
+        if result.errors.len() > 0 {
+            errors.append(&mut result.errors);
+        } else {
+            structs.push_str(&result.value);
+        }
+    }
+
+    let mut output = String::from(PRELUDE);
+    output.push_str(&structs);
+    output.push_str(&prototypes);
+    output.push_str("\n");
+    output.push_str(&functions);
+
+    if let Some(main_entity) = main_entity {
+        output.push_str(&format!(
+            "int main(void) {{\n{}();\nreturn 0;\n}}\n",
+            build_function_name(db, main_entity)
+        ));
+    }
+
+    WithError {
+        value: output,
+        errors,
+    }
+}
@@ -1,32 +1,201 @@
+use crate::source_map::{GeneratedSource, SourceWriter};
 use lark_debug_with::DebugWith;
-use lark_entity::{Entity, EntityData, ItemKind, LangItem};
+use lark_entity::{Entity, EntityData, ItemKind, LangItem, MemberKind};
 use lark_error::{Diagnostic, WithError};
 use lark_intern::{Intern, Untern};
 use lark_mir::{
-    BasicBlock, FnBytecode, MirDatabase, Operand, OperandData, Place, PlaceData, Rvalue,
-    RvalueData, Statement, StatementKind,
+    BasicBlock, BinOp, FnBytecode, MirDatabase, Operand, OperandData, Place, PlaceData, Rvalue,
+    RvalueData, Statement, StatementKind, Terminator, UnOp,
 };
-use lark_parser::{ParserDatabase, ParserDatabaseExt};
+use lark_parser::ParserDatabase;
 use lark_query_system::LarkDatabase;
-use lark_ty::Ty;
+use lark_ty::base_inferred::BaseInferred;
+use lark_ty::declaration::Declaration;
+use lark_ty::{BaseKind, BoundVarOr, GenericKind, Ty};
+use std::collections::BTreeSet;
 
-pub fn build_type(db: &LarkDatabase, ty: &Ty<lark_ty::declaration::Declaration>) -> String {
+/// Runtime support that every generated Rust file starts with.
+/// `debug` and `format` show values through `LarkDisplay` (which the
+/// generated code also implements for the structs, enums and sizes of
+/// tuple of the program), and list indexing and `substring` are checked
+/// by the helpers here, so that values and runtime errors look just
+/// like they do in the evaluator.
+const PRELUDE: &str = r#"trait LarkDisplay {
+    fn lark_fmt(&self) -> String;
+}
+
+impl LarkDisplay for bool {
+    fn lark_fmt(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl LarkDisplay for i32 {
+    fn lark_fmt(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl LarkDisplay for u32 {
+    fn lark_fmt(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl LarkDisplay for String {
+    fn lark_fmt(&self) -> String {
+        self.clone()
+    }
+}
+
+impl LarkDisplay for () {
+    fn lark_fmt(&self) -> String {
+        "()".to_string()
+    }
+}
+
+impl<T: LarkDisplay> LarkDisplay for Vec<T> {
+    fn lark_fmt(&self) -> String {
+        let elements: Vec<String> = self.iter().map(|element| element.lark_fmt()).collect();
+        format!("[{}]", elements.join(", "))
+    }
+}
+
+macro_rules! lark_tuple_display {
+    ($($element:ident $index:tt),+) => {
+        impl<$($element: LarkDisplay),+> LarkDisplay for ($($element,)+) {
+            fn lark_fmt(&self) -> String {
+                let elements: Vec<String> = vec![$(self.$index.lark_fmt()),+];
+                format!("({})", elements.join(", "))
+            }
+        }
+    };
+}
+
+fn lark_fields_fmt(name: &str, fields: Vec<String>) -> String {
+    format!("{}({})", name, fields.join(", "))
+}
+
+struct LarkIndex(u32);
+
+impl<T> std::ops::Index<LarkIndex> for Vec<T> {
+    type Output = T;
+
+    fn index(&self, index: LarkIndex) -> &T {
+        match self.get(index.0 as usize) {
+            Some(element) => element,
+            None => lark_rt_index_out_of_bounds(self.len(), index.0),
+        }
+    }
+}
+
+impl<T> std::ops::IndexMut<LarkIndex> for Vec<T> {
+    fn index_mut(&mut self, index: LarkIndex) -> &mut T {
+        let length = self.len();
+        match self.get_mut(index.0 as usize) {
+            Some(element) => element,
+            None => lark_rt_index_out_of_bounds(length, index.0),
+        }
+    }
+}
+
+fn lark_rt_index_out_of_bounds(length: usize, index: u32) -> ! {
+    panic!("index out of bounds: the length is {} but the index is {}", length, index)
+}
+
+fn lark_rt_substring(string: &str, start: u32, end: u32) -> String {
+    let length = string.chars().count() as u32;
+    if start > end || end > length {
+        panic!(
+            "substring out of bounds: the length is {} but the range is {}..{}",
+            length, start, end
+        )
+    }
+    string
+        .chars()
+        .skip(start as usize)
+        .take((end - start) as usize)
+        .collect()
+}
+
+fn lark_rt_init() {
+    std::panic::set_hook(Box::new(|info| {
+        let payload = info.payload();
+        let message = match payload.downcast_ref::<&str>() {
+            Some(message) => message.to_string(),
+            None => match payload.downcast_ref::<String>() {
+                Some(message) => message.clone(),
+                None => String::from("panic"),
+            },
+        };
+        eprintln!("error: {}", message);
+    }));
+}
+
+"#;
+
+/// Builds the Rust type for `ty`, which is declared within an item
+/// whose generic parameters are named `generics`.
+pub fn build_type(
+    db: &LarkDatabase,
+    generics: &[String],
+    ty: &Ty<lark_ty::declaration::Declaration>,
+) -> String {
     let boolean_entity = EntityData::LangItem(LangItem::Boolean).intern(db);
+    let int_entity = EntityData::LangItem(LangItem::Int).intern(db);
     let uint_entity = EntityData::LangItem(LangItem::Uint).intern(db);
     let void_entity = EntityData::LangItem(LangItem::Tuple(0)).intern(db);
+    let string_entity = EntityData::LangItem(LangItem::String).intern(db);
 
     match ty.base.untern(db) {
-        lark_ty::BoundVarOr::BoundVar(_) => unimplemented!("Bound variables not yet supported"),
+        lark_ty::BoundVarOr::BoundVar(bound_var) => generics[bound_var.as_usize()].clone(),
         lark_ty::BoundVarOr::Known(ty) => match ty.kind {
             lark_ty::BaseKind::Named(entity) => {
                 if entity == boolean_entity {
                     "bool".into()
+                } else if entity == int_entity {
+                    "i32".into()
                 } else if entity == uint_entity {
                     "u32".into()
                 } else if entity == void_entity {
                     "()".into()
+                } else if entity == string_entity {
+                    "String".into()
                 } else {
-                    unimplemented!("Unknown type: {:#?}", entity)
+                    match entity.untern(db) {
+                        EntityData::ItemName {
+                            kind: ItemKind::Struct,
+                            ..
+                        }
+                        | EntityData::ItemName {
+                            kind: ItemKind::Enum,
+                            ..
+                        } => {
+                            let arguments: Vec<String> = ty
+                                .generics
+                                .iter()
+                                .map(|generic| build_type(db, generics, &generic.assert_ty()))
+                                .collect();
+                            format!(
+                                "{}{}",
+                                crate::item_name(db, entity),
+                                build_generic_list(&arguments)
+                            )
+                        }
+                        EntityData::LangItem(LangItem::Tuple(_)) => {
+                            let elements: Vec<String> = ty
+                                .generics
+                                .iter()
+                                .map(|generic| build_type(db, generics, &generic.assert_ty()))
+                                .collect();
+                            build_tuple(elements)
+                        }
+                        EntityData::LangItem(LangItem::List) => {
+                            let element = ty.generics.iter().next().unwrap().assert_ty();
+                            format!("Vec<{}>", build_type(db, generics, &element))
+                        }
+                        _ => unimplemented!("Unknown type: {:#?}", entity),
+                    }
                 }
             }
             _ => unimplemented!("Unknown base kind"),
@@ -34,6 +203,291 @@ pub fn build_type(db: &LarkDatabase, ty: &Ty<lark_ty::declaration::Declaration>)
     }
 }
 
+/// The names of the generic parameters in scope within `entity`,
+/// starting with those it inherits from its parent item (e.g., a
+/// method has the generic parameters of its struct).
+fn generic_parameter_names(db: &LarkDatabase, entity: Entity) -> Vec<String> {
+    let generic_declarations = match db.generic_declarations(entity).into_value() {
+        Ok(generic_declarations) => generic_declarations,
+        Err(_) => return vec![],
+    };
+
+    let mut names = match generic_declarations.parent_item {
+        Some(parent_item) => generic_parameter_names(db, parent_item),
+        None => vec![],
+    };
+    names.extend(
+        generic_declarations
+            .declarations
+            .iter()
+            .map(|declaration| match declaration {
+                GenericKind::Ty(declaration) => declaration.name.untern(db).to_string(),
+            }),
+    );
+    names
+}
+
+/// True if values of `ty` can be `Copy` in Rust: that is the case for
+/// the scalar types, and for the tuples, structs and enums made up of
+/// them.
+fn is_copy_type(db: &LarkDatabase, ty: &Ty<Declaration>) -> bool {
+    match ty.base.untern(db) {
+        // We don't know what the generic parameter will be, so we
+        // can't rely on it being `Copy`.
+        BoundVarOr::BoundVar(_) => false,
+        BoundVarOr::Known(ty) => match ty.kind {
+            BaseKind::Named(entity) => match entity.untern(db) {
+                EntityData::LangItem(LangItem::Boolean)
+                | EntityData::LangItem(LangItem::Int)
+                | EntityData::LangItem(LangItem::Uint) => true,
+                EntityData::LangItem(LangItem::Tuple(_)) => ty
+                    .generics
+                    .iter()
+                    .all(|generic| is_copy_type(db, &generic.assert_ty())),
+                EntityData::ItemName {
+                    kind: ItemKind::Struct,
+                    ..
+                }
+                | EntityData::ItemName {
+                    kind: ItemKind::Enum,
+                    ..
+                } => is_copy_item(db, entity),
+                _ => false,
+            },
+            BaseKind::Placeholder(_) | BaseKind::Error => false,
+        },
+    }
+}
+
+/// True if the struct or enum `entity` can derive `Copy`, because it
+/// is not generic and all of its fields can be `Copy`.
+fn is_copy_item(db: &LarkDatabase, entity: Entity) -> bool {
+    if !generic_parameter_names(db, entity).is_empty() {
+        return false;
+    }
+
+    let members = db.members(entity).unwrap();
+    let owners: Vec<Entity> = match entity.untern(db) {
+        EntityData::ItemName {
+            kind: ItemKind::Enum,
+            ..
+        } => members
+            .iter()
+            .filter(|m| m.kind == MemberKind::Variant)
+            .map(|m| m.entity)
+            .collect(),
+        _ => vec![entity],
+    };
+
+    owners.into_iter().all(|owner| {
+        db.members(owner)
+            .unwrap()
+            .iter()
+            .filter(|m| m.kind == MemberKind::Field)
+            .all(|field| is_copy_type(db, &db.ty(field.entity).into_value()))
+    })
+}
+
+/// The `#[derive]` attribute for the struct or enum `entity`.
+fn build_derive(db: &LarkDatabase, entity: Entity) -> &'static str {
+    if is_copy_item(db, entity) {
+        "#[derive(Clone, Copy, PartialEq)]\n"
+    } else {
+        "#[derive(Clone, PartialEq)]\n"
+    }
+}
+
+/// The start of the `LarkDisplay` impl for the struct or enum `entity`,
+/// up to the body of `lark_fmt`. Its generic parameters `generics` must
+/// be shown too.
+fn build_display_impl(db: &LarkDatabase, entity: Entity, generics: &[String]) -> String {
+    let bounds: Vec<String> = generics
+        .iter()
+        .map(|generic| format!("{}: LarkDisplay", generic))
+        .collect();
+    format!(
+        "impl{} LarkDisplay for {}{} {{\nfn lark_fmt(&self) -> String {{\n",
+        build_generic_list(&bounds),
+        crate::item_name(db, entity),
+        build_generic_list(generics)
+    )
+}
+
+/// Builds an expression showing the field `name`, which is found at
+/// `{prefix}{name}`, as `name: value`.
+fn build_field_display(name: &str, prefix: &str) -> String {
+    format!("format!(\"{}: {{}}\", {}{}.lark_fmt())", name, prefix, name)
+}
+
+/// Generates `LarkDisplay` impls for the sizes of tuple in `arities`.
+fn build_tuple_displays(arities: &BTreeSet<usize>) -> String {
+    let mut output = String::new();
+    for &arity in arities {
+        let elements: Vec<String> = (0..arity)
+            .map(|index| format!("T{} {}", index, index))
+            .collect();
+        output.push_str(&format!("lark_tuple_display!({});\n", elements.join(", ")));
+    }
+    output
+}
+
+/// Adds the size of each tuple type within `ty`, including within its
+/// generic arguments, to `arities`.
+fn declared_tuple_arities(db: &LarkDatabase, ty: &Ty<Declaration>, arities: &mut BTreeSet<usize>) {
+    if let BoundVarOr::Known(base) = ty.base.untern(db) {
+        if let BaseKind::Named(entity) = base.kind {
+            if let EntityData::LangItem(LangItem::Tuple(arity)) = entity.untern(db) {
+                arities.insert(arity);
+            }
+        }
+        for generic in base.generics.iter() {
+            declared_tuple_arities(db, &generic.assert_ty(), arities);
+        }
+    }
+}
+
+/// Like `declared_tuple_arities`, for an inferred type.
+fn inferred_tuple_arities(db: &LarkDatabase, ty: Ty<BaseInferred>, arities: &mut BTreeSet<usize>) {
+    let base = ty.base.untern(db);
+    if let BaseKind::Named(entity) = base.kind {
+        if let EntityData::LangItem(LangItem::Tuple(arity)) = entity.untern(db) {
+            arities.insert(arity);
+        }
+    }
+    for generic in base.generics.iter() {
+        inferred_tuple_arities(db, generic.assert_ty(), arities);
+    }
+}
+
+/// The sizes of the (non-empty) tuples that the program uses: those in
+/// the types of the fields of its structs and enums, and of the
+/// variables of its functions, which between them hold every value.
+fn tuple_arities(db: &LarkDatabase) -> BTreeSet<usize> {
+    let mut arities = BTreeSet::new();
+
+    for entity in crate::program_items(db) {
+        match entity.untern(db) {
+            EntityData::ItemName {
+                kind: ItemKind::Struct,
+                ..
+            }
+            | EntityData::ItemName {
+                kind: ItemKind::Enum,
+                ..
+            } => {
+                // The fields of a struct, or of the variants of an enum
+                let mut owners = vec![entity];
+                owners.extend(
+                    db.members(entity)
+                        .unwrap()
+                        .iter()
+                        .filter(|m| m.kind == MemberKind::Variant)
+                        .map(|m| m.entity),
+                );
+                for owner in owners {
+                    let members = db.members(owner).unwrap();
+                    for field in members.iter().filter(|m| m.kind == MemberKind::Field) {
+                        let field_ty = db.ty(field.entity).into_value();
+                        declared_tuple_arities(db, &field_ty, &mut arities);
+                    }
+                }
+            }
+            _ => {
+                let fn_bytecode = db.fn_bytecode(entity).into_value();
+                for variable_data in fn_bytecode.tables.variables.iter() {
+                    inferred_tuple_arities(db, variable_data.value.ty, &mut arities);
+                }
+            }
+        }
+    }
+
+    arities.remove(&0);
+    arities
+}
+
+/// Formats generic parameters or arguments, like `<A, B>`, or nothing
+/// at all if there are none.
+fn build_generic_list(elements: &[String]) -> String {
+    if elements.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", elements.join(", "))
+    }
+}
+
+/// Formats the elements of a tuple (type or value) as a Rust tuple,
+/// which needs a trailing comma if it has a single element.
+fn build_tuple(elements: Vec<String>) -> String {
+    if elements.len() == 1 {
+        format!("({},)", elements[0])
+    } else {
+        format!("({})", elements.join(", "))
+    }
+}
+
+/// The variable that `place` is stored in (or a part of), if any.
+fn root_variable(
+    fn_bytecode: &std::sync::Arc<FnBytecode>,
+    place: Place,
+) -> Option<lark_mir::Variable> {
+    match fn_bytecode.tables[place] {
+        PlaceData::Variable(variable) => Some(variable),
+        PlaceData::Field { owner, .. }
+        | PlaceData::VariantField { owner, .. }
+        | PlaceData::Index { owner, .. } => root_variable(fn_bytecode, owner),
+        PlaceData::Entity(_) | PlaceData::Error(_) => None,
+    }
+}
+
+/// True if some list stored in `variable` is modified in place (by
+/// `push`), or if `variable` is assigned again after it was
+/// initialized (like the counter of a `for` loop), so that Rust needs
+/// the variable to be declared `mut`.
+fn is_mutated(
+    db: &LarkDatabase,
+    fn_bytecode: &std::sync::Arc<FnBytecode>,
+    variable: lark_mir::Variable,
+) -> bool {
+    let push_entity = EntityData::LangItem(LangItem::ListPush).intern(db);
+    let pushed = fn_bytecode
+        .tables
+        .rvalues
+        .iter()
+        .any(|rvalue| match &rvalue.value {
+            RvalueData::Call(entity, args) if *entity == push_entity => {
+                match fn_bytecode.tables[args.iter(fn_bytecode).next().unwrap()] {
+                    OperandData::Copy(place) | OperandData::Move(place) => {
+                        root_variable(fn_bytecode, place) == Some(variable)
+                    }
+                    _ => false,
+                }
+            }
+            _ => false,
+        });
+
+    // Arguments are initialized by the caller, so any assignment to
+    // them is a second one.
+    let assignments = fn_bytecode
+        .tables
+        .statements
+        .iter()
+        .filter(|statement| match statement.value.kind {
+            StatementKind::Assign(place, _) => match fn_bytecode.tables[place] {
+                PlaceData::Variable(assigned) => assigned == variable,
+                _ => false,
+            },
+            _ => false,
+        })
+        .count();
+    let is_argument = fn_bytecode
+        .arguments
+        .iter(fn_bytecode)
+        .any(|argument| argument == variable);
+    let initializations = if is_argument { 0 } else { 1 };
+
+    pushed || assignments > initializations
+}
+
 fn build_variable_name(
     db: &LarkDatabase,
     fn_bytecode: &std::sync::Arc<FnBytecode>,
@@ -41,7 +495,17 @@ fn build_variable_name(
 ) -> String {
     let variable_data = fn_bytecode.tables[variable];
     let identifier = fn_bytecode.tables[variable_data.name];
-    identifier.text.untern(db).to_string()
+
+    // Each variable gets a name of its own, so that no variable can
+    // clash with another one (or a hidden one, like the tuple of a
+    // `let (a, b) = ...`) that Rust would consider to be in scope.
+    format!("{}_{}", identifier.text.untern(db), variable.as_u32())
+}
+
+/// Functions are prefixed so that they can't clash with the `main`
+/// that starts the program, nor with local variables.
+fn build_function_name(db: &LarkDatabase, entity: Entity) -> String {
+    format!("lark_fn_{}", crate::item_name(db, entity))
 }
 
 fn build_entity_name(
@@ -54,7 +518,22 @@ fn build_entity_name(
         EntityData::LangItem(LangItem::False) => "false".into(),
         EntityData::LangItem(LangItem::True) => "true".into(),
         EntityData::LangItem(LangItem::Debug) => "println!".into(),
-        EntityData::ItemName { id, .. } => id.untern(db).to_string(),
+        EntityData::LangItem(LangItem::Format) => "format!".into(),
+        EntityData::ItemName {
+            kind: ItemKind::Function,
+            ..
+        } => build_function_name(db, entity),
+        EntityData::ItemName { .. } => crate::item_name(db, entity),
+        EntityData::MemberName {
+            kind: MemberKind::Method,
+            base,
+            id,
+        }
+        | EntityData::MemberName {
+            kind: MemberKind::Variant,
+            base,
+            id,
+        } => format!("{}::{}", crate::item_name(db, base), id.untern(db)),
         x => unimplemented!("Unsupported entity name: {:#?}", x),
     }
 }
@@ -76,6 +555,55 @@ pub fn build_place(
                 identifier.text.untern(db).to_string()
             )
         }
+        PlaceData::VariantField {
+            owner,
+            variant,
+            name,
+        } => {
+            // Rust has no syntax for reading the field of a variant
+            // directly, so use a `match` to get at it. That can't move
+            // the field out of the enum, so it is cloned.
+            let identifier = fn_bytecode.tables[*name];
+
+            format!(
+                "(match &{} {{ {} {{ {}, .. }} => {}.clone(), _ => unreachable!() }})",
+                build_place(db, fn_bytecode, *owner),
+                build_entity_name(db, fn_bytecode, *variant),
+                identifier.text.untern(db),
+                identifier.text.untern(db),
+            )
+        }
+        PlaceData::Index { owner, index } => format!(
+            "{}[LarkIndex({})]",
+            build_place(db, fn_bytecode, *owner),
+            build_operand(db, fn_bytecode, *index)
+        ),
+        PlaceData::Error(_) => {
+            unreachable!("Cannot generate code for a place that failed to lower")
+        }
+    }
+}
+
+fn build_bin_op(op: BinOp) -> &'static str {
+    match op {
+        BinOp::Add => "+",
+        BinOp::Sub => "-",
+        BinOp::Mul => "*",
+        BinOp::Div => "/",
+        BinOp::Eq => "==",
+        BinOp::Ne => "!=",
+        BinOp::Lt => "<",
+        BinOp::Le => "<=",
+        BinOp::Gt => ">",
+        BinOp::Ge => ">=",
+        BinOp::Concat => unreachable!("Concatenation is generated with `format!`"),
+    }
+}
+
+fn build_un_op(op: UnOp) -> &'static str {
+    match op {
+        UnOp::Not => "!",
+        UnOp::Neg => "-",
     }
 }
 
@@ -85,39 +613,192 @@ fn build_operand(
     operand: Operand,
 ) -> String {
     match &fn_bytecode.tables[operand] {
-        OperandData::ConstantInt(i) => format!("{}", i),
-        OperandData::ConstantString(s) => format!("\"{}\"", s),
+        // A negative literal is parenthesized so that it stays whole
+        // when a method is called on it
+        OperandData::ConstantInt(i) if *i < 0 => format!("({}i32)", i),
+        OperandData::ConstantInt(i) => format!("{}i32", i),
+        OperandData::ConstantUint(u) => format!("{}u32", u),
+        // `{:?}` re-escapes the string as a valid Rust literal
+        OperandData::ConstantString(s) => format!("String::from({:?})", s),
         OperandData::Copy(place) | OperandData::Move(place) => {
             //FIXME: separate copy and move
             build_place(db, fn_bytecode, *place)
         }
+        OperandData::Error(_) => {
+            unreachable!("Cannot generate code for an operand that failed to lower")
+        }
     }
 }
 
-pub fn codegen_struct(
+/// True if the value in `place` is still usable in Rust after it is
+/// handed on. We only know the types of variables here, so other
+/// places are assumed to be moved out of.
+fn is_copy_place(
     db: &LarkDatabase,
-    entity: Entity,
-    id: lark_string::GlobalIdentifier,
-) -> WithError<String> {
-    let name = id.untern(db);
+    fn_bytecode: &std::sync::Arc<FnBytecode>,
+    place: Place,
+) -> bool {
+    match fn_bytecode.tables[place] {
+        PlaceData::Variable(variable) => fn_bytecode.tables[variable]
+            .ty
+            .base
+            .untern(db)
+            .kind
+            .is_copy(db),
+        // Already cloned by `build_place`
+        PlaceData::Entity(_) | PlaceData::VariantField { .. } => true,
+        PlaceData::Field { .. } | PlaceData::Index { .. } | PlaceData::Error(_) => false,
+    }
+}
+
+/// Like `build_operand`, but for an operand whose value is handed on
+/// (to a function, a variable or a new value), which moves it in Rust.
+/// Where Lark only copies the value instead (e.g., because it is
+/// shared), it is cloned, so that it can still be used afterwards.
+fn build_owned_operand(
+    db: &LarkDatabase,
+    fn_bytecode: &std::sync::Arc<FnBytecode>,
+    operand: Operand,
+) -> String {
+    match fn_bytecode.tables[operand] {
+        OperandData::Copy(place) if !is_copy_place(db, fn_bytecode, place) => {
+            format!("{}.clone()", build_place(db, fn_bytecode, place))
+        }
+        // Rust can't move an element out of a `Vec`; Lark considers
+        // the whole list moved, so a clone of the element will do.
+        OperandData::Move(place) => match fn_bytecode.tables[place] {
+            PlaceData::Index { .. } => format!("{}.clone()", build_place(db, fn_bytecode, place)),
+            _ => build_place(db, fn_bytecode, place),
+        },
+        _ => build_operand(db, fn_bytecode, operand),
+    }
+}
+
+pub fn codegen_struct(db: &LarkDatabase, entity: Entity) -> WithError<SourceWriter> {
+    let name = crate::item_name(db, entity);
+    let generics = generic_parameter_names(db, entity);
     let members = db.members(entity).unwrap();
-    let mut output = String::new();
+    let mut output = SourceWriter::default();
     let mut errors: Vec<Diagnostic> = vec![];
 
-    output.push_str(&format!("struct {} {{\n", name));
+    output.set_span(db.parsed_entity(entity).characteristic_span);
 
-    for member in members.iter() {
+    output.push_str(build_derive(db, entity));
+    output.push_str(&format!(
+        "struct {}{} {{\n",
+        name,
+        build_generic_list(&generics)
+    ));
+
+    for member in members.iter().filter(|m| m.kind == MemberKind::Field) {
         let member_name = member.name.untern(db);
         let member_ty = db.ty(member.entity).accumulate_errors_into(&mut errors);
         output.push_str(&format!(
             "{}: {},\n",
             member_name,
-            build_type(db, &member_ty)
+            build_type(db, &generics, &member_ty)
         ));
     }
 
     output.push_str("}\n");
 
+    // Shown the way they are constructed, like `Point(x: 1, y: 2)`
+    let fields: Vec<String> = members
+        .iter()
+        .filter(|m| m.kind == MemberKind::Field)
+        .map(|member| build_field_display(&member.name.untern(db), "self."))
+        .collect();
+    output.push_str(&build_display_impl(db, entity, &generics));
+    output.push_str(&format!(
+        "lark_fields_fmt({:?}, vec![{}])\n}}\n}}\n",
+        crate::display_name(db, entity),
+        fields.join(", ")
+    ));
+
+    WithError {
+        value: output,
+        errors,
+    }
+}
+
+pub fn codegen_enum(db: &LarkDatabase, entity: Entity) -> WithError<SourceWriter> {
+    let name = crate::item_name(db, entity);
+    let generics = generic_parameter_names(db, entity);
+    let members = db.members(entity).unwrap();
+    let mut output = SourceWriter::default();
+    let mut errors: Vec<Diagnostic> = vec![];
+
+    output.set_span(db.parsed_entity(entity).characteristic_span);
+
+    // Every variant is declared with named fields (even if it has
+    // none), so that values are built like `Level::Error{code: 1,}`,
+    // just like structs.
+    output.push_str(build_derive(db, entity));
+    output.push_str(&format!(
+        "enum {}{} {{\n",
+        name,
+        build_generic_list(&generics)
+    ));
+
+    for variant in members.iter().filter(|m| m.kind == MemberKind::Variant) {
+        output.push_str(&format!("{} {{\n", variant.name.untern(db)));
+
+        let fields = db.members(variant.entity).unwrap();
+        for field in fields.iter().filter(|m| m.kind == MemberKind::Field) {
+            let field_ty = db.ty(field.entity).accumulate_errors_into(&mut errors);
+            output.push_str(&format!(
+                "{}: {},\n",
+                field.name.untern(db),
+                build_type(db, &generics, &field_ty)
+            ));
+        }
+
+        output.push_str("},\n");
+    }
+
+    output.push_str("}\n");
+
+    // Like patterns, variants without fields are shown without
+    // parentheses. Matching on `*self` (binding the fields by `ref`)
+    // works for enums without variants too.
+    output.push_str(&build_display_impl(db, entity, &generics));
+    output.push_str("match *self {\n");
+    for variant in members.iter().filter(|m| m.kind == MemberKind::Variant) {
+        let shown_name = format!(
+            "{}::{}",
+            crate::display_name(db, entity),
+            variant.name.untern(db)
+        );
+        let field_names: Vec<String> = db
+            .members(variant.entity)
+            .unwrap()
+            .iter()
+            .filter(|m| m.kind == MemberKind::Field)
+            .map(|field| field.name.untern(db).to_string())
+            .collect();
+
+        output.push_str(&format!("{}::{} {{ ", name, variant.name.untern(db)));
+        for field_name in &field_names {
+            output.push_str(&format!("ref {}, ", field_name));
+        }
+        output.push_str(".. } => ");
+
+        if field_names.is_empty() {
+            output.push_str(&format!("{:?}.to_string(),\n", shown_name));
+        } else {
+            let fields: Vec<String> = field_names
+                .iter()
+                .map(|field_name| build_field_display(field_name, ""))
+                .collect();
+            output.push_str(&format!(
+                "lark_fields_fmt({:?}, vec![{}]),\n",
+                shown_name,
+                fields.join(", ")
+            ));
+        }
+    }
+    output.push_str("}\n}\n}\n");
+
     WithError {
         value: output,
         errors,
@@ -128,23 +809,93 @@ pub fn codegen_rvalue(
     db: &LarkDatabase,
     fn_bytecode: &std::sync::Arc<FnBytecode>,
     rvalue: Rvalue,
-    output: &mut String,
+    output: &mut SourceWriter,
 ) {
     match &fn_bytecode.tables[rvalue] {
-        RvalueData::Use(operand) => output.push_str(&build_operand(db, fn_bytecode, *operand)),
+        RvalueData::Use(operand) => {
+            output.push_str(&build_owned_operand(db, fn_bytecode, *operand))
+        }
+        RvalueData::BinaryOp(BinOp::Concat, left, right) => output.push_str(&format!(
+            "format!(\"{{}}{{}}\", {}, {})",
+            build_operand(db, fn_bytecode, *left),
+            build_operand(db, fn_bytecode, *right)
+        )),
+        RvalueData::BinaryOp(op, left, right) => output.push_str(&format!(
+            "{} {} {}",
+            build_operand(db, fn_bytecode, *left),
+            build_bin_op(*op),
+            build_operand(db, fn_bytecode, *right)
+        )),
+        RvalueData::UnaryOp(op, operand) => output.push_str(&format!(
+            "{}{}",
+            build_un_op(*op),
+            build_operand(db, fn_bytecode, *operand)
+        )),
         RvalueData::Call(entity, args) => {
+            // The methods of `List` and `String` are those of Rust's
+            // `Vec` and `String`.
+            match entity.untern(db) {
+                EntityData::LangItem(LangItem::ListLen) => {
+                    let list = args.iter(fn_bytecode).next().unwrap();
+                    output.push_str(&format!(
+                        "({}.len() as u32)",
+                        build_operand(db, fn_bytecode, list)
+                    ));
+                    return;
+                }
+                EntityData::LangItem(LangItem::ListPush) => {
+                    let mut args = args.iter(fn_bytecode);
+                    let list = args.next().unwrap();
+                    let value = args.next().unwrap();
+                    output.push_str(&format!(
+                        "{}.push({})",
+                        build_operand(db, fn_bytecode, list),
+                        build_owned_operand(db, fn_bytecode, value)
+                    ));
+                    return;
+                }
+                EntityData::LangItem(LangItem::StringLen) => {
+                    let string = args.iter(fn_bytecode).next().unwrap();
+                    output.push_str(&format!(
+                        "({}.chars().count() as u32)",
+                        build_operand(db, fn_bytecode, string)
+                    ));
+                    return;
+                }
+                EntityData::LangItem(LangItem::StringSubstring) => {
+                    let mut args = args.iter(fn_bytecode);
+                    let string = build_operand(db, fn_bytecode, args.next().unwrap());
+                    let start = build_operand(db, fn_bytecode, args.next().unwrap());
+                    let end = build_operand(db, fn_bytecode, args.next().unwrap());
+                    output.push_str(&format!(
+                        "lark_rt_substring(&{}, {}, {})",
+                        string, start, end
+                    ));
+                    return;
+                }
+                _ => {}
+            }
+
             output.push_str(&build_entity_name(db, fn_bytecode, *entity));
 
             output.push_str("(");
             let mut first = true;
 
-            match entity.untern(db) {
+            // The formatting macros only borrow their arguments, which
+            // are shown through `LarkDisplay`.
+            let is_macro = match entity.untern(db) {
                 EntityData::LangItem(LangItem::Debug) => {
                     output.push_str("\"{}\"");
                     first = false;
+                    true
                 }
-                _ => {}
-            }
+                EntityData::LangItem(LangItem::Format) => {
+                    output.push_str(&format!("{:?}", "{}".repeat(args.len())));
+                    first = false;
+                    true
+                }
+                _ => false,
+            };
 
             for arg in args.iter(fn_bytecode) {
                 if !first {
@@ -153,29 +904,53 @@ pub fn codegen_rvalue(
                     first = false;
                 }
 
-                output.push_str(&build_operand(db, fn_bytecode, arg));
+                if is_macro {
+                    output.push_str(&format!(
+                        "{}.lark_fmt()",
+                        build_operand(db, fn_bytecode, arg)
+                    ));
+                } else {
+                    output.push_str(&build_owned_operand(db, fn_bytecode, arg));
+                }
             }
             output.push_str(")");
         }
         RvalueData::Aggregate(entity, args) => {
+            let elements = || -> Vec<String> {
+                args.iter(fn_bytecode)
+                    .map(|arg| build_owned_operand(db, fn_bytecode, arg))
+                    .collect()
+            };
+            match entity.untern(db) {
+                EntityData::LangItem(LangItem::Tuple(_)) => {
+                    output.push_str(&build_tuple(elements()));
+                    return;
+                }
+                EntityData::LangItem(LangItem::List) => {
+                    output.push_str(&format!("vec![{}]", elements().join(", ")));
+                    return;
+                }
+                _ => {}
+            }
+
             output.push_str(&build_entity_name(db, fn_bytecode, *entity));
             output.push_str("{");
 
             let members = db.members(*entity).unwrap();
 
-            for (member, arg) in members.iter().zip(args.iter(fn_bytecode)) {
+            let fields = members.iter().filter(|m| m.kind == MemberKind::Field);
+            for (member, arg) in fields.zip(args.iter(fn_bytecode)) {
                 let member_name = member.name.untern(db);
 
                 output.push_str(&format!(
                     "{}: {},",
                     member_name,
-                    build_operand(db, fn_bytecode, arg)
+                    build_owned_operand(db, fn_bytecode, arg)
                 ));
             }
 
             output.push_str("}");
         }
-        x => unimplemented!("Rvalue value not supported: {:#?}", x.debug_with(db)),
     }
 }
 
@@ -183,10 +958,12 @@ pub fn codegen_statement(
     db: &LarkDatabase,
     statement: Statement,
     fn_bytecode: &std::sync::Arc<FnBytecode>,
-    output: &mut String,
+    output: &mut SourceWriter,
 ) {
     let statement_data = &fn_bytecode.tables[statement];
 
+    output.set_span(fn_bytecode.tables.span(statement));
+
     match &statement_data.kind {
         StatementKind::Expression(rvalue) => {
             codegen_rvalue(db, fn_bytecode, *rvalue, output);
@@ -196,54 +973,321 @@ pub fn codegen_statement(
             codegen_rvalue(db, fn_bytecode, *rvalue, output);
         }
         StatementKind::StorageLive(variable) => {
+            let mutability = if is_mutated(db, fn_bytecode, *variable) {
+                "mut "
+            } else {
+                ""
+            };
             output.push_str(&format!(
-                "{{\nlet {};\n",
+                "let {}{};\n",
+                mutability,
                 build_variable_name(db, fn_bytecode, *variable)
             ));
         }
-        StatementKind::StorageDead(_) => {
-            output.push_str("\n}\n");
-        }
+        // Rust ends the storage of the variables declared in a block
+        // along with the block, which may also be left through a
+        // `break` or `return`
+        StatementKind::StorageDead(_) => {}
     }
 }
 
+/// Generates the statements of `basic_block`. `first` tracks whether
+/// the next statement starts a new Rust statement by itself (at the
+/// start of the enclosing Rust block, or after a `let`), so that we
+/// know where separators are needed. `returns_value` is true if the
+/// enclosing function returns a value.
 pub fn codegen_basic_block(
     db: &LarkDatabase,
     fn_bytecode: &std::sync::Arc<FnBytecode>,
     basic_block: BasicBlock,
-    output: &mut String,
+    returns_value: bool,
+    first: &mut bool,
+    output: &mut SourceWriter,
 ) {
     let basic_block_data = &fn_bytecode.tables[basic_block];
 
-    let mut first = true;
+    // The function returns the value of the last statement before a
+    // `Return`. That statement might be nested in an `if` or a `loop`,
+    // so it has to be returned explicitly.
+    let returned_statement = match basic_block_data.terminator {
+        Terminator::Return => basic_block_data
+            .statements
+            .iter(&fn_bytecode)
+            .filter(|&statement| match fn_bytecode.tables[statement].kind {
+                StatementKind::StorageLive(_) | StatementKind::StorageDead(_) => false,
+                _ => true,
+            })
+            .last()
+            .filter(|&statement| match fn_bytecode.tables[statement].kind {
+                StatementKind::Expression(_) => true,
+                _ => false,
+            }),
+        _ => None,
+    };
 
     for statement in basic_block_data.statements.iter(&fn_bytecode) {
-        if !first {
-            output.push_str(";\n")
-        } else {
-            first = false;
+        match fn_bytecode.tables[statement].kind {
+            StatementKind::StorageDead(_) => {}
+            StatementKind::StorageLive(_) => {
+                if !*first {
+                    output.push_str(";\n")
+                }
+                codegen_statement(db, statement, fn_bytecode, output);
+                *first = true;
+            }
+            _ => {
+                if !*first {
+                    output.push_str(";\n")
+                }
+                if returned_statement == Some(statement) {
+                    output.push_str("return ");
+                }
+                codegen_statement(db, statement, fn_bytecode, output);
+                *first = false;
+            }
+        }
+    }
+
+    // A bare `return` has no statement to return, but it still has to
+    // leave the function rather than fall through to the code after
+    // the enclosing `if` or `loop`.
+    if let Terminator::Return = basic_block_data.terminator {
+        if returned_statement.is_none() && !returns_value {
+            if !*first {
+                output.push_str(";\n")
+            }
+            output.push_str("return");
+            *first = false;
+        }
+    }
+}
+
+/// The loops of a function, which we turn back into Rust `loop`s.
+pub struct Loops {
+    /// The loops of the function, as recorded when it was lowered.
+    loops: Vec<lark_mir::Loop>,
+
+    /// The loops that we are generating the body of, innermost last.
+    open: Vec<lark_mir::Loop>,
+}
+
+impl Loops {
+    fn new(fn_bytecode: &FnBytecode) -> Self {
+        Loops {
+            loops: fn_bytecode.loops.clone(),
+            open: vec![],
+        }
+    }
+
+    /// The loop that `basic_block` starts, if we are not already
+    /// generating it.
+    fn starts_new_loop(&self, basic_block: BasicBlock) -> Option<lark_mir::Loop> {
+        if self.open.last().map(|l| l.header) == Some(basic_block) {
+            return None;
+        }
+
+        self.loops.iter().cloned().find(|l| l.header == basic_block)
+    }
+
+    /// The Rust code for jumping to `target`, if that restarts or
+    /// leaves the innermost loop.
+    fn jump_to(&self, target: BasicBlock) -> Option<&'static str> {
+        match self.open.last() {
+            Some(l) if l.header == target => Some("continue"),
+            Some(l) if l.exit == target => Some("break"),
+            _ => None,
         }
-        codegen_statement(db, statement, fn_bytecode, output);
     }
 }
 
-pub fn codegen_function(
+/// Generates the code for a branch that goes to `target`, which is
+/// either a jump out of the innermost loop or to its start, or else
+/// the chain of blocks starting at `target`.
+fn codegen_branch(
     db: &LarkDatabase,
-    entity: Entity,
-    id: lark_string::GlobalIdentifier,
-) -> WithError<String> {
-    let mut output = String::new();
+    fn_bytecode: &std::sync::Arc<FnBytecode>,
+    target: BasicBlock,
+    returns_value: bool,
+    loops: &mut Loops,
+    output: &mut SourceWriter,
+) -> Option<BasicBlock> {
+    match loops.jump_to(target) {
+        Some(jump) => {
+            output.push_str(jump);
+            None
+        }
+        None => codegen_block_chain(db, fn_bytecode, target, returns_value, loops, output),
+    }
+}
+
+/// Generates the code for the chain of blocks starting at `basic_block`,
+/// turning `If` and `Switch` terminators back into structured `if`/`else`
+/// and `match` expressions, and back edges into `loop`s. Returns the
+/// block that the chain jumps to when it is done, if any; for the
+/// branches of an `if` (or the arms of a `match`), this is where they
+/// join again.
+pub fn codegen_block_chain(
+    db: &LarkDatabase,
+    fn_bytecode: &std::sync::Arc<FnBytecode>,
+    basic_block: BasicBlock,
+    returns_value: bool,
+    loops: &mut Loops,
+    output: &mut SourceWriter,
+) -> Option<BasicBlock> {
+    let mut basic_block = basic_block;
+    let mut first = true;
+
+    loop {
+        if let Some(new_loop) = loops.starts_new_loop(basic_block) {
+            if !first {
+                output.push_str(";\n");
+            }
+            first = false;
+
+            output.push_str("loop {\n");
+            loops.open.push(new_loop);
+            codegen_block_chain(db, fn_bytecode, basic_block, returns_value, loops, output);
+            loops.open.pop();
+            output.push_str("\n}");
+
+            basic_block = new_loop.exit;
+            continue;
+        }
+
+        codegen_basic_block(
+            db,
+            fn_bytecode,
+            basic_block,
+            returns_value,
+            &mut first,
+            output,
+        );
+
+        match &fn_bytecode.tables[basic_block].terminator {
+            Terminator::Return => return None,
+            Terminator::Goto(target) => {
+                if let Some(jump) = loops.jump_to(*target) {
+                    if !first {
+                        output.push_str(";\n");
+                    }
+                    output.push_str(jump);
+                    return None;
+                }
+
+                if loops.starts_new_loop(*target).is_some() {
+                    basic_block = *target;
+                } else {
+                    return Some(*target);
+                }
+            }
+            Terminator::If {
+                condition,
+                if_true,
+                if_false,
+            } => {
+                if !first {
+                    output.push_str(";\n");
+                }
+                first = false;
+
+                output.set_span(fn_bytecode.tables.span(*condition));
+                output.push_str(&format!(
+                    "if {} {{\n",
+                    build_operand(db, fn_bytecode, *condition)
+                ));
+                let true_exit =
+                    codegen_branch(db, fn_bytecode, *if_true, returns_value, loops, output);
+                output.push_str("\n} else {\n");
+                let false_exit =
+                    codegen_branch(db, fn_bytecode, *if_false, returns_value, loops, output);
+                output.push_str("\n}");
+
+                match true_exit.or(false_exit) {
+                    Some(join_block) => basic_block = join_block,
+                    None => return None,
+                }
+            }
+            Terminator::Switch {
+                discriminant,
+                targets,
+            } => {
+                if !first {
+                    output.push_str(";\n");
+                }
+                first = false;
+
+                output.set_span(fn_bytecode.tables.span(*discriminant));
+                output.push_str(&format!(
+                    "match {} {{\n",
+                    build_operand(db, fn_bytecode, *discriminant)
+                ));
+
+                // Variants that go to the same block (e.g., because of
+                // a `_` pattern) share one arm.
+                let mut arms: Vec<(BasicBlock, Vec<String>)> = vec![];
+                for &(variant, target) in targets {
+                    let pattern =
+                        format!("{} {{ .. }}", build_entity_name(db, fn_bytecode, variant));
+                    match arms.iter_mut().find(|(block, _)| *block == target) {
+                        Some((_, patterns)) => patterns.push(pattern),
+                        None => arms.push((target, vec![pattern])),
+                    }
+                }
+
+                let mut exit = None;
+                for (target, patterns) in arms {
+                    output.push_str(&format!("{} => {{\n", patterns.join(" | ")));
+                    let arm_exit =
+                        codegen_branch(db, fn_bytecode, target, returns_value, loops, output);
+                    output.push_str("\n}\n");
+                    exit = exit.or(arm_exit);
+                }
+                output.push_str("}");
+
+                match exit {
+                    Some(join_block) => basic_block = join_block,
+                    None => return None,
+                }
+            }
+        }
+    }
+}
+
+pub fn codegen_function(db: &LarkDatabase, entity: Entity) -> WithError<SourceWriter> {
+    let mut output = SourceWriter::default();
     let mut errors: Vec<Diagnostic> = vec![];
 
+    output.set_span(db.parsed_entity(entity).characteristic_span);
+
     let fn_bytecode = db.fn_bytecode(entity).accumulate_errors_into(&mut errors);
     let signature = db
         .signature(entity)
         .accumulate_errors_into(&mut errors)
         .unwrap();
 
-    let name = id.untern(db);
+    // Methods become methods of an `impl` block for their struct, and
+    // are called like `Point::norm(p)`. They have the generic
+    // parameters of their struct, which the `impl` block declares.
+    let generics = generic_parameter_names(db, entity);
+    let (name, impl_struct) = match entity.untern(db) {
+        EntityData::MemberName {
+            kind: MemberKind::Method,
+            base,
+            id,
+        } => (id.untern(db).to_string(), Some(crate::item_name(db, base))),
+        _ => (build_function_name(db, entity), None),
+    };
 
-    output.push_str(&format!("fn {}(", name));
+    if let Some(impl_struct) = &impl_struct {
+        let generic_list = build_generic_list(&generics);
+        output.push_str(&format!(
+            "impl{} {}{} {{\n",
+            generic_list, impl_struct, generic_list
+        ));
+        output.push_str(&format!("fn {}(", name));
+    } else {
+        output.push_str(&format!("fn {}{}(", name, build_generic_list(&generics)));
+    }
 
     let mut first = true;
     for (argument, argument_type) in fn_bytecode
@@ -251,10 +1295,7 @@ pub fn codegen_function(
         .iter(&fn_bytecode)
         .zip(signature.inputs.iter())
     {
-        let variable_data = fn_bytecode.tables[argument];
-        let identifier = fn_bytecode.tables[variable_data.name];
-
-        let argument_name = identifier.text.untern(db);
+        let argument_name = build_variable_name(db, &fn_bytecode, argument);
 
         if !first {
             output.push_str(", ");
@@ -262,17 +1303,38 @@ pub fn codegen_function(
             first = false;
         }
 
+        if is_mutated(db, &fn_bytecode, argument) {
+            output.push_str("mut ");
+        }
         output.push_str(&format!("{}: ", argument_name));
-        output.push_str(&format!("{}", build_type(db, argument_type)));
+        output.push_str(&format!("{}", build_type(db, &generics, argument_type)));
     }
 
     output.push_str(") -> ");
-    output.push_str(&format!("{}", build_type(db, &signature.output)));
+    output.push_str(&format!("{}", build_type(db, &generics, &signature.output)));
     output.push_str(" {\n");
-    for basic_block in fn_bytecode.basic_blocks.iter(&fn_bytecode) {
-        codegen_basic_block(db, &fn_bytecode, basic_block, &mut output);
+    let unit_entity = EntityData::LangItem(LangItem::Tuple(0)).intern(db);
+    let returns_value = match signature.output.base.untern(db) {
+        BoundVarOr::Known(base) => match base.kind {
+            BaseKind::Named(entity) => entity != unit_entity,
+            _ => true,
+        },
+        BoundVarOr::BoundVar(_) => true,
+    };
+    let mut loops = Loops::new(&fn_bytecode);
+    codegen_block_chain(
+        db,
+        &fn_bytecode,
+        fn_bytecode.entry_block(),
+        returns_value,
+        &mut loops,
+        &mut output,
+    );
+    output.push_str("\n}\n");
+
+    if impl_struct.is_some() {
+        output.push_str("}\n");
     }
-    output.push_str("}\n");
 
     WithError {
         value: output,
@@ -280,48 +1342,73 @@ pub fn codegen_function(
     }
 }
 
-/// Converts the MIR context of definitions into Rust source
-pub fn codegen_rust(db: &LarkDatabase) -> WithError<String> {
-    let mut output = String::new();
-    let input_files = db.file_names();
+/// Converts the MIR context of definitions into Rust source, along with
+/// a map from the generated lines back to the Lark code they came from
+pub fn codegen_rust(db: &LarkDatabase, entry: &str) -> WithError<GeneratedSource> {
+    let mut output = SourceWriter::default();
+    let mut main_entity = None;
     let mut errors: Vec<Diagnostic> = vec![];
 
-    for &input_file in &*input_files {
-        let entities = db.top_level_entities_in_file(input_file);
+    output.push_str(PRELUDE);
+    output.push_str(&build_tuple_displays(&tuple_arities(db)));
 
-        for &entity in &*entities {
-            match entity.untern(&db) {
-                EntityData::ItemName {
-                    kind: ItemKind::Function,
-                    id,
-                    ..
-                } => {
-                    let mut result = codegen_function(db, entity, id);
-                    if result.errors.len() > 0 {
-                        errors.append(&mut result.errors);
-                    } else {
-                        output.push_str(&result.value);
+    for entity in crate::program_items(db) {
+        match entity.untern(&db) {
+            EntityData::ItemName {
+                kind: ItemKind::Function,
+                ..
+            }
+            | EntityData::MemberName {
+                kind: MemberKind::Method,
+                ..
+            } => {
+                let mut result = codegen_function(db, entity);
+                if result.errors.len() > 0 {
+                    errors.append(&mut result.errors);
+                } else {
+                    output.append(result.value);
+                    if crate::item_name(db, entity) == entry {
+                        main_entity = Some(entity);
                     }
                 }
-                EntityData::ItemName {
-                    kind: ItemKind::Struct,
-                    id,
-                    ..
-                } => {
-                    let mut result = codegen_struct(db, entity, id);
-                    if result.errors.len() > 0 {
-                        errors.append(&mut result.errors);
-                    } else {
-                        output.push_str(&result.value);
-                    }
+            }
+            EntityData::ItemName {
+                kind: ItemKind::Struct,
+                ..
+            } => {
+                let mut result = codegen_struct(db, entity);
+                if result.errors.len() > 0 {
+                    errors.append(&mut result.errors);
+                } else {
+                    output.append(result.value);
                 }
-                x => unimplemented!("Can not codegen {:#?}", x.debug_with(db)),
             }
+            EntityData::ItemName {
+                kind: ItemKind::Enum,
+                ..
+            } => {
+                let mut result = codegen_enum(db, entity);
+                if result.errors.len() > 0 {
+                    errors.append(&mut result.errors);
+                } else {
+                    output.append(result.value);
+                }
+            }
+            x => unimplemented!("Can not codegen {:#?}", x.debug_with(db)),
         }
     }
 
+    // Rust programs always start at `main`, which installs the runtime
+    // error reporting and calls the entry function.
+    if let Some(main_entity) = main_entity {
+        output.push_str(&format!(
+            "fn main() {{\nlark_rt_init();\n{}();\n}}\n",
+            build_function_name(db, main_entity)
+        ));
+    }
+
     WithError {
-        value: output,
+        value: output.finish(),
         errors,
     }
 }
@@ -1,27 +1,125 @@
+#![feature(crate_visibility_modifier)]
+
 mod build;
+mod codegen_c;
 mod codegen_rust;
+mod source_map;
 
-use lark_error::WithError;
+use lark_entity::{Entity, EntityData, ItemKind, MemberKind};
+use lark_error::{Diagnostic, WithError};
+use lark_intern::{Intern, Untern};
+use lark_parser::ParserDatabase;
 use lark_query_system::LarkDatabase;
 
+pub use crate::source_map::{GeneratedSource, SourceMap};
+
 #[derive(Copy, Clone)]
 pub enum CodegenType {
     Rust,
+    C,
+}
+
+/// Ways that building the generated source can fail
+pub enum BuildError {
+    /// The compiler rejected the generated source. Each diagnostic
+    /// points at the Lark code that the rejected code came from.
+    Diagnostics(Vec<Diagnostic>),
+
+    /// We could not run the compiler, or could not make sense of why
+    /// it failed.
+    Io(std::io::Error),
 }
 
-/// Converts the MIR context of definitions into the chosen source type
-pub fn codegen(db: &LarkDatabase, codegen_type: CodegenType) -> WithError<String> {
+impl From<std::io::Error> for BuildError {
+    fn from(error: std::io::Error) -> Self {
+        BuildError::Io(error)
+    }
+}
+
+/// Converts the MIR context of definitions into the chosen source type.
+/// The resulting program starts by calling the function named `entry`.
+pub fn codegen(
+    db: &LarkDatabase,
+    codegen_type: CodegenType,
+    entry: &str,
+) -> WithError<GeneratedSource> {
     match codegen_type {
-        CodegenType::Rust => codegen_rust::codegen_rust(db),
-        //CodegenType::C => codegen_c::codegen_c(context),
+        CodegenType::Rust => codegen_rust::codegen_rust(db, entry),
+        CodegenType::C => codegen_c::codegen_c(db, entry).map(GeneratedSource::unmapped),
+    }
+}
+
+/// The structs, enums, functions and methods of the program, including those
+/// nested within modules, file by file.
+crate fn program_items(db: &LarkDatabase) -> Vec<Entity> {
+    let mut items = vec![];
+
+    for &file in db.file_names().iter() {
+        let file_entity = EntityData::InputFile { file }.intern(db);
+        for &entity in db.descendant_entities(file_entity).iter() {
+            match entity.untern(db) {
+                EntityData::ItemName {
+                    kind: ItemKind::Struct,
+                    ..
+                }
+                | EntityData::ItemName {
+                    kind: ItemKind::Enum,
+                    ..
+                }
+                | EntityData::ItemName {
+                    kind: ItemKind::Function,
+                    ..
+                }
+                | EntityData::MemberName {
+                    kind: MemberKind::Method,
+                    ..
+                } => items.push(entity),
+                _ => {}
+            }
+        }
+    }
+
+    items
+}
+
+/// The name of the item `entity` in generated code. Items within
+/// modules are prefixed with the names of those modules (so
+/// `geometry::origin` becomes `geometry__origin`), keeping them apart
+/// from items of the same name elsewhere. Likewise, methods are
+/// prefixed with the name of their struct.
+crate fn item_name(db: &LarkDatabase, entity: Entity) -> String {
+    match entity.untern(db) {
+        EntityData::MemberName {
+            kind: MemberKind::Method,
+            base,
+            id,
+        } => format!("{}__{}", item_name(db, base), id.untern(db)),
+        EntityData::ItemName { base, id, .. } => match base.untern(db) {
+            EntityData::ItemName {
+                kind: ItemKind::Module,
+                ..
+            } => format!("{}__{}", item_name(db, base), id.untern(db)),
+            _ => id.untern(db).to_string(),
+        },
+        x => unimplemented!("Unsupported item name: {:#?}", x),
+    }
+}
+
+/// The name of the struct or enum `entity` as it is written in the
+/// program, which is how its values are shown. Unlike `item_name`,
+/// this leaves out the modules around it.
+crate fn display_name(db: &LarkDatabase, entity: Entity) -> String {
+    match entity.untern(db) {
+        EntityData::ItemName { id, .. } => id.untern(db).to_string(),
+        x => unreachable!("not a struct or enum: {:#?}", x),
     }
 }
 
 /// Builds source code for the given source type
 pub fn build(
     target_filename: &str,
-    src: &String,
+    src: &GeneratedSource,
     codegen_type: CodegenType,
-) -> std::io::Result<()> {
+) -> Result<(), BuildError> {
     build::build(target_filename, &src, codegen_type)
 }
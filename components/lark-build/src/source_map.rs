@@ -0,0 +1,93 @@
+use lark_span::{FileName, Span};
+
+/// Source code generated by one of the backends.
+pub struct GeneratedSource {
+    pub text: String,
+
+    /// Where in the Lark code each part of `text` came from.
+    pub source_map: SourceMap,
+}
+
+impl GeneratedSource {
+    /// Source code for which we don't know where anything came from.
+    pub fn unmapped(text: String) -> Self {
+        GeneratedSource {
+            text,
+            source_map: SourceMap::default(),
+        }
+    }
+}
+
+/// Maps the lines of some generated source code back to the Lark code
+/// they were generated from.
+#[derive(Default)]
+pub struct SourceMap {
+    /// Zero-based line numbers in the generated code, in increasing
+    /// order, each paired with the span of the Lark code that the
+    /// generated code starting on that line came from.
+    lines: Vec<(usize, Span<FileName>)>,
+}
+
+impl SourceMap {
+    /// Returns the span of the Lark code that the given (zero-based)
+    /// line of generated code came from, if known.
+    pub fn span_for_line(&self, line: usize) -> Option<Span<FileName>> {
+        self.lines
+            .iter()
+            .rev()
+            .find(|(start_line, _)| *start_line <= line)
+            .map(|(_, span)| *span)
+    }
+}
+
+/// Accumulates generated source code, remembering which Lark code
+/// each part of it was generated from.
+#[derive(Default)]
+crate struct SourceWriter {
+    text: String,
+
+    /// Byte offsets into `text`, in increasing order, each paired with
+    /// the span of the Lark code that the text from there on came from.
+    spans: Vec<(usize, Span<FileName>)>,
+}
+
+impl SourceWriter {
+    crate fn push_str(&mut self, text: &str) {
+        self.text.push_str(text);
+    }
+
+    /// Records that the text written from now on is generated from the
+    /// Lark code at `span`.
+    crate fn set_span(&mut self, span: Span<FileName>) {
+        self.spans.push((self.text.len(), span));
+    }
+
+    /// Appends the text (and spans) of `other` to this writer.
+    crate fn append(&mut self, other: SourceWriter) {
+        let offset = self.text.len();
+        self.text.push_str(&other.text);
+        self.spans.extend(
+            other
+                .spans
+                .into_iter()
+                .map(|(start, span)| (start + offset, span)),
+        );
+    }
+
+    crate fn finish(self) -> GeneratedSource {
+        let mut lines = vec![];
+        let mut line = 0;
+        let mut counted_until = 0;
+
+        for (start, span) in self.spans {
+            line += self.text[counted_until..start].matches('\n').count();
+            counted_until = start;
+            lines.push((line, span));
+        }
+
+        GeneratedSource {
+            text: self.text,
+            source_map: SourceMap { lines },
+        }
+    }
+}
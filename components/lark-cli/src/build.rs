@@ -1,6 +1,9 @@
+use crate::project::Project;
+use crate::ExitStatus;
 use flexi_logger::{opt_format, Logger};
 use language_reporting::{emit, Diagnostic, Label, Severity};
 use languageserver_types::Position;
+use lark_build::{BuildError, CodegenType};
 use lark_entity::{EntityData, ItemKind, MemberKind};
 use lark_intern::{Intern, Untern};
 use lark_language_server::{lsp_serve, LspResponder};
@@ -13,114 +16,252 @@ use lark_query_system::QuerySystem;
 use lark_span::{ByteIndex, FileName, IntoFileName, Span};
 use lark_task_manager::Actor;
 use salsa::Database;
+use serde_json::json;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::Read;
+use std::io::{Read, Write};
 use std::sync::Arc;
 use std::{env, io};
-use termcolor::{ColorChoice, StandardStream, WriteColor};
-
-pub fn build(file_name: &str, output_file_name: Option<&str>) {
-    let mut file = match File::open(file_name) {
-        Ok(f) => f,
-        Err(err) => {
-            eprintln!("failed to open `{}`: {}", file_name, err);
-            return;
-        }
+use termcolor::{ColorChoice, NoColor, StandardStream, WriteColor};
+
+pub fn build(
+    project: &Project,
+    output_file_name: Option<&str>,
+    codegen_type: CodegenType,
+    quiet: bool,
+    message_format: MessageFormat,
+) -> ExitStatus {
+    let db = match crate::load_project(project) {
+        Ok(db) => db,
+        Err(status) => return status,
+    };
+
+    let error_count = match crate::report_errors(&db, quiet, message_format) {
+        Ok(error_count) => error_count,
+        Err(cancelled) => return cancelled.into(),
     };
 
-    let mut contents = String::new();
-    match file.read_to_string(&mut contents) {
-        Ok(_bytes_read) => {}
-        Err(err) => {
-            eprintln!("failed to read `{}`: {}", file_name, err);
-            return;
+    if error_count > 0 {
+        if quiet {
+            println!("{}", error_count);
         }
+        return ExitStatus::Errors;
     }
 
-    let mut db = LarkDatabase::default();
+    if !crate::check_entry(&db, project) {
+        return ExitStatus::Errors;
+    }
 
-    let file_id: FileName = file_name.into_file_name(&db);
-    db.add_file(file_id, contents);
+    let out_file_name = match output_file_name {
+        Some(path) => path.to_string(),
+        None => project.name.clone(),
+    };
 
-    let writer = StandardStream::stderr(ColorChoice::Auto);
-    let error_count = db
-        .display_errors(&mut writer.lock())
-        .unwrap_or_else(|Cancelled| panic!("cancelled"));
+    // The program type-checked, so if the generated code does not
+    // build, that is a bug in Lark rather than in the program.
+    let build_result = if quiet {
+        db.build(
+            &out_file_name,
+            codegen_type,
+            &project.entry,
+            NoColor::new(io::sink()),
+            message_format,
+        )
+    } else {
+        let writer = crate::error_writer(message_format);
+        db.build(
+            &out_file_name,
+            codegen_type,
+            &project.entry,
+            &mut writer.lock(),
+            message_format,
+        )
+    };
 
-    if error_count == 0 {
-        let out_file_name = if let Some(path) = output_file_name {
-            path.to_string()
-        } else {
-            let file_path = if cfg!(windows) {
-                std::path::Path::new(file_name).with_extension("exe")
-            } else {
-                std::path::Path::new(file_name).with_extension("")
-            };
+    let error_count = match build_result {
+        Ok(error_count) => error_count,
+        Err(cancelled) => return cancelled.into(),
+    };
 
-            file_path.file_name().unwrap().to_str().unwrap().to_string()
-        };
+    if quiet {
+        println!("{}", error_count);
+    }
 
-        db.build(&out_file_name)
-            .unwrap_or_else(|Cancelled| panic!("cancelled"));
+    if error_count == 0 {
+        ExitStatus::Success
+    } else {
+        ExitStatus::InternalError
     }
 }
 
+/// How the CLI writes out errors.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MessageFormat {
+    /// Rendered for people to read, with the offending source code.
+    Human,
+
+    /// One JSON object per line, for tools to consume.
+    Json,
+}
+
 pub trait LarkDatabaseExt {
-    fn display_errors(&self, out: impl WriteColor) -> Result<usize, Cancelled>;
+    fn display_errors(
+        &self,
+        out: impl WriteColor,
+        message_format: MessageFormat,
+    ) -> Result<usize, Cancelled>;
 
-    /// Build an executable into `output_file_name`.
-    fn build(&self, output_file_name: &str) -> Result<(), Cancelled>;
+    /// Build an executable into `output_file_name`, using the backend
+    /// selected by `codegen_type`, that starts by calling the function
+    /// named `entry`. If the build fails, the errors are displayed on
+    /// `out`. Returns `Ok(n)` where n is the number of errors.
+    fn build(
+        &self,
+        output_file_name: &str,
+        codegen_type: CodegenType,
+        entry: &str,
+        out: impl WriteColor,
+        message_format: MessageFormat,
+    ) -> Result<usize, Cancelled>;
+
+    /// Writes a single error to `out`. `index` is the number of errors
+    /// that were written before this one.
+    fn write_error(
+        &self,
+        out: &mut impl WriteColor,
+        message_format: MessageFormat,
+        index: usize,
+        label: String,
+        span: Option<Span<FileName>>,
+    );
 }
 
 impl LarkDatabaseExt for LarkDatabase {
-    fn build(&self, output_file_name: &str) -> Result<(), Cancelled> {
-        let source_file = lark_build::codegen(self, lark_build::CodegenType::Rust);
+    fn build(
+        &self,
+        output_file_name: &str,
+        codegen_type: CodegenType,
+        entry: &str,
+        mut out: impl WriteColor,
+        message_format: MessageFormat,
+    ) -> Result<usize, Cancelled> {
+        let source_file = lark_build::codegen(self, codegen_type, entry);
 
-        lark_build::build(
-            &output_file_name,
-            &source_file.value,
-            lark_build::CodegenType::Rust,
-        )
-        .unwrap();
+        // If code generation failed, there is nothing worth building.
+        let diagnostics = if !source_file.errors.is_empty() {
+            source_file.errors
+        } else {
+            match lark_build::build(&output_file_name, &source_file.value, codegen_type) {
+                Ok(()) => return Ok(0),
+
+                Err(BuildError::Diagnostics(diagnostics)) => diagnostics,
+
+                Err(BuildError::Io(err)) => {
+                    let label = format!("failed to build generated code: {}", err);
+                    self.write_error(&mut out, message_format, 0, label, None);
+
+                    return Ok(1);
+                }
+            }
+        };
 
-        Ok(())
+        for (index, diagnostic) in diagnostics.iter().enumerate() {
+            self.write_error(
+                &mut out,
+                message_format,
+                index,
+                diagnostic.label.clone(),
+                Some(diagnostic.span),
+            );
+        }
+
+        Ok(diagnostics.len())
     }
 
     /// Displays all errors for the project on stderr. Returns `Ok(n)` where
     /// n is the number of errors (or `Cancelled` if execution is cancelled).
-    fn display_errors(&self, mut out: impl WriteColor) -> Result<usize, Cancelled> {
+    fn display_errors(
+        &self,
+        mut out: impl WriteColor,
+        message_format: MessageFormat,
+    ) -> Result<usize, Cancelled> {
         let db = self;
 
         let errors = db.errors_for_project()?;
-        let mut first = true;
         let mut error_count = 0;
 
         for (file_name, ranged_diagnostics) in errors {
             let file_id: FileName = file_name.into_file_name(&db);
 
             for ranged_diagnostic in ranged_diagnostics {
-                error_count += 1;
-                if !std::mem::replace(&mut first, false) {
-                    eprintln!("");
-                }
-
                 let range = ranged_diagnostic.range;
-                let error = Diagnostic::new(Severity::Error, ranged_diagnostic.label);
-
                 let span = Span::new(
                     file_id,
                     db.byte_index(file_id, range.start.line, range.start.character),
                     db.byte_index(file_id, range.end.line, range.end.character),
                 );
 
-                let error = error.with_label(Label::new_primary(span));
-
-                emit(&mut out, &db, &error, &language_reporting::DefaultConfig).unwrap();
+                db.write_error(
+                    &mut out,
+                    message_format,
+                    error_count,
+                    ranged_diagnostic.label,
+                    Some(span),
+                );
+                error_count += 1;
             }
         }
 
         Ok(error_count)
     }
+
+    fn write_error(
+        &self,
+        out: &mut impl WriteColor,
+        message_format: MessageFormat,
+        index: usize,
+        label: String,
+        span: Option<Span<FileName>>,
+    ) {
+        match message_format {
+            MessageFormat::Human => {
+                if index > 0 {
+                    writeln!(out).unwrap();
+                }
+
+                let mut error = Diagnostic::new(Severity::Error, label);
+                if let Some(span) = span {
+                    error = error.with_label(Label::new_primary(span));
+                }
+
+                emit(out, &self, &error, &language_reporting::DefaultConfig).unwrap();
+            }
+
+            MessageFormat::Json => {
+                // Lines and columns are zero-based, as in the language
+                // server protocol; byte offsets are from the start of the file.
+                let location = span.map(|span| {
+                    let range = self.range(span);
+                    json!({
+                        "file": span.file().untern(self).to_string(),
+                        "byte_start": span.start().to_usize(),
+                        "byte_end": span.end().to_usize(),
+                        "line_start": range.start.line,
+                        "column_start": range.start.character,
+                        "line_end": range.end.line,
+                        "column_end": range.end.character,
+                    })
+                });
+
+                let error = json!({
+                    "severity": "error",
+                    "label": label,
+                    "span": location,
+                });
+
+                writeln!(out, "{}", error).unwrap();
+            }
+        }
+    }
 }
@@ -0,0 +1,25 @@
+use crate::build::MessageFormat;
+use crate::project::Project;
+use crate::ExitStatus;
+
+pub fn check(project: &Project, quiet: bool, message_format: MessageFormat) -> ExitStatus {
+    let db = match crate::load_project(project) {
+        Ok(db) => db,
+        Err(status) => return status,
+    };
+
+    let error_count = match crate::report_errors(&db, quiet, message_format) {
+        Ok(error_count) => error_count,
+        Err(cancelled) => return cancelled.into(),
+    };
+
+    if quiet {
+        println!("{}", error_count);
+    }
+
+    if error_count == 0 {
+        ExitStatus::Success
+    } else {
+        ExitStatus::Errors
+    }
+}
@@ -14,11 +14,24 @@
 #![allow(dead_code)]
 #![allow(unused_imports)]
 
+use crate::build::{LarkDatabaseExt, MessageFormat};
+use crate::project::Project;
 use flexi_logger::{opt_format, Logger};
+use lark_build::CodegenType;
+use lark_entity::{EntityData, ItemKind};
+use lark_intern::{Intern, Untern};
+use lark_parser::{ParserDatabase, ParserDatabaseExt};
+use lark_query_system::ls_ops::{Cancelled, LsDatabase};
+use lark_query_system::LarkDatabase;
+use std::fs::File;
+use std::io::Read;
 use std::{env, io};
+use termcolor::{ColorChoice, StandardStream};
 
 pub mod build;
+mod check;
 mod ide;
+mod project;
 mod repl;
 mod run;
 
@@ -30,22 +43,279 @@ pub fn main() {
         .start()
         .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
 
-    let mut args = std::env::args();
+    let status = run_command(std::env::args().collect());
+    std::process::exit(status.code());
+}
+
+/// How a `lark` command finished. Each outcome has its own process
+/// exit code, so that scripts can tell them apart.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ExitStatus {
+    /// The command succeeded.
+    Success,
+
+    /// Errors were reported against the Lark program (or against the
+    /// manifest describing it).
+    Errors,
+
+    /// The command line could not be understood.
+    Usage,
+
+    /// Something went wrong that is not the fault of the Lark program:
+    /// an input file could not be read, the generated code failed to
+    /// compile, and so forth.
+    InternalError,
+
+    /// The queries were cancelled before they could finish.
+    Cancelled,
+}
+
+impl ExitStatus {
+    pub fn code(self) -> i32 {
+        match self {
+            ExitStatus::Success => 0,
+            ExitStatus::Errors => 1,
+            ExitStatus::Usage => 2,
+            ExitStatus::InternalError => 3,
+            ExitStatus::Cancelled => 4,
+        }
+    }
+}
+
+impl From<Cancelled> for ExitStatus {
+    fn from(_: Cancelled) -> Self {
+        ExitStatus::Cancelled
+    }
+}
+
+fn run_command(mut args: Vec<String>) -> ExitStatus {
+    let codegen_type = match take_backend_option(&mut args) {
+        Ok(codegen_type) => codegen_type,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitStatus::Usage;
+        }
+    };
+    let message_format = match take_message_format_option(&mut args) {
+        Ok(message_format) => message_format,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitStatus::Usage;
+        }
+    };
+    let quiet = take_flag(&mut args, "--quiet");
+    let mut args = args.into_iter();
 
     match (args.next(), args.next(), args.next(), args.next()) {
-        (_, Some(ref cmd), Some(ref x), Some(ref out)) if cmd == "build" => {
-            build::build(x, Some(out))
+        (_, Some(ref cmd), Some(ref x), Some(ref out)) if cmd == "build" => build::build(
+            &Project::single_file(x),
+            Some(out),
+            codegen_type,
+            quiet,
+            message_format,
+        ),
+        (_, Some(ref cmd), Some(ref x), None) if cmd == "build" => build::build(
+            &Project::single_file(x),
+            None,
+            codegen_type,
+            quiet,
+            message_format,
+        ),
+        (_, Some(ref cmd), Some(ref x), None) if cmd == "check" => {
+            check::check(&Project::single_file(x), quiet, message_format)
+        }
+        (_, Some(ref cmd), Some(ref x), None) if cmd == "run" => {
+            run::run(&Project::single_file(x), message_format)
+        }
+        (_, Some(ref cmd), None, None) if cmd == "build" || cmd == "check" || cmd == "run" => {
+            let project = match Project::in_current_dir() {
+                Some(Ok(project)) => project,
+                Some(Err(message)) => {
+                    eprintln!("{}", message);
+                    return ExitStatus::Errors;
+                }
+                None => {
+                    eprintln!(
+                        "no file given, and no `{}` in the current directory",
+                        project::MANIFEST_FILE_NAME
+                    );
+                    return ExitStatus::Usage;
+                }
+            };
+
+            match &cmd[..] {
+                "build" => build::build(&project, None, codegen_type, quiet, message_format),
+                "check" => check::check(&project, quiet, message_format),
+                _ => run::run(&project, message_format),
+            }
+        }
+        (_, Some(ref cmd), None, None) if cmd == "repl" => {
+            repl::repl();
+            ExitStatus::Success
+        }
+        (_, Some(ref cmd), None, None) if cmd == "ide" => {
+            ide::ide();
+            ExitStatus::Success
         }
-        (_, Some(ref cmd), Some(ref x), None) if cmd == "build" => build::build(x, None),
-        (_, Some(ref cmd), Some(ref x), None) if cmd == "run" => run::run(x),
-        (_, Some(ref cmd), None, None) if cmd == "repl" => repl::repl(),
-        (_, Some(ref cmd), None, None) if cmd == "ide" => ide::ide(),
         _ => {
             println!("Usage:");
             println!("  lark build <file> [<output>] - compiles the given file");
+            println!("    --backend <rust|c>         - selects the code generator (default: rust)");
+            println!("  lark check <file>            - reports errors in the given file");
             println!("  lark run <file>              - runs the given file");
             println!("  lark repl                    - REPL/interactive mode");
             println!("  lark ide                     - run the Lark languge server/IDE support");
+            println!();
+            println!("  Without a <file>, `build`, `check` and `run` work on the project");
+            println!("  described by the `Lark.toml` in the current directory.");
+            println!();
+            println!("  --quiet                      - with `build` or `check`, prints only");
+            println!("                                 the number of errors");
+            println!("  --message-format=<human|json> - with `build`, `check` or `run`, selects");
+            println!("                                 how errors are written; `json` writes");
+            println!("                                 one JSON object per error to stdout");
+            ExitStatus::Usage
+        }
+    }
+}
+
+/// Creates a database holding the contents of each file in `project`,
+/// reporting on stderr if any file cannot be read.
+fn load_project(project: &Project) -> Result<LarkDatabase, ExitStatus> {
+    let mut db = LarkDatabase::default();
+
+    for path in &project.files {
+        let file_name = path.to_string_lossy();
+
+        let mut file = match File::open(path) {
+            Ok(f) => f,
+            Err(err) => {
+                eprintln!("failed to open `{}`: {}", file_name, err);
+                return Err(ExitStatus::InternalError);
+            }
+        };
+
+        let mut contents = String::new();
+        match file.read_to_string(&mut contents) {
+            Ok(_bytes_read) => {}
+            Err(err) => {
+                eprintln!("failed to read `{}`: {}", file_name, err);
+                return Err(ExitStatus::InternalError);
+            }
         }
+
+        db.add_file(&file_name[..], contents);
+    }
+
+    Ok(db)
+}
+
+/// Checks that some file in the project defines the entry function,
+/// reporting on stderr if none does.
+fn check_entry(db: &LarkDatabase, project: &Project) -> bool {
+    let entry = project.entry.as_str().intern(db);
+
+    let found = db.file_names().iter().any(|&file| {
+        db.top_level_entities_in_file(file)
+            .iter()
+            .any(|entity| match entity.untern(db) {
+                EntityData::ItemName {
+                    kind: ItemKind::Function,
+                    id,
+                    ..
+                } => id == entry,
+                _ => false,
+            })
+    });
+
+    if !found {
+        eprintln!(
+            "no function named `{}` found to start the program at",
+            project.entry
+        );
+    }
+
+    found
+}
+
+/// Reports the errors in the project (or, if `quiet` is set, does not
+/// report them at all). Returns the number of errors.
+fn report_errors(
+    db: &LarkDatabase,
+    quiet: bool,
+    message_format: MessageFormat,
+) -> Result<usize, Cancelled> {
+    if quiet {
+        Ok(db
+            .errors_for_project()?
+            .values()
+            .map(|errors| errors.len())
+            .sum())
+    } else {
+        let writer = error_writer(message_format);
+        db.display_errors(&mut writer.lock(), message_format)
+    }
+}
+
+/// Where errors in the given format are written: people read them on
+/// stderr, while tools read JSON from stdout.
+fn error_writer(message_format: MessageFormat) -> StandardStream {
+    match message_format {
+        MessageFormat::Human => StandardStream::stderr(ColorChoice::Auto),
+        MessageFormat::Json => StandardStream::stdout(ColorChoice::Never),
+    }
+}
+
+/// Removes a `--message-format=<format>` option from `args`, returning
+/// the format it selects (`Human`, if there is no such option).
+fn take_message_format_option(args: &mut Vec<String>) -> Result<MessageFormat, String> {
+    const PREFIX: &str = "--message-format=";
+
+    let position = match args.iter().position(|arg| arg.starts_with(PREFIX)) {
+        Some(position) => position,
+        None => return Ok(MessageFormat::Human),
+    };
+
+    let option = args.remove(position);
+    match &option[PREFIX.len()..] {
+        "human" => Ok(MessageFormat::Human),
+        "json" => Ok(MessageFormat::Json),
+        name => Err(format!(
+            "unknown message format `{}`, expected `human` or `json`",
+            name
+        )),
+    }
+}
+
+/// Removes every occurrence of `flag` from `args`, returning true if
+/// there were any.
+fn take_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    let len = args.len();
+    args.retain(|arg| arg != flag);
+    args.len() != len
+}
+
+/// Removes a `--backend <name>` option from `args`, returning the code
+/// generator it selects (Rust, if there is no such option).
+fn take_backend_option(args: &mut Vec<String>) -> Result<CodegenType, String> {
+    let position = match args.iter().position(|arg| arg == "--backend") {
+        Some(position) => position,
+        None => return Ok(CodegenType::Rust),
+    };
+
+    if position + 1 >= args.len() {
+        return Err("`--backend` requires a backend name (`rust` or `c`)".to_string());
+    }
+
+    let name = args.remove(position + 1);
+    args.remove(position);
+
+    match &name[..] {
+        "rust" => Ok(CodegenType::Rust),
+        "c" => Ok(CodegenType::C),
+        _ => Err(format!(
+            "unknown backend `{}`, expected `rust` or `c`",
+            name
+        )),
     }
 }
@@ -0,0 +1,133 @@
+use serde_derive::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The name of the manifest that marks the root directory of a project.
+pub const MANIFEST_FILE_NAME: &str = "Lark.toml";
+
+/// The contents of a `Lark.toml` manifest, e.g.:
+///
+/// ```toml
+/// [project]
+/// name = "hello"
+/// sources = ["src", "tools/extra.lark"]
+/// entry = "main"
+/// ```
+#[derive(Deserialize)]
+struct Manifest {
+    project: ManifestProject,
+}
+
+#[derive(Deserialize)]
+struct ManifestProject {
+    name: String,
+
+    /// Source files, or directories that are searched (recursively)
+    /// for `.lark` files, relative to the manifest.
+    sources: Vec<String>,
+
+    /// The function that the program starts at (`main` by default).
+    entry: Option<String>,
+}
+
+/// The set of Lark files that make up a program.
+#[derive(Debug)]
+pub struct Project {
+    /// The name of the project, which is also the default name for
+    /// its executable.
+    pub name: String,
+
+    /// The source files of the project, in a stable order.
+    pub files: Vec<PathBuf>,
+
+    /// The name of the function that the program starts at.
+    pub entry: String,
+}
+
+impl Project {
+    /// Creates a project consisting of just `file`, starting at `main`.
+    pub fn single_file(file: &str) -> Project {
+        let path = Path::new(file);
+
+        let name = if cfg!(windows) {
+            path.with_extension("exe")
+        } else {
+            path.with_extension("")
+        };
+
+        Project {
+            name: name.file_name().unwrap().to_str().unwrap().to_string(),
+            files: vec![path.to_path_buf()],
+            entry: "main".to_string(),
+        }
+    }
+
+    /// Loads the project whose manifest is in the directory `root`.
+    pub fn load(root: &Path) -> Result<Project, String> {
+        let manifest_path = root.join(MANIFEST_FILE_NAME);
+
+        let contents = fs::read_to_string(&manifest_path)
+            .map_err(|err| format!("failed to read `{}`: {}", manifest_path.display(), err))?;
+
+        let manifest: Manifest = toml::from_str(&contents)
+            .map_err(|err| format!("failed to parse `{}`: {}", manifest_path.display(), err))?;
+
+        let mut files = vec![];
+        for source in &manifest.project.sources {
+            let path = root.join(source);
+            if path.is_dir() {
+                collect_lark_files(&path, &mut files)
+                    .map_err(|err| format!("failed to read `{}`: {}", path.display(), err))?;
+            } else if path.is_file() {
+                files.push(path);
+            } else {
+                return Err(format!(
+                    "source `{}` listed in `{}` does not exist",
+                    source,
+                    manifest_path.display()
+                ));
+            }
+        }
+
+        if files.is_empty() {
+            return Err(format!(
+                "no source files found for project `{}`",
+                manifest.project.name
+            ));
+        }
+
+        Ok(Project {
+            name: manifest.project.name,
+            files,
+            entry: manifest.project.entry.unwrap_or_else(|| "main".to_string()),
+        })
+    }
+
+    /// Loads the project in the current directory, if it has a manifest.
+    pub fn in_current_dir() -> Option<Result<Project, String>> {
+        let root = Path::new("");
+        if root.join(MANIFEST_FILE_NAME).is_file() {
+            Some(Project::load(root))
+        } else {
+            None
+        }
+    }
+}
+
+/// Pushes every `.lark` file within `dir` onto `files`, sorted by path.
+fn collect_lark_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            collect_lark_files(&path, files)?;
+        } else if path.extension().map_or(false, |ext| ext == "lark") {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
@@ -106,8 +106,10 @@ pub fn repl() {
             let mut num_to_skip_remaining = num_to_skip;
             let mut num_to_skip_next = 0;
             let mut output = Value::Void;
+            let mut runtime_error = None;
 
-            for basic_block in fn_bytecode.basic_blocks.iter(&fn_bytecode) {
+            let mut next_block = Some(fn_bytecode.entry_block());
+            'execute: while let Some(basic_block) = next_block {
                 let basic_block_data = &fn_bytecode.tables[basic_block];
 
                 for statement in basic_block_data.statements.iter(&fn_bytecode) {
@@ -127,17 +129,39 @@ pub fn repl() {
                             }
                             _ => {
                                 num_to_skip_next += 1;
-                                output = lark_eval::eval_statement(
+                                match lark_eval::eval_statement(
                                     &mut db,
                                     &fn_bytecode,
                                     statement,
                                     &mut variables,
                                     &mut io_handler,
-                                );
+                                ) {
+                                    Ok(value) => output = value,
+                                    Err(error) => {
+                                        runtime_error = Some(error);
+                                        break 'execute;
+                                    }
+                                }
                             }
                         }
                     }
                 }
+
+                match lark_eval::eval_terminator(&mut db, &fn_bytecode, basic_block, &mut variables)
+                {
+                    Ok(block) => next_block = block,
+                    Err(error) => {
+                        runtime_error = Some(error);
+                        break 'execute;
+                    }
+                }
+            }
+
+            if let Some(error) = runtime_error {
+                // The last command failed while running, so don't keep it around
+                println!("error: {}", error.label);
+                fn_body.pop();
+                continue;
             }
 
             num_to_skip += num_to_skip_next;
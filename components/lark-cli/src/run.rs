@@ -1,28 +1,39 @@
-use lark_parser::ParserDatabaseExt;
-use lark_query_system::LarkDatabase;
-use std::fs::File;
-use std::io::Read;
+use crate::build::{LarkDatabaseExt, MessageFormat};
+use crate::project::Project;
+use crate::ExitStatus;
 
-pub fn run(filename: &str) {
-    let mut file = match File::open(filename) {
-        Ok(f) => f,
-        Err(err) => {
-            eprintln!("failed to open `{}`: {}", filename, err);
-            return;
-        }
+pub fn run(project: &Project, message_format: MessageFormat) -> ExitStatus {
+    let mut db = match crate::load_project(project) {
+        Ok(db) => db,
+        Err(status) => return status,
     };
 
-    let mut contents = String::new();
-    match file.read_to_string(&mut contents) {
-        Ok(_bytes_read) => {}
-        Err(err) => {
-            eprintln!("failed to read `{}`: {}", filename, err);
-            return;
-        }
+    match crate::report_errors(&db, false, message_format) {
+        Ok(0) => {}
+        Ok(_) => return ExitStatus::Errors,
+        Err(cancelled) => return cancelled.into(),
     }
 
-    let mut db = LarkDatabase::default();
-    let _ = db.add_file(filename, contents.to_string());
+    if !crate::check_entry(&db, project) {
+        return ExitStatus::Errors;
+    }
+
+    if let Err(error) = lark_eval::eval_entry(
+        &mut db,
+        &project.entry,
+        &mut lark_eval::IOHandler::new(false),
+    ) {
+        let writer = crate::error_writer(message_format);
+        db.write_error(
+            &mut writer.lock(),
+            message_format,
+            0,
+            error.label,
+            Some(error.span),
+        );
+
+        return ExitStatus::Errors;
+    }
 
-    lark_eval::eval(&mut db, &mut lark_eval::IOHandler::new(false));
+    ExitStatus::Success
 }
@@ -2,10 +2,10 @@
 #![feature(const_fn)]
 #![feature(const_let)]
 
-use lark_intern::{Intern, Untern};
 use lark_debug_derive::DebugWith;
 use lark_debug_with::{DebugWith, FmtWithSpecialized};
 use lark_error::{ErrorReported, ErrorSentinel};
+use lark_intern::{Intern, Untern};
 use lark_span::FileName;
 use lark_string::{GlobalIdentifier, GlobalIdentifierTables};
 
@@ -70,16 +70,37 @@ impl EntityData {
                 kind: ItemKind::Struct,
                 ..
             }
+            | EntityData::ItemName {
+                kind: ItemKind::Enum,
+                ..
+            }
+            | EntityData::ItemName {
+                kind: ItemKind::Module,
+                ..
+            }
+            | EntityData::ItemName {
+                kind: ItemKind::Use,
+                ..
+            }
+            | EntityData::MemberName {
+                kind: MemberKind::Variant,
+                ..
+            }
             | EntityData::LangItem(LangItem::Int)
             | EntityData::LangItem(LangItem::Tuple(_))
             | EntityData::LangItem(LangItem::String)
             | EntityData::LangItem(LangItem::Uint)
-            | EntityData::LangItem(LangItem::Boolean) => false,
+            | EntityData::LangItem(LangItem::Boolean)
+            | EntityData::LangItem(LangItem::List) => false,
 
             EntityData::ItemName {
                 kind: ItemKind::Function,
                 ..
             }
+            | EntityData::ItemName {
+                kind: ItemKind::Const,
+                ..
+            }
             | EntityData::MemberName {
                 kind: MemberKind::Method,
                 ..
@@ -91,6 +112,11 @@ impl EntityData {
             | EntityData::LangItem(LangItem::True)
             | EntityData::LangItem(LangItem::False)
             | EntityData::LangItem(LangItem::Debug)
+            | EntityData::LangItem(LangItem::ListLen)
+            | EntityData::LangItem(LangItem::ListPush)
+            | EntityData::LangItem(LangItem::StringLen)
+            | EntityData::LangItem(LangItem::StringSubstring)
+            | EntityData::LangItem(LangItem::Format)
             | EntityData::Error(_) => true,
         }
     }
@@ -110,18 +136,54 @@ pub enum LangItem {
     True,
     False,
     Debug,
+
+    /// `List<T>`, a growable list of values
+    List,
+
+    /// The `len` method of `List`
+    ListLen,
+
+    /// The `push` method of `List`
+    ListPush,
+
+    /// The `len` method of `String`
+    StringLen,
+
+    /// The `substring` method of `String`
+    StringSubstring,
+
+    /// `format(...)`, which builds a `String` from the text of its
+    /// arguments
+    Format,
 }
 
 #[derive(Copy, Clone, Debug, DebugWith, PartialEq, Eq, Hash)]
 pub enum ItemKind {
     Struct,
     Function,
+
+    /// An `enum` declaration, whose children are its variants.
+    Enum,
+
+    /// A `mod` declaration, whose children are the items within it.
+    Module,
+
+    /// A `use` declaration, named after the item that it imports.
+    Use,
+
+    /// A `const` declaration, whose value is computed at compile
+    /// time.
+    Const,
 }
 
 #[derive(Copy, Clone, Debug, DebugWith, PartialEq, Eq, Hash)]
 pub enum MemberKind {
     Field,
     Method,
+
+    /// A variant of an enum. The fields of its payload (if any) are
+    /// in turn members of the variant.
+    Variant,
 }
 
 lark_intern::intern_tables! {
@@ -158,6 +220,21 @@ impl Entity {
             }
         }
     }
+
+    /// True if `self` is `ancestor` or is (transitively) nested
+    /// within it.
+    pub fn is_within(self, db: &dyn AsRef<EntityTables>, ancestor: Entity) -> bool {
+        if self == ancestor {
+            return true;
+        }
+
+        match self.untern(db) {
+            EntityData::ItemName { base, .. } | EntityData::MemberName { base, .. } => {
+                base.is_within(db, ancestor)
+            }
+            EntityData::LangItem(_) | EntityData::InputFile { .. } | EntityData::Error(_) => false,
+        }
+    }
 }
 
 impl<DB> ErrorSentinel<&DB> for Entity
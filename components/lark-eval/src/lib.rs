@@ -1,25 +1,68 @@
 use lark_debug_with::DebugWith;
-use lark_entity::{EntityData, ItemKind, LangItem};
+use lark_entity::{Entity, EntityData, ItemKind, LangItem, MemberKind};
+use lark_error::{Diagnostic, WithError};
 use lark_intern::{Intern, Untern};
+use lark_mir::ops;
 use lark_mir::{
-    BasicBlock, FnBytecode, IdentifierData, MirDatabase, Operand, OperandData, Place, PlaceData,
-    Rvalue, RvalueData, Statement, StatementKind, Variable,
+    BasicBlock, BinOp, FnBytecode, IdentifierData, List, MirDatabase, Operand, OperandData, Place,
+    PlaceData, Rvalue, RvalueData, Statement, StatementKind, Terminator, UnOp, Variable,
 };
 use lark_parser::{ParserDatabase, ParserDatabaseExt};
 use lark_query_system::LarkDatabase;
+use lark_string::Text;
 use std::collections::HashMap;
 use std::fmt;
+use std::sync::Arc;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Value {
     Void,
     Bool(bool),
     I32(i32),
+    U32(u32),
     Str(String),
-    Struct(HashMap<lark_string::GlobalIdentifier, Value>),
+    Struct {
+        /// The name of the struct, as it is shown
+        name: String,
+        fields: Fields,
+    },
+    Tuple(Vec<Value>),
+    List(Vec<Value>),
+    Variant {
+        variant: Entity,
+        /// The name of the variant, as it is shown (e.g., `Level::Error`)
+        name: String,
+        fields: Fields,
+    },
     Reference(usize), // a reference into the value stack
 }
 
+/// The fields of a struct or variant, by name, in the order in which
+/// they are declared (which is the order in which they are shown).
+pub type Fields = Vec<(Text, Value)>;
+
+fn field<'f>(fields: &'f [(Text, Value)], name: &Text) -> &'f Value {
+    &fields.iter().find(|(field, _)| field == name).unwrap().1
+}
+
+fn field_mut<'f>(fields: &'f mut Fields, name: &Text) -> &'f mut Value {
+    &mut fields
+        .iter_mut()
+        .find(|(field, _)| field == name)
+        .unwrap()
+        .1
+}
+
+/// Shows a struct or variant the way it is constructed:
+/// `name(field: value, ...)`.
+fn display_fields(name: &str, fields: &[(Text, Value)]) -> String {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|(field, value)| format!("{}: {}", field, value))
+        .collect();
+    format!("{}({})", name, fields.join(", "))
+}
+
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -27,11 +70,24 @@ impl fmt::Display for Value {
             "{}",
             match self {
                 Value::I32(i) => i.to_string(),
+                Value::U32(u) => u.to_string(),
                 Value::Str(s) => s.clone(),
                 Value::Bool(b) => b.to_string(),
                 Value::Reference(r) => format!("reference to {}", r),
-                Value::Void => "<void>".into(),
-                Value::Struct(s) => format!("{:?}", s),
+                Value::Void => "()".into(),
+                Value::Struct { name, fields } => display_fields(name, fields),
+                Value::Tuple(elements) => {
+                    let elements: Vec<String> = elements.iter().map(|e| e.to_string()).collect();
+                    format!("({})", elements.join(", "))
+                }
+                Value::List(elements) => {
+                    let elements: Vec<String> = elements.iter().map(|e| e.to_string()).collect();
+                    format!("[{}]", elements.join(", "))
+                }
+                // Like patterns, variants without fields are shown
+                // without parentheses
+                Value::Variant { name, fields, .. } if fields.is_empty() => name.clone(),
+                Value::Variant { name, fields, .. } => display_fields(name, fields),
             }
         )
     }
@@ -67,10 +123,10 @@ pub fn eval_place(
     fn_bytecode: &FnBytecode,
     place: Place,
     variables: &mut HashMap<Variable, Vec<Value>>,
-) -> Value {
+) -> Result<Value, Diagnostic> {
     let place_data = &fn_bytecode.tables[place];
 
-    match place_data {
+    let value = match place_data {
         PlaceData::Entity(entity) => match entity.untern(db) {
             EntityData::LangItem(LangItem::True) => Value::Bool(true),
             EntityData::LangItem(LangItem::False) => Value::Bool(false),
@@ -81,30 +137,238 @@ pub fn eval_place(
             stack.last().unwrap().clone()
         }
         PlaceData::Field { owner, name } => {
-            let target = eval_place(db, fn_bytecode, *owner, variables);
+            let target = eval_place(db, fn_bytecode, *owner, variables)?;
             match target {
-                Value::Struct(s) => match fn_bytecode.tables[*name] {
-                    IdentifierData { text } => s.get(&text).unwrap().clone(),
+                Value::Struct { fields, .. } => match fn_bytecode.tables[*name] {
+                    IdentifierData { text } => field(&fields, &text.untern(db)).clone(),
+                },
+                // The fields of a tuple are named by the index of each
+                // element
+                Value::Tuple(elements) => match fn_bytecode.tables[*name] {
+                    IdentifierData { text } => {
+                        let index: usize = text.untern(db).parse().unwrap();
+                        elements[index].clone()
+                    }
                 },
                 _ => panic!("Member access (.) into value that is not a struct"),
             }
         }
+        PlaceData::VariantField {
+            owner,
+            variant,
+            name,
+        } => {
+            let target = eval_place(db, fn_bytecode, *owner, variables)?;
+            match target {
+                Value::Variant {
+                    variant: actual_variant,
+                    fields,
+                    ..
+                } => {
+                    assert_eq!(*variant, actual_variant, "field of the wrong variant");
+                    match fn_bytecode.tables[*name] {
+                        IdentifierData { text } => field(&fields, &text.untern(db)).clone(),
+                    }
+                }
+                _ => panic!("Variant field access into value that is not a variant"),
+            }
+        }
+        PlaceData::Index { owner, index } => {
+            let target = eval_place(db, fn_bytecode, *owner, variables)?;
+            let index = eval_index(db, fn_bytecode, *index, variables)?;
+            match target {
+                Value::List(elements) => match elements.get(index as usize) {
+                    Some(element) => element.clone(),
+                    None => {
+                        return Err(index_out_of_bounds(
+                            fn_bytecode,
+                            place,
+                            elements.len(),
+                            index,
+                        ));
+                    }
+                },
+                _ => panic!("Indexing ([]) into value that is not a list"),
+            }
+        }
+        PlaceData::Error(_) => panic!("Cannot evaluate a place that failed to lower"),
+    };
+
+    Ok(value)
+}
+
+/// Like `eval_place`, but returns the value stored in `place` itself,
+/// so that it can be modified (e.g., by `push`).
+pub fn eval_place_mut<'v>(
+    db: &LarkDatabase,
+    fn_bytecode: &FnBytecode,
+    place: Place,
+    variables: &'v mut HashMap<Variable, Vec<Value>>,
+) -> Result<&'v mut Value, Diagnostic> {
+    let place_data = &fn_bytecode.tables[place];
+
+    let value = match place_data {
+        PlaceData::Variable(variable) => {
+            let stack = variables.get_mut(variable).unwrap();
+            stack.last_mut().unwrap()
+        }
+        PlaceData::Field { owner, name } => {
+            let IdentifierData { text } = fn_bytecode.tables[*name];
+            match eval_place_mut(db, fn_bytecode, *owner, variables)? {
+                Value::Struct { fields, .. } => field_mut(fields, &text.untern(db)),
+                Value::Tuple(elements) => {
+                    let index: usize = text.untern(db).parse().unwrap();
+                    &mut elements[index]
+                }
+                _ => panic!("Member access (.) into value that is not a struct"),
+            }
+        }
+        PlaceData::VariantField { owner, name, .. } => {
+            let IdentifierData { text } = fn_bytecode.tables[*name];
+            match eval_place_mut(db, fn_bytecode, *owner, variables)? {
+                Value::Variant { fields, .. } => field_mut(fields, &text.untern(db)),
+                _ => panic!("Variant field access into value that is not a variant"),
+            }
+        }
+        PlaceData::Index { owner, index } => {
+            // The index may read other variables, so evaluate it
+            // before borrowing the list.
+            let index = eval_index(db, fn_bytecode, *index, variables)?;
+            match eval_place_mut(db, fn_bytecode, *owner, variables)? {
+                Value::List(elements) => {
+                    let len = elements.len();
+                    match elements.get_mut(index as usize) {
+                        Some(element) => element,
+                        None => return Err(index_out_of_bounds(fn_bytecode, place, len, index)),
+                    }
+                }
+                _ => panic!("Indexing ([]) into value that is not a list"),
+            }
+        }
+        PlaceData::Entity(_) => panic!("Cannot modify an entity"),
+        PlaceData::Error(_) => panic!("Cannot evaluate a place that failed to lower"),
+    };
+
+    Ok(value)
+}
+
+/// Evaluates the index into a list.
+fn eval_index(
+    db: &LarkDatabase,
+    fn_bytecode: &FnBytecode,
+    index: Operand,
+    variables: &mut HashMap<Variable, Vec<Value>>,
+) -> Result<u32, Diagnostic> {
+    match eval_operand(db, fn_bytecode, index, variables)? {
+        Value::U32(index) => Ok(index),
+        x => panic!("Index into a list is not a `uint`: {}", x),
     }
 }
 
+/// The runtime error for accessing the element `index` of a list
+/// (stored in `place`) with only `len` elements.
+fn index_out_of_bounds(
+    fn_bytecode: &FnBytecode,
+    place: Place,
+    len: usize,
+    index: u32,
+) -> Diagnostic {
+    Diagnostic::new(
+        format!(
+            "index out of bounds: the length is {} but the index is {}",
+            len, index
+        ),
+        fn_bytecode.tables.span(place),
+    )
+}
+
 pub fn eval_operand(
     db: &LarkDatabase,
     fn_bytecode: &FnBytecode,
     operand: Operand,
     variables: &mut HashMap<Variable, Vec<Value>>,
-) -> Value {
+) -> Result<Value, Diagnostic> {
     let operand_data = &fn_bytecode.tables[operand];
 
     match operand_data {
         OperandData::Copy(place) | OperandData::Move(place) => {
             eval_place(db, fn_bytecode, *place, variables)
         }
-        _ => unimplemented!("Operand not yet supported"),
+        OperandData::ConstantInt(i) => Ok(Value::I32(*i)),
+        OperandData::ConstantUint(u) => Ok(Value::U32(*u)),
+        OperandData::ConstantString(s) => Ok(Value::Str(s.clone())),
+        OperandData::Error(_) => panic!("Cannot evaluate an operand that failed to lower"),
+    }
+}
+
+/// Evaluates each of `operands` in order.
+fn eval_operands(
+    db: &LarkDatabase,
+    fn_bytecode: &FnBytecode,
+    operands: List<Operand>,
+    variables: &mut HashMap<Variable, Vec<Value>>,
+) -> Result<Vec<Value>, Diagnostic> {
+    operands
+        .iter(fn_bytecode)
+        .map(|operand| eval_operand(db, fn_bytecode, operand, variables))
+        .collect()
+}
+
+/// Applies a binary operator to two values. If the operation fails at
+/// runtime (division by zero, overflow), returns a message describing
+/// the failure.
+pub fn eval_bin_op(op: BinOp, left: Value, right: Value) -> Result<Value, String> {
+    match op {
+        BinOp::Eq => Ok(Value::Bool(left == right)),
+        BinOp::Ne => Ok(Value::Bool(left != right)),
+        BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge => match (&left, &right) {
+            (Value::I32(left), Value::I32(right)) => Ok(Value::Bool(ops::compare(op, left, right))),
+            (Value::U32(left), Value::U32(right)) => Ok(Value::Bool(ops::compare(op, left, right))),
+            _ => panic!(
+                "Comparison applied to incompatible values: {} and {}",
+                left, right
+            ),
+        },
+        BinOp::Concat => match (left, right) {
+            (Value::Str(left), Value::Str(right)) => Ok(Value::Str(left + &right)),
+            (left, right) => panic!(
+                "Concatenation applied to non-string values: {} and {}",
+                left, right
+            ),
+        },
+        BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div => match (left, right) {
+            (Value::I32(left), Value::I32(right)) => {
+                ops::checked_arithmetic(op, left, right).map(Value::I32)
+            }
+            (Value::U32(left), Value::U32(right)) => {
+                ops::checked_arithmetic(op, left, right).map(Value::U32)
+            }
+            (left, right) => panic!(
+                "Binary operator applied to incompatible values: {} and {}",
+                left, right
+            ),
+        },
+    }
+}
+
+/// Applies a unary operator to a value. Like `eval_bin_op`, returns a
+/// message describing the failure if the operation overflows.
+pub fn eval_un_op(op: UnOp, value: Value) -> Result<Value, String> {
+    match (op, value) {
+        (UnOp::Not, Value::Bool(b)) => Ok(Value::Bool(!b)),
+        (UnOp::Not, value) => panic!("Not operator applied to non-boolean value: {}", value),
+        (UnOp::Neg, Value::I32(i)) => ops::checked_negate(i).map(Value::I32),
+        (UnOp::Neg, value) => panic!("Negation applied to non-integer value: {}", value),
+    }
+}
+
+/// Fetches the bytecode for `entity`. If the function could not be
+/// lowered, we cannot run it, so return the first lowering error instead.
+fn fn_bytecode_or_error(db: &LarkDatabase, entity: Entity) -> Result<Arc<FnBytecode>, Diagnostic> {
+    let WithError { value, errors } = db.fn_bytecode(entity);
+    match errors.into_iter().next() {
+        Some(error) => Err(error),
+        None => Ok(value),
     }
 }
 
@@ -114,35 +378,126 @@ pub fn eval_rvalue(
     rvalue: Rvalue,
     variables: &mut HashMap<Variable, Vec<Value>>,
     io_handler: &mut IOHandler,
-) -> Value {
+) -> Result<Value, Diagnostic> {
     let rvalue_data = &fn_bytecode.tables[rvalue];
 
-    match rvalue_data {
-        RvalueData::Use(operand) => eval_operand(db, fn_bytecode, *operand, variables),
+    let value = match rvalue_data {
+        RvalueData::Use(operand) => eval_operand(db, fn_bytecode, *operand, variables)?,
+        RvalueData::BinaryOp(op, left, right) => {
+            let left = eval_operand(db, fn_bytecode, *left, variables)?;
+            let right = eval_operand(db, fn_bytecode, *right, variables)?;
+            match eval_bin_op(*op, left, right) {
+                Ok(value) => value,
+                Err(message) => {
+                    return Err(Diagnostic::new(message, fn_bytecode.tables.span(rvalue)));
+                }
+            }
+        }
+        RvalueData::UnaryOp(op, operand) => {
+            let value = eval_operand(db, fn_bytecode, *operand, variables)?;
+            match eval_un_op(*op, value) {
+                Ok(value) => value,
+                Err(message) => {
+                    return Err(Diagnostic::new(message, fn_bytecode.tables.span(rvalue)));
+                }
+            }
+        }
         RvalueData::Call(entity, operands) => match entity.untern(db) {
             EntityData::LangItem(LangItem::Debug) => {
                 for operand in operands.iter(fn_bytecode) {
-                    let result = eval_operand(db, fn_bytecode, operand, variables);
+                    let result = eval_operand(db, fn_bytecode, operand, variables)?;
                     io_handler.println(format!("{}", result));
                 }
 
                 Value::Void
             }
-            EntityData::ItemName { .. } => {
-                let bytecode = db.fn_bytecode(*entity).value;
+            EntityData::LangItem(LangItem::Format) => {
+                let values = eval_operands(db, fn_bytecode, *operands, variables)?;
+                let text: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+                Value::Str(text.concat())
+            }
+            EntityData::LangItem(LangItem::StringLen) => {
+                let string = operands.iter(fn_bytecode).next().unwrap();
+                match eval_operand(db, fn_bytecode, string, variables)? {
+                    Value::Str(s) => Value::U32(s.chars().count() as u32),
+                    x => panic!("`len` called on a value that is not a string: {}", x),
+                }
+            }
+            EntityData::LangItem(LangItem::StringSubstring) => {
+                let mut values = eval_operands(db, fn_bytecode, *operands, variables)?.into_iter();
+                let (string, start, end) = match (values.next(), values.next(), values.next()) {
+                    (Some(Value::Str(s)), Some(Value::U32(start)), Some(Value::U32(end))) => {
+                        (s, start, end)
+                    }
+                    x => panic!("`substring` called with unexpected arguments: {:?}", x),
+                };
+
+                let len = string.chars().count() as u32;
+                if start > end || end > len {
+                    return Err(Diagnostic::new(
+                        format!(
+                            "substring out of bounds: the length is {} but the range is {}..{}",
+                            len, start, end
+                        ),
+                        fn_bytecode.tables.span(rvalue),
+                    ));
+                }
+
+                Value::Str(
+                    string
+                        .chars()
+                        .skip(start as usize)
+                        .take((end - start) as usize)
+                        .collect(),
+                )
+            }
+            EntityData::LangItem(LangItem::ListLen) => {
+                let list = operands.iter(fn_bytecode).next().unwrap();
+                match eval_operand(db, fn_bytecode, list, variables)? {
+                    Value::List(elements) => Value::U32(elements.len() as u32),
+                    x => panic!("`len` called on a value that is not a list: {}", x),
+                }
+            }
+            EntityData::LangItem(LangItem::ListPush) => {
+                let mut operands = operands.iter(fn_bytecode);
+                let list = operands.next().unwrap();
+                let value = eval_operand(db, fn_bytecode, operands.next().unwrap(), variables)?;
+
+                // `push` modifies the list where it is stored.
+                let place = match fn_bytecode.tables[list] {
+                    OperandData::Copy(place) | OperandData::Move(place) => place,
+                    _ => panic!("`push` called on a list that is not stored in a place"),
+                };
+                match eval_place_mut(db, fn_bytecode, place, variables)? {
+                    Value::List(elements) => elements.push(value),
+                    x => panic!("`push` called on a value that is not a list: {}", x),
+                }
 
-                for (arg, param) in operands
-                    .iter(fn_bytecode)
+                Value::Void
+            }
+            EntityData::ItemName { .. }
+            | EntityData::MemberName {
+                kind: MemberKind::Method,
+                ..
+            } => {
+                let bytecode = fn_bytecode_or_error(db, *entity)?;
+
+                // Evaluate all the arguments before binding any of the
+                // parameters, since the parameters may shadow variables
+                // that the later arguments refer to.
+                let arg_values = eval_operands(db, fn_bytecode, *operands, variables)?;
+
+                for (arg_value, param) in arg_values
+                    .into_iter()
                     .zip(bytecode.arguments.iter(&bytecode))
                 {
-                    let arg_value = eval_operand(db, fn_bytecode, arg, variables);
                     create_variable(variables, param);
                     assign_to_variable(variables, param, arg_value);
                 }
 
-                let return_value = eval_function(db, &bytecode, variables, io_handler);
+                let return_value = eval_function(db, &bytecode, variables, io_handler)?;
 
-                for argument in fn_bytecode.arguments.iter(&fn_bytecode) {
+                for argument in bytecode.arguments.iter(&bytecode) {
                     pop_variable(variables, argument);
                 }
 
@@ -154,17 +509,61 @@ pub fn eval_rvalue(
             ),
         },
         RvalueData::Aggregate(entity, args) => {
+            match entity.untern(db) {
+                EntityData::LangItem(LangItem::Tuple(_)) => {
+                    return Ok(Value::Tuple(eval_operands(
+                        db,
+                        fn_bytecode,
+                        *args,
+                        variables,
+                    )?));
+                }
+                EntityData::LangItem(LangItem::List) => {
+                    return Ok(Value::List(eval_operands(
+                        db,
+                        fn_bytecode,
+                        *args,
+                        variables,
+                    )?));
+                }
+                _ => {}
+            }
+
             let members = db.members(*entity).unwrap();
-            let mut result_struct = HashMap::new();
+            let mut fields = vec![];
 
-            for (member, arg) in members.iter().zip(args.iter(fn_bytecode)) {
-                let arg_result = eval_operand(db, fn_bytecode, arg, variables);
-                result_struct.insert(member.name, arg_result);
+            let members = members.iter().filter(|m| m.kind == MemberKind::Field);
+            for (member, arg) in members.zip(args.iter(fn_bytecode)) {
+                let arg_result = eval_operand(db, fn_bytecode, arg, variables)?;
+                fields.push((member.name.untern(db), arg_result));
             }
 
-            Value::Struct(result_struct)
+            match entity.untern(db) {
+                EntityData::MemberName {
+                    kind: MemberKind::Variant,
+                    base,
+                    id,
+                } => Value::Variant {
+                    variant: *entity,
+                    name: format!("{}::{}", item_name(db, base), id.untern(db)),
+                    fields,
+                },
+                _ => Value::Struct {
+                    name: item_name(db, *entity),
+                    fields,
+                },
+            }
         }
-        x => unimplemented!("Rvalue not yet supported in eval: {:#?}", x.debug_with(db)),
+    };
+
+    Ok(value)
+}
+
+/// The name of the struct or enum `entity`, as written in the program.
+fn item_name(db: &LarkDatabase, entity: Entity) -> String {
+    match entity.untern(db) {
+        EntityData::ItemName { id, .. } => id.untern(db).to_string(),
+        x => panic!("not a struct or enum: {:#?}", x.debug_with(db)),
     }
 }
 
@@ -193,7 +592,7 @@ pub fn eval_statement(
     statement: Statement,
     variables: &mut HashMap<Variable, Vec<Value>>,
     io_handler: &mut IOHandler,
-) -> Value {
+) -> Result<Value, Diagnostic> {
     let statement_data = &fn_bytecode.tables[statement];
 
     match &statement_data.kind {
@@ -201,12 +600,12 @@ pub fn eval_statement(
             eval_rvalue(db, fn_bytecode, *rvalue, variables, io_handler)
         }
         StatementKind::Assign(place, rvalue) => {
-            let rhs = eval_rvalue(db, fn_bytecode, *rvalue, variables, io_handler);
+            let rhs = eval_rvalue(db, fn_bytecode, *rvalue, variables, io_handler)?;
             match &fn_bytecode.tables[*place] {
                 PlaceData::Variable(variable) => assign_to_variable(variables, *variable, rhs),
                 _ => unimplemented!("PlaceData not yet supported in eval"),
             }
-            Value::Void
+            Ok(Value::Void)
         }
         _ => {
             // If we get here, something went wrong. Statements like StorageLive/StorageDead
@@ -216,15 +615,17 @@ pub fn eval_statement(
     }
 }
 
+/// Evaluates the statements in `basic_block`, returning the value of
+/// the last one (if any).
 pub fn eval_basic_block(
     db: &LarkDatabase,
     fn_bytecode: &FnBytecode,
     basic_block: BasicBlock,
     variables: &mut HashMap<Variable, Vec<Value>>,
     io_handler: &mut IOHandler,
-) -> Value {
+) -> Result<Option<Value>, Diagnostic> {
     let basic_block_data = &fn_bytecode.tables[basic_block];
-    let mut return_value = Value::Void;
+    let mut return_value = None;
 
     for statement in basic_block_data.statements.iter(&fn_bytecode) {
         let statement_data = &fn_bytecode.tables[statement];
@@ -236,11 +637,57 @@ pub fn eval_basic_block(
             StatementKind::StorageDead(variable) => {
                 pop_variable(variables, *variable);
             }
-            _ => return_value = eval_statement(db, fn_bytecode, statement, variables, io_handler),
+            _ => {
+                return_value = Some(eval_statement(
+                    db,
+                    fn_bytecode,
+                    statement,
+                    variables,
+                    io_handler,
+                )?)
+            }
         }
     }
 
-    return_value
+    Ok(return_value)
+}
+
+/// Evaluates the terminator of `basic_block`, returning the block to
+/// continue with, or `None` if the function returns.
+pub fn eval_terminator(
+    db: &LarkDatabase,
+    fn_bytecode: &FnBytecode,
+    basic_block: BasicBlock,
+    variables: &mut HashMap<Variable, Vec<Value>>,
+) -> Result<Option<BasicBlock>, Diagnostic> {
+    let next_block = match &fn_bytecode.tables[basic_block].terminator {
+        Terminator::Return => None,
+        Terminator::Goto(target) => Some(*target),
+        Terminator::If {
+            condition,
+            if_true,
+            if_false,
+        } => match eval_operand(db, fn_bytecode, *condition, variables)? {
+            Value::Bool(true) => Some(*if_true),
+            Value::Bool(false) => Some(*if_false),
+            x => panic!("Condition of `if` is not a boolean: {}", x),
+        },
+        Terminator::Switch {
+            discriminant,
+            targets,
+        } => match eval_operand(db, fn_bytecode, *discriminant, variables)? {
+            Value::Variant { variant, .. } => {
+                let &(_, target) = targets
+                    .iter()
+                    .find(|&&(target_variant, _)| target_variant == variant)
+                    .expect("no target for variant in `match`");
+                Some(target)
+            }
+            x => panic!("Discriminant of `match` is not a variant: {}", x),
+        },
+    };
+
+    Ok(next_block)
 }
 
 pub fn eval_function(
@@ -248,21 +695,39 @@ pub fn eval_function(
     fn_bytecode: &FnBytecode,
     variables: &mut HashMap<Variable, Vec<Value>>,
     io_handler: &mut IOHandler,
-) -> Value {
+) -> Result<Value, Diagnostic> {
     let mut return_value = Value::Void;
-    for basic_block in fn_bytecode.basic_blocks.iter(fn_bytecode) {
-        return_value = eval_basic_block(db, fn_bytecode, basic_block, variables, io_handler);
+    let mut next_block = Some(fn_bytecode.entry_block());
+
+    while let Some(basic_block) = next_block {
+        if let Some(value) = eval_basic_block(db, fn_bytecode, basic_block, variables, io_handler)?
+        {
+            return_value = value;
+        }
+
+        next_block = eval_terminator(db, fn_bytecode, basic_block, variables)?;
     }
 
-    return_value
+    Ok(return_value)
+}
+
+/// Runs the `main` function of each input file. Stops at the first
+/// runtime error (e.g., division by zero), which is returned.
+pub fn eval(db: &LarkDatabase, io_handler: &mut IOHandler) -> Result<(), Diagnostic> {
+    eval_entry(db, "main", io_handler)
 }
 
-pub fn eval(db: &LarkDatabase, io_handler: &mut IOHandler) {
+/// Like `eval`, but runs the functions named `entry` instead of `main`.
+pub fn eval_entry(
+    db: &LarkDatabase,
+    entry: &str,
+    io_handler: &mut IOHandler,
+) -> Result<(), Diagnostic> {
     let input_files = db.file_names();
     //let mut errors: Vec<Diagnostic> = vec![];
 
     let mut variables: HashMap<Variable, Vec<Value>> = HashMap::new();
-    let main_name = "main".intern(&db);
+    let main_name = entry.intern(&db);
 
     for &input_file in &*input_files {
         let entities = db.top_level_entities_in_file(input_file);
@@ -275,13 +740,15 @@ pub fn eval(db: &LarkDatabase, io_handler: &mut IOHandler) {
                     ..
                 } => {
                     if id == main_name {
-                        let bytecode = db.fn_bytecode(entity);
+                        let bytecode = fn_bytecode_or_error(db, entity)?;
 
-                        eval_function(db, &bytecode.value, &mut variables, io_handler);
+                        eval_function(db, &bytecode, &mut variables, io_handler)?;
                     }
                 }
                 _ => {}
             }
         }
     }
+
+    Ok(())
 }
@@ -15,8 +15,10 @@ use lark_entity::MemberKind;
 use lark_error::ErrorReported;
 use lark_error::ErrorSentinel;
 use lark_indices::{IndexVec, U32Index};
+use lark_intern::Untern;
 use lark_span::{FileName, Span};
 use lark_string::GlobalIdentifier;
+use lark_string::GlobalIdentifierTables;
 use std::sync::Arc;
 
 #[derive(Copy, Clone, Debug, DebugWith, PartialEq, Eq, Hash)]
@@ -77,6 +79,12 @@ pub struct FnBodyTables {
     /// A `a: b` pair.
     pub identified_expressions: IndexVec<IdentifiedExpression, IdentifiedExpressionData>,
 
+    /// A `<pattern> => <body>` arm of a `match`.
+    pub match_arms: IndexVec<MatchArm, MatchArmData>,
+
+    /// Map each pattern index to its associated data.
+    pub patterns: IndexVec<Pattern, PatternData>,
+
     /// Map each place index to its associated data.
     pub places: IndexVec<Place, PlaceData>,
 
@@ -271,6 +279,8 @@ macro_rules! define_meta_index {
 define_meta_index! {
     (Expression, ExpressionData, expressions),
     (IdentifiedExpression, IdentifiedExpressionData, identified_expressions),
+    (MatchArm, MatchArmData, match_arms),
+    (Pattern, PatternData, patterns),
     (Place, PlaceData, places),
     (Variable, VariableData, variables),
     (Identifier, IdentifierData, identifiers),
@@ -437,6 +447,36 @@ pub enum ExpressionData {
         if_false: Expression,
     },
 
+    /// match E { P1 => E1, ..., Pn => En }
+    Match {
+        scrutinee: Expression,
+        arms: List<MatchArm>,
+    },
+
+    /// while E1 { E2 }
+    While {
+        condition: Expression,
+        body: Expression,
+    },
+
+    /// for V in E1 { E2 } -- E2 runs once for each element of the
+    /// list E1, which is bound to V
+    For {
+        variable: Variable,
+        list: Expression,
+        body: Expression,
+    },
+
+    /// `break` -- leaves the innermost enclosing loop
+    Break {},
+
+    /// `continue` -- starts the next iteration of the innermost
+    /// enclosing loop
+    Continue {},
+
+    /// `return E`; a bare `return` returns a `Unit` expression
+    Return { value: Expression },
+
     /// E1 (op) E2
     Binary {
         operator: BinaryOperator,
@@ -457,11 +497,17 @@ pub enum ExpressionData {
     /// tuple:
     ///
     /// - `Struct { field1: expression1, ... fieldN: expressionN }`
+    /// - `(expression0, ... expressionN)`, whose fields are named
+    ///   `0` through `N`
     Aggregate {
         entity: Entity,
         fields: List<IdentifiedExpression>,
     },
 
+    /// `[E1, ..., En]` -- constructs a `List` holding the given
+    /// elements
+    ListLiteral { elements: List<Expression> },
+
     /// `()`
     Unit {},
 
@@ -477,11 +523,24 @@ pub enum BinaryOperator {
     Divide,
     Equals,
     NotEquals,
+    LessThan,
+    LessThanOrEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+
+    /// `&&`, which only evaluates its right operand if the left one
+    /// is true
+    And,
+
+    /// `||`, which only evaluates its right operand if the left one
+    /// is false
+    Or,
 }
 
 #[derive(Copy, Clone, Debug, DebugWith, PartialEq, Eq, Hash)]
 pub enum UnaryOperator {
     Not,
+    Negate,
 }
 
 lark_indices::index_type! {
@@ -494,6 +553,40 @@ pub struct IdentifiedExpressionData {
     pub expression: Expression,
 }
 
+lark_indices::index_type! {
+    pub struct MatchArm { .. }
+}
+
+#[derive(Copy, Clone, Debug, DebugWith, PartialEq, Eq, Hash)]
+pub struct MatchArmData {
+    pub pattern: Pattern,
+    pub body: Expression,
+}
+
+lark_indices::index_type! {
+    pub struct Pattern { .. }
+}
+
+#[derive(Copy, Clone, Debug, DebugWith, PartialEq, Eq, Hash)]
+pub enum PatternData {
+    /// `Enum::Variant(x, y)` -- matches the variant `entity`, binding
+    /// each field of its payload (in declaration order) to a variable.
+    Variant {
+        entity: Entity,
+        bindings: List<Variable>,
+    },
+
+    /// `_` -- matches anything
+    Wildcard,
+
+    /// `Error` -- some error condition; any variables that the
+    /// pattern binds are given the error type
+    Error {
+        error: Error,
+        bindings: List<Variable>,
+    },
+}
+
 lark_indices::index_type! {
     pub struct Place { .. }
 }
@@ -503,7 +596,16 @@ pub enum PlaceData {
     Variable(Variable),
     Entity(Entity),
     Temporary(Expression),
-    Field { owner: Place, name: Identifier },
+    Field {
+        owner: Place,
+        name: Identifier,
+    },
+
+    /// `owner[index]` -- an element of a `List`
+    Index {
+        owner: Place,
+        index: Expression,
+    },
 }
 
 #[derive(Copy, Clone, Debug, DebugWith, PartialEq, Eq, Hash)]
@@ -512,13 +614,44 @@ pub struct LiteralData {
 
     /// We represent all literals as strings internally, which
     /// sidesteps questions about how many bits to allocate for an
-    /// integer and so forth.
+    /// integer and so forth. For string literals, this is the
+    /// contents of the string, with escape sequences already
+    /// processed.
     pub value: GlobalIdentifier,
 }
 
+impl LiteralData {
+    /// The value of an integer literal used as a `uint`, or `None` if
+    /// it does not fit.
+    pub fn uint_value(&self, db: &dyn AsRef<GlobalIdentifierTables>) -> Option<u32> {
+        self.digits(db).parse().ok()
+    }
+
+    /// The value of an integer literal used as an `int`, or `None` if
+    /// it does not fit.
+    pub fn int_value(&self, db: &dyn AsRef<GlobalIdentifierTables>) -> Option<i32> {
+        self.digits(db).parse().ok()
+    }
+
+    /// True for an integer literal like `-22`, which can only be an `int`.
+    pub fn is_negative(&self, db: &dyn AsRef<GlobalIdentifierTables>) -> bool {
+        self.digits(db).starts_with('-')
+    }
+
+    /// The digits of an integer literal, without any `_` separators.
+    fn digits(&self, db: &dyn AsRef<GlobalIdentifierTables>) -> String {
+        assert_eq!(self.kind, LiteralKind::Integer);
+        let text = self.value.untern(db);
+        text.chars().filter(|&c| c != '_').collect()
+    }
+}
+
 #[derive(Copy, Clone, Debug, DebugWith, PartialEq, Eq, Hash)]
 pub enum LiteralKind {
-    UnsignedInteger,
+    /// An integer literal, like `22` or `-22` (a `-` right before an
+    /// integer literal is part of it). Negative literals are `int`;
+    /// others are `int` where an `int` is expected and `uint` otherwise.
+    Integer,
     String,
 }
 
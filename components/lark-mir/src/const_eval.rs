@@ -0,0 +1,311 @@
+use crate::ops;
+use crate::BinOp;
+use crate::ConstValue;
+use crate::MirDatabase;
+use lark_collections::FxIndexSet;
+use lark_entity::{Entity, EntityData, ItemKind, LangItem};
+use lark_error::Diagnostic;
+use lark_error::ErrorReported;
+use lark_error::WithError;
+use lark_hir as hir;
+use lark_intern::Untern;
+use lark_ty::base_inferred::BaseInferred;
+use lark_ty::{BaseKind, Ty};
+use lark_type_check::TypeCheckResults;
+use std::sync::Arc;
+
+/// Evaluates the initializer of the `const` item `entity`. Only a
+/// small subset of the language is supported here: literals, `true`
+/// and `false`, other constants, and the unary and binary operators.
+///
+/// Errors in the initializer that were found by earlier passes are
+/// not reported again; in that case, we just give back an error.
+crate fn const_value(
+    db: &impl MirDatabase,
+    entity: Entity,
+) -> WithError<Result<ConstValue, ErrorReported>> {
+    let span = db.parsed_entity(entity).characteristic_span;
+
+    // A constant that (indirectly) refers to itself has no value.
+    // This has to be checked up front: evaluating it would ask salsa
+    // for the value that we are in the middle of computing.
+    if depends_on(db, entity, entity) {
+        let message = "cycle detected when evaluating this constant".to_string();
+        return WithError::report_error(db, message, span);
+    }
+
+    let fn_body = db.fn_body(entity);
+    let base_type_check = db.base_type_check(entity);
+    let full_type_check = db.full_type_check(entity);
+    let earlier_errors: Vec<Diagnostic> = fn_body
+        .errors
+        .iter()
+        .chain(&base_type_check.errors)
+        .chain(&full_type_check.errors)
+        .cloned()
+        .collect();
+    if !earlier_errors.is_empty() {
+        return WithError::ok(Err(ErrorReported::at_diagnostics(&earlier_errors)));
+    }
+
+    let fn_body = fn_body.value;
+    let mut errors = vec![];
+    let value = ConstEvaluator {
+        db,
+        fn_body: &fn_body,
+        type_check_results: base_type_check.value,
+        errors: &mut errors,
+    }
+    .eval(fn_body.root_expression);
+
+    WithError { value, errors }
+}
+
+/// True if evaluating the constant `entity` requires the value of
+/// `target`, whether directly or through other constants.
+fn depends_on(db: &impl MirDatabase, entity: Entity, target: Entity) -> bool {
+    let mut visited = FxIndexSet::default();
+    let mut stack = vec![entity];
+
+    while let Some(entity) = stack.pop() {
+        for dependency in referenced_constants(db, entity) {
+            if dependency == target {
+                return true;
+            }
+
+            if visited.insert(dependency) {
+                stack.push(dependency);
+            }
+        }
+    }
+
+    false
+}
+
+/// The constants named in the initializer of the constant `entity`.
+fn referenced_constants(db: &impl MirDatabase, entity: Entity) -> Vec<Entity> {
+    let fn_body = db.fn_body(entity).into_value();
+    fn_body
+        .tables
+        .places
+        .iter()
+        .filter_map(|place_data| match *place_data {
+            hir::PlaceData::Entity(entity) if is_const(db, entity) => Some(entity),
+            _ => None,
+        })
+        .collect()
+}
+
+fn is_const(db: &impl MirDatabase, entity: Entity) -> bool {
+    match entity.untern(db) {
+        EntityData::ItemName {
+            kind: ItemKind::Const,
+            ..
+        } => true,
+        _ => false,
+    }
+}
+
+struct ConstEvaluator<'me, DB: MirDatabase> {
+    db: &'me DB,
+    fn_body: &'me hir::FnBody,
+
+    /// The results of type-checking the initializer, which tell us
+    /// e.g. whether an integer literal is an `int` or a `uint`.
+    type_check_results: Arc<TypeCheckResults<BaseInferred>>,
+
+    errors: &'me mut Vec<Diagnostic>,
+}
+
+impl<'me, DB> ConstEvaluator<'me, DB>
+where
+    DB: MirDatabase,
+{
+    fn eval(&mut self, expression: hir::Expression) -> Result<ConstValue, ErrorReported> {
+        let fn_body = self.fn_body;
+        match fn_body.tables[expression] {
+            hir::ExpressionData::Literal { data } => match data.kind {
+                // Literals that do not fit their type were reported by
+                // the type checker, so we never get here for them.
+                hir::LiteralKind::Integer if self.is_lang_item(expression, LangItem::Int) => {
+                    Ok(ConstValue::Int(data.int_value(self.db).unwrap()))
+                }
+                hir::LiteralKind::Integer => {
+                    Ok(ConstValue::Uint(data.uint_value(self.db).unwrap()))
+                }
+                hir::LiteralKind::String => {
+                    Ok(ConstValue::String(data.value.untern(self.db).to_string()))
+                }
+            },
+
+            hir::ExpressionData::Place { place } => match fn_body.tables[place] {
+                hir::PlaceData::Entity(entity) => match entity.untern(self.db) {
+                    EntityData::LangItem(LangItem::True) => Ok(ConstValue::Bool(true)),
+                    EntityData::LangItem(LangItem::False) => Ok(ConstValue::Bool(false)),
+                    EntityData::ItemName {
+                        kind: ItemKind::Const,
+                        ..
+                    } => {
+                        // Any errors were reported along with the
+                        // other constant.
+                        self.db.const_value(entity).into_value()
+                    }
+                    _ => Err(self.report_unsupported(expression)),
+                },
+                _ => Err(self.report_unsupported(expression)),
+            },
+
+            hir::ExpressionData::Unary { operator, value } => {
+                let value = self.eval(value)?;
+                match (operator, value) {
+                    (hir::UnaryOperator::Not, ConstValue::Bool(b)) => Ok(ConstValue::Bool(!b)),
+                    (hir::UnaryOperator::Negate, ConstValue::Int(i)) => ops::checked_negate(i)
+                        .map(ConstValue::Int)
+                        .map_err(|message| self.report(expression, &message)),
+                    _ => Err(self.report_mismatch(expression)),
+                }
+            }
+
+            // Like at runtime, the right operand is only evaluated if
+            // the left one does not already decide the result.
+            hir::ExpressionData::Binary {
+                operator,
+                left,
+                right,
+            } if operator == hir::BinaryOperator::And || operator == hir::BinaryOperator::Or => {
+                match (operator, self.eval(left)?) {
+                    (hir::BinaryOperator::And, ConstValue::Bool(false)) => {
+                        Ok(ConstValue::Bool(false))
+                    }
+                    (hir::BinaryOperator::Or, ConstValue::Bool(true)) => Ok(ConstValue::Bool(true)),
+                    _ => self.eval(right),
+                }
+            }
+
+            hir::ExpressionData::Binary {
+                operator,
+                left,
+                right,
+            } => {
+                let left = self.eval(left)?;
+                let right = self.eval(right)?;
+                self.eval_binary(expression, operator, left, right)
+            }
+
+            hir::ExpressionData::Error { .. } => {
+                // Already reported when the HIR was constructed.
+                Err(ErrorReported::at_span(fn_body.span(expression)))
+            }
+
+            hir::ExpressionData::Let { .. }
+            | hir::ExpressionData::Assignment { .. }
+            | hir::ExpressionData::MethodCall { .. }
+            | hir::ExpressionData::Call { .. }
+            | hir::ExpressionData::Sequence { .. }
+            | hir::ExpressionData::If { .. }
+            | hir::ExpressionData::Match { .. }
+            | hir::ExpressionData::While { .. }
+            | hir::ExpressionData::For { .. }
+            | hir::ExpressionData::Break { .. }
+            | hir::ExpressionData::Continue { .. }
+            | hir::ExpressionData::Return { .. }
+            | hir::ExpressionData::Aggregate { .. }
+            | hir::ExpressionData::ListLiteral { .. }
+            | hir::ExpressionData::Unit {} => Err(self.report_unsupported(expression)),
+        }
+    }
+
+    /// Applies a binary operator other than `&&` and `||`, with the
+    /// same checks for overflow as at runtime.
+    fn eval_binary(
+        &mut self,
+        expression: hir::Expression,
+        operator: hir::BinaryOperator,
+        left: ConstValue,
+        right: ConstValue,
+    ) -> Result<ConstValue, ErrorReported> {
+        use self::ConstValue::{Bool, Int, Uint};
+
+        let op = match operator {
+            hir::BinaryOperator::Add => BinOp::Add,
+            hir::BinaryOperator::Subtract => BinOp::Sub,
+            hir::BinaryOperator::Multiply => BinOp::Mul,
+            hir::BinaryOperator::Divide => BinOp::Div,
+            hir::BinaryOperator::Equals => BinOp::Eq,
+            hir::BinaryOperator::NotEquals => BinOp::Ne,
+            hir::BinaryOperator::LessThan => BinOp::Lt,
+            hir::BinaryOperator::LessThanOrEqual => BinOp::Le,
+            hir::BinaryOperator::GreaterThan => BinOp::Gt,
+            hir::BinaryOperator::GreaterThanOrEqual => BinOp::Ge,
+            hir::BinaryOperator::And | hir::BinaryOperator::Or => {
+                unreachable!("`&&` and `||` are evaluated lazily")
+            }
+        };
+
+        let result = match (op, left, right) {
+            (BinOp::Eq, left, right) => Ok(Bool(left == right)),
+            (BinOp::Ne, left, right) => Ok(Bool(left != right)),
+
+            (BinOp::Lt, Int(left), Int(right))
+            | (BinOp::Le, Int(left), Int(right))
+            | (BinOp::Gt, Int(left), Int(right))
+            | (BinOp::Ge, Int(left), Int(right)) => Ok(Bool(ops::compare(op, &left, &right))),
+            (BinOp::Lt, Uint(left), Uint(right))
+            | (BinOp::Le, Uint(left), Uint(right))
+            | (BinOp::Gt, Uint(left), Uint(right))
+            | (BinOp::Ge, Uint(left), Uint(right)) => Ok(Bool(ops::compare(op, &left, &right))),
+
+            (BinOp::Add, ConstValue::String(left), ConstValue::String(right)) => {
+                Ok(ConstValue::String(left + &right))
+            }
+
+            (BinOp::Add, Int(left), Int(right))
+            | (BinOp::Sub, Int(left), Int(right))
+            | (BinOp::Mul, Int(left), Int(right))
+            | (BinOp::Div, Int(left), Int(right)) => {
+                ops::checked_arithmetic(op, left, right).map(Int)
+            }
+            (BinOp::Add, Uint(left), Uint(right))
+            | (BinOp::Sub, Uint(left), Uint(right))
+            | (BinOp::Mul, Uint(left), Uint(right))
+            | (BinOp::Div, Uint(left), Uint(right)) => {
+                ops::checked_arithmetic(op, left, right).map(Uint)
+            }
+
+            _ => return Err(self.report_mismatch(expression)),
+        };
+
+        result.map_err(|message| self.report(expression, &message))
+    }
+
+    fn is_lang_item(&self, expression: hir::Expression, item: LangItem) -> bool {
+        let ty: Ty<BaseInferred> = self.type_check_results.ty(expression);
+        match ty.base.untern(self.db).kind {
+            BaseKind::Named(entity) => entity.untern(self.db) == EntityData::LangItem(item),
+            BaseKind::Placeholder(_) | BaseKind::Error => false,
+        }
+    }
+
+    fn report(&mut self, expression: hir::Expression, label: &str) -> ErrorReported {
+        let span = self.fn_body.span(expression);
+        self.errors.push(Diagnostic::new(label.to_string(), span));
+        ErrorReported::at_span(span)
+    }
+
+    /// The type checker only lets operators through whose operands
+    /// have a suitable type, so this is only reachable if it and the
+    /// evaluator disagree.
+    fn report_mismatch(&mut self, expression: hir::Expression) -> ErrorReported {
+        self.report(
+            expression,
+            "cannot apply this operator to values of these types",
+        )
+    }
+
+    fn report_unsupported(&mut self, expression: hir::Expression) -> ErrorReported {
+        self.report(
+            expression,
+            "only literals, operators and other constants may appear in a `const`",
+        )
+    }
+}
@@ -1,13 +1,19 @@
 use crate as mir;
 use crate::MirDatabase;
 use lark_collections::FxIndexMap;
-use lark_entity::Entity;
+use lark_entity::{Entity, EntityData, ItemKind, LangItem, MemberKind};
 use lark_error::Diagnostic;
+use lark_error::ErrorReported;
+use lark_error::ErrorSentinel;
 use lark_error::WithError;
 use lark_hir as hir;
-use lark_intern::Intern;
-use lark_span::{FileName, Span, Spanned};
-use lark_string::GlobalIdentifier;
+use lark_intern::{Intern, Untern};
+use lark_span::{FileName, Span};
+use lark_ty::base_inferred::BaseInferred;
+use lark_ty::declaration::DeclaredPermKind;
+use lark_ty::full_inferred::FullInferred;
+use lark_ty::{BaseData, BaseKind, Generics, PermKind, Ty, TypeFamily};
+use lark_type_check::TypeCheckResults;
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -16,7 +22,14 @@ crate fn fn_bytecode(
     item_entity: Entity,
 ) -> WithError<Arc<crate::FnBytecode>> {
     let mut errors = vec![];
-    let fn_bytecode = MirLower::new(db, item_entity, &mut errors).lower_to_bytecode();
+    let mut fn_bytecode = MirLower::new(db, item_entity, &mut errors).lower_to_bytecode();
+
+    // If anything could not be lowered, don't hand the partially
+    // lowered body to the backends.
+    if !errors.is_empty() {
+        fn_bytecode = ErrorSentinel::error_sentinel(db, ErrorReported::at_diagnostics(&errors));
+    }
+
     WithError {
         value: Arc::new(fn_bytecode),
         errors,
@@ -27,42 +40,201 @@ struct MirLower<'me, DB: MirDatabase> {
     db: &'me DB,
     item_entity: Entity,
     fn_bytecode_tables: mir::FnBytecodeTables,
-    variables: FxIndexMap<GlobalIdentifier, mir::Variable>,
-    //errors: &'me mut Vec<Diagnostic>,
+
+    /// The results of type-checking the function, used to give each
+    /// variable a type.
+    type_check_results: Arc<TypeCheckResults<BaseInferred>>,
+
+    /// The results of the full type-check, used to decide which uses
+    /// of a place move the value out of it.
+    full_type_check_results: Arc<TypeCheckResults<FullInferred>>,
+
+    /// The MIR variable for each HIR variable lowered so far. Each
+    /// `let` gets its own variable, even if it shadows another one.
+    variables: FxIndexMap<hir::Variable, mir::Variable>,
+    errors: &'me mut Vec<Diagnostic>,
     next_temporary_id: usize,
+
+    /// All basic blocks created so far, in order of creation.
+    basic_blocks: Vec<mir::BasicBlock>,
+
+    /// The block that new statements are being added to. This starts
+    /// out as the entry block, which is always the first block created.
+    current_block: mir::BasicBlock,
+
+    /// The variables whose storage is live at this point of the
+    /// lowering, innermost last. Code that jumps out of their scopes
+    /// (like `break`) ends their storage first.
+    live_variables: Vec<mir::Variable>,
+
+    /// The `while` and `for` loops that enclose the code being
+    /// lowered, innermost last.
+    loops: Vec<LoopScope>,
+
+    /// Every loop lowered so far, for `FnBytecode::loops`.
+    lowered_loops: Vec<mir::Loop>,
+}
+
+/// Where `break` and `continue` go within a loop.
+struct LoopScope {
+    /// The block that decides whether to run another iteration.
+    continue_block: mir::BasicBlock,
+
+    /// The block following the loop.
+    break_block: mir::BasicBlock,
+
+    /// The number of `live_variables` on entry to the body of the
+    /// loop.
+    live_variables: usize,
 }
 
 impl<'me, DB> MirLower<'me, DB>
 where
     DB: MirDatabase,
 {
-    fn new(db: &'me DB, item_entity: Entity, _errors: &'me mut Vec<Diagnostic>) -> Self {
+    fn new(db: &'me DB, item_entity: Entity, errors: &'me mut Vec<Diagnostic>) -> Self {
         MirLower {
             db,
-            //errors,
+            errors,
             item_entity,
             fn_bytecode_tables: Default::default(),
+            type_check_results: db.base_type_check(item_entity).into_value(),
+            full_type_check_results: db.full_type_check(item_entity).into_value(),
             variables: Default::default(),
             next_temporary_id: 0,
+            basic_blocks: vec![],
+            current_block: mir::BasicBlock::new(0),
+            live_variables: vec![],
+            loops: vec![],
+            lowered_loops: vec![],
         }
     }
 
     fn add<D: mir::MirIndexData>(&mut self, span: Span<FileName>, node: D) -> D::Index {
-        D::index_vec_mut(&mut self.fn_bytecode_tables).push(Spanned::new(node, span))
+        self.fn_bytecode_tables.add(span, node)
+    }
+
+    /// Reports that the code at `span` uses something that MIR
+    /// lowering does not support yet, returning an error that can
+    /// stand in for the unsupported code.
+    fn report_unimplemented(&mut self, span: Span<FileName>, label: &str) -> mir::Error {
+        self.errors.push(Diagnostic::new(label.to_string(), span));
+        self.add(span, mir::ErrorData::Unimplemented)
+    }
+
+    /// Creates an operand standing in for an expression that could not
+    /// be lowered.
+    fn error_operand(&mut self, span: Span<FileName>, error: mir::Error) -> mir::Operand {
+        self.add(span, mir::OperandData::Error(error))
+    }
+
+    /// Creates a new basic block. Its statements and terminator are
+    /// filled in later by `terminate_block`, which lets branches refer
+    /// to blocks that have not been lowered yet.
+    fn new_basic_block(&mut self, span: Span<FileName>) -> mir::BasicBlock {
+        let basic_block = self.add(
+            span,
+            mir::BasicBlockData {
+                statements: mir::List::default(),
+                terminator: mir::Terminator::Return,
+            },
+        );
+        self.basic_blocks.push(basic_block);
+        basic_block
     }
 
-    fn save_scope(&self) -> FxIndexMap<GlobalIdentifier, mir::Variable> {
-        self.variables.clone()
+    /// Finishes the current block with the pending `statements` and
+    /// the given terminator. Callers must then pick a new current block.
+    fn terminate_block(
+        &mut self,
+        statements: &mut Vec<mir::Statement>,
+        terminator: mir::Terminator,
+    ) {
+        let statements =
+            mir::List::from_iterator(&mut self.fn_bytecode_tables, statements.drain(..));
+        let basic_block_data = &mut self.fn_bytecode_tables.basic_blocks[self.current_block].value;
+        basic_block_data.statements = statements;
+        basic_block_data.terminator = terminator;
+    }
+
+    /// Starts the storage of `variable`, which stays live until the
+    /// matching `storage_dead`.
+    fn storage_live(
+        &mut self,
+        span: Span<FileName>,
+        variable: mir::Variable,
+        statements: &mut Vec<mir::Statement>,
+    ) {
+        let statement = self.add(
+            span,
+            mir::StatementData {
+                kind: mir::StatementKind::StorageLive(variable),
+            },
+        );
+        statements.push(statement);
+        self.live_variables.push(variable);
     }
 
-    fn restore_scope(&mut self, scope: FxIndexMap<GlobalIdentifier, mir::Variable>) {
-        self.variables = scope;
+    /// Assigns the value of `rvalue` to `place`.
+    fn assign(
+        &mut self,
+        span: Span<FileName>,
+        place: mir::Place,
+        rvalue: mir::Rvalue,
+        statements: &mut Vec<mir::Statement>,
+    ) {
+        let statement = self.add(
+            span,
+            mir::StatementData {
+                kind: mir::StatementKind::Assign(place, rvalue),
+            },
+        );
+        statements.push(statement);
+    }
+
+    /// Ends the storage of `variable`.
+    fn storage_dead(
+        &mut self,
+        span: Span<FileName>,
+        variable: mir::Variable,
+        statements: &mut Vec<mir::Statement>,
+    ) {
+        let statement = self.add(
+            span,
+            mir::StatementData {
+                kind: mir::StatementKind::StorageDead(variable),
+            },
+        );
+        statements.push(statement);
+        if let Some(index) = self.live_variables.iter().rposition(|&v| v == variable) {
+            self.live_variables.remove(index);
+        }
     }
 
-    /// Brings a variable into scope, returning anything that was shadowed.
-    fn bring_into_scope(&mut self, variable: mir::Variable) {
-        let name = self[variable].name;
-        self.variables.insert(self[name].text, variable);
+    /// Jumps out of the scopes of all but the first `live_variables`
+    /// live variables, ending their storage, and terminates the current
+    /// block with `terminator`. Any code that follows the jump is
+    /// unreachable; it goes into a fresh block.
+    fn lower_jump(
+        &mut self,
+        span: Span<FileName>,
+        live_variables: usize,
+        terminator: mir::Terminator,
+        statements: &mut Vec<mir::Statement>,
+    ) {
+        let dying_variables: Vec<_> = self.live_variables[live_variables..].to_vec();
+        for variable in dying_variables.into_iter().rev() {
+            let statement = self.add(
+                span,
+                mir::StatementData {
+                    kind: mir::StatementKind::StorageDead(variable),
+                },
+            );
+            statements.push(statement);
+        }
+
+        self.terminate_block(statements, terminator);
+        self.current_block = self.new_basic_block(span);
     }
 
     /*
@@ -71,7 +243,116 @@ where
     }
     */
 
-    fn create_temporary(&mut self, span: Span<FileName>) -> mir::Variable {
+    /// The type inferred for `index`. If type-checking failed before
+    /// reaching `index`, this is the error type.
+    fn ty(&self, index: impl Into<hir::MetaIndex>) -> Ty<BaseInferred> {
+        self.type_check_results
+            .opt_ty(index)
+            .unwrap_or_else(|| BaseInferred::error_type(self.db))
+    }
+
+    /// The built-in type `item`, like `uint`, for values that do not
+    /// come from any expression.
+    fn primitive_ty(&self, item: LangItem) -> Ty<BaseInferred> {
+        let entity = EntityData::LangItem(item).intern(self.db);
+        Ty {
+            repr: BaseInferred::direct_repr(self.db),
+            perm: BaseInferred::own_perm(self.db),
+            base: BaseInferred::intern_base_data(
+                self.db,
+                BaseData {
+                    kind: BaseKind::Named(entity),
+                    generics: Generics::empty(),
+                },
+            ),
+        }
+    }
+
+    /// True if using the value of `index` (an expression, or a
+    /// variable bound by a pattern) moves it out of its place: that
+    /// is the case for values that we own, unless they can be copied.
+    fn moves_on_use(&self, index: impl Into<hir::MetaIndex>) -> bool {
+        let ty = match self.full_type_check_results.opt_ty(index) {
+            Some(ty) => ty,
+            None => return false,
+        };
+
+        if ty.perm != PermKind::Own {
+            return false;
+        }
+
+        !ty.base.untern(self.db).kind.is_copy(self.db)
+    }
+
+    /// True if `expression` produces a value of the built-in type `item`.
+    fn is_lang_item(&self, expression: hir::Expression, item: LangItem) -> bool {
+        match self.ty(expression).base.untern(self.db).kind {
+            BaseKind::Named(entity) => entity.untern(self.db) == EntityData::LangItem(item),
+            BaseKind::Placeholder(_) | BaseKind::Error => false,
+        }
+    }
+
+    /// If `place` names a `const` item, returns an operand holding
+    /// its value: constants are inlined into the bytecode, so the
+    /// backends never see them.
+    fn constant_operand(
+        &mut self,
+        fn_body: &hir::FnBody,
+        place: hir::Place,
+    ) -> Option<mir::Operand> {
+        let entity = match fn_body.tables[place] {
+            hir::PlaceData::Entity(entity) => entity,
+            _ => return None,
+        };
+
+        match entity.untern(self.db) {
+            EntityData::ItemName {
+                kind: ItemKind::Const,
+                ..
+            } => {}
+            _ => return None,
+        }
+
+        let span = fn_body.span(place);
+        let operand = match self.db.const_value(entity).into_value() {
+            Ok(mir::ConstValue::Bool(value)) => {
+                let lang_item = if value {
+                    LangItem::True
+                } else {
+                    LangItem::False
+                };
+                let entity = EntityData::LangItem(lang_item).intern(self.db);
+                mir::OperandData::Copy(self.add(span, mir::PlaceData::Entity(entity)))
+            }
+            Ok(mir::ConstValue::Int(value)) => mir::OperandData::ConstantInt(value),
+            Ok(mir::ConstValue::Uint(value)) => mir::OperandData::ConstantUint(value),
+            Ok(mir::ConstValue::String(value)) => mir::OperandData::ConstantString(value),
+            Err(ErrorReported(_)) => {
+                // Reported when evaluating the constant.
+                mir::OperandData::Error(self.add(span, mir::ErrorData::Misc))
+            }
+        };
+
+        Some(self.add(span, operand))
+    }
+
+    /// Creates the operand that uses the value of `expression`, which
+    /// is stored in `place`.
+    fn place_operand(
+        &mut self,
+        fn_body: &hir::FnBody,
+        expression: hir::Expression,
+        place: mir::Place,
+    ) -> mir::Operand {
+        let operand = if self.moves_on_use(expression) {
+            mir::OperandData::Move(place)
+        } else {
+            mir::OperandData::Copy(place)
+        };
+        self.add(fn_body.span(expression), operand)
+    }
+
+    fn create_temporary(&mut self, span: Span<FileName>, ty: Ty<BaseInferred>) -> mir::Variable {
         let temp_variable_name = format!("_tmp{}", self.next_temporary_id).intern(&mut self.db);
         let temp_identifier = self.add(
             span,
@@ -84,45 +365,113 @@ where
             span,
             mir::VariableData {
                 name: temp_identifier,
+                ty,
             },
         )
     }
 
+    /// The members of `owner` of the given kind (e.g., the variants
+    /// of an enum), in the order in which they are declared.
+    fn members_of_kind(&self, owner: Entity, kind: MemberKind) -> Vec<hir::Member> {
+        match self.db.members(owner) {
+            Ok(members) => members.iter().filter(|m| m.kind == kind).cloned().collect(),
+            Err(ErrorReported(_)) => vec![],
+        }
+    }
+
     fn lower_variable(&mut self, fn_body: &hir::FnBody, variable: hir::Variable) -> mir::Variable {
+        if let Some(&mir_variable) = self.variables.get(&variable) {
+            return mir_variable;
+        }
+
         match fn_body.tables[variable] {
             hir::VariableData { name } => match fn_body.tables[name] {
                 hir::IdentifierData { text } => {
-                    if let Some(&variable) = self.variables.get(&text) {
-                        variable
-                    } else {
-                        let mir_identifier =
-                            self.add(fn_body.span(variable), mir::IdentifierData { text });
-
-                        self.add(
-                            fn_body.span(variable),
-                            mir::VariableData {
-                                name: mir_identifier,
-                            },
-                        )
-                    }
+                    let mir_identifier =
+                        self.add(fn_body.span(variable), mir::IdentifierData { text });
+                    let ty = self.ty(variable);
+
+                    let mir_variable = self.add(
+                        fn_body.span(variable),
+                        mir::VariableData {
+                            name: mir_identifier,
+                            ty,
+                        },
+                    );
+                    self.variables.insert(variable, mir_variable);
+                    mir_variable
                 }
             },
         }
     }
 
+    /// Lowers a call expression: either a call to a named function
+    /// or a method call (whose first argument is `self`).
     fn lower_call(
         &mut self,
         fn_body: &hir::FnBody,
-        function: hir::Place,
+        expression: hir::Expression,
+        statements: &mut Vec<mir::Statement>,
+    ) -> (mir::RvalueData, Vec<mir::Variable>) {
+        match fn_body.tables[expression] {
+            hir::ExpressionData::Call {
+                function,
+                arguments,
+            } => match fn_body.tables[function] {
+                hir::PlaceData::Entity(entity) => {
+                    self.lower_entity_call(fn_body, entity, arguments, statements)
+                }
+                _ => {
+                    let span = fn_body.span(function);
+                    let error = self.report_unimplemented(
+                        span,
+                        "calling a value that is not a named function is not yet supported",
+                    );
+                    (
+                        mir::RvalueData::Use(self.error_operand(span, error)),
+                        vec![],
+                    )
+                }
+            },
+
+            hir::ExpressionData::MethodCall { method, arguments } => {
+                match self.type_check_results.opt_entity(method) {
+                    Some(entity) => self.lower_entity_call(fn_body, entity, arguments, statements),
+                    None => {
+                        // Type-checking already reported that there
+                        // is no such method.
+                        let span = fn_body.span(method);
+                        let error = self.add(span, mir::ErrorData::Misc);
+                        (
+                            mir::RvalueData::Use(self.error_operand(span, error)),
+                            vec![],
+                        )
+                    }
+                }
+            }
+
+            _ => panic!("lower_call invoked on an expression that is not a call"),
+        }
+    }
+
+    /// Lowers a call to the function or method `entity`.
+    fn lower_entity_call(
+        &mut self,
+        fn_body: &hir::FnBody,
+        entity: Entity,
         arguments: hir::List<hir::Expression>,
         statements: &mut Vec<mir::Statement>,
-    ) -> (Entity, mir::List<mir::Operand>, Vec<mir::Variable>) {
+    ) -> (mir::RvalueData, Vec<mir::Variable>) {
         let mut args = vec![];
         let mut temp_vars = vec![];
 
-        for argument in arguments.iter(fn_body) {
-            let (arg_operand, mut arg_temp_vars) =
-                self.lower_operand(fn_body, argument, statements);
+        let inspected_arguments = self.inspected_arguments(entity, arguments.len());
+        for (argument, inspected) in arguments.iter(fn_body).zip(inspected_arguments) {
+            let (arg_operand, mut arg_temp_vars) = if inspected {
+                self.lower_inspected_operand(fn_body, argument, statements)
+            } else {
+                self.lower_operand(fn_body, argument, statements)
+            };
             args.push(arg_operand);
             arg_temp_vars.append(&mut temp_vars);
             temp_vars = arg_temp_vars;
@@ -130,12 +479,41 @@ where
 
         let call_arguments = mir::List::from_iterator(&mut self.fn_bytecode_tables, args);
 
-        let entity = match fn_body.tables[function] {
-            hir::PlaceData::Entity(entity) => entity,
-            _ => unimplemented!("Call to non-entity"),
+        (mir::RvalueData::Call(entity, call_arguments), temp_vars)
+    }
+
+    /// For each of the `count` arguments in a call to `entity`, true if
+    /// the callee only inspects that argument, so that passing a place
+    /// does not move out of it. That is the case for parameters
+    /// declared as `share` or `borrow` (e.g., `share self`), and for
+    /// all the arguments of `debug`.
+    fn inspected_arguments(&self, entity: Entity, count: usize) -> Vec<bool> {
+        let mut inspected = match entity.untern(self.db) {
+            EntityData::LangItem(LangItem::Debug) | EntityData::LangItem(LangItem::Format) => {
+                vec![true; count]
+            }
+
+            EntityData::ItemName { .. }
+            | EntityData::MemberName { .. }
+            | EntityData::LangItem(LangItem::ListLen)
+            | EntityData::LangItem(LangItem::ListPush)
+            | EntityData::LangItem(LangItem::StringLen)
+            | EntityData::LangItem(LangItem::StringSubstring) => {
+                match self.db.signature(entity).into_value() {
+                    Ok(signature) => signature
+                        .inputs
+                        .iter()
+                        .map(|input| input.perm.untern(self.db) != DeclaredPermKind::Own)
+                        .collect(),
+                    Err(ErrorReported(_)) => vec![],
+                }
+            }
+
+            _ => vec![],
         };
 
-        (entity, call_arguments, temp_vars)
+        inspected.resize(count, false);
+        inspected
     }
 
     fn lower_operand(
@@ -146,34 +524,23 @@ where
     ) -> (mir::Operand, Vec<mir::Variable>) {
         match fn_body.tables[expression] {
             hir::ExpressionData::Place { place, .. } => {
-                let place = self.lower_place(fn_body, place);
-                (
-                    self.add(fn_body.span(expression), mir::OperandData::Copy(place)),
-                    vec![],
-                )
+                if let Some(operand) = self.constant_operand(fn_body, place) {
+                    return (operand, vec![]);
+                }
+
+                let (place, temp_vars) = self.lower_place(fn_body, place, statements);
+                (self.place_operand(fn_body, expression, place), temp_vars)
             }
-            hir::ExpressionData::Call {
-                function,
-                arguments,
-            } => {
-                let (entity, call_arguments, mut temp_vars) =
-                    self.lower_call(fn_body, function, arguments, statements);
-                let new_temp_var = self.create_temporary(fn_body.span(expression));
+            hir::ExpressionData::Call { .. } | hir::ExpressionData::MethodCall { .. } => {
+                let (call_rvalue, mut temp_vars) = self.lower_call(fn_body, expression, statements);
+                let new_temp_var =
+                    self.create_temporary(fn_body.span(expression), self.ty(expression));
 
                 // Start the variable scope
-                let statement = self.add(
-                    fn_body.span(expression),
-                    mir::StatementData {
-                        kind: mir::StatementKind::StorageLive(new_temp_var),
-                    },
-                );
-                statements.push(statement);
+                self.storage_live(fn_body.span(expression), new_temp_var, statements);
 
                 // Assign this call to the temp variable
-                let rvalue = self.add(
-                    fn_body.span(expression),
-                    mir::RvalueData::Call(entity, call_arguments),
-                );
+                let rvalue = self.add(fn_body.span(expression), call_rvalue);
                 let lvalue = self.add(
                     fn_body.span(expression),
                     mir::PlaceData::Variable(new_temp_var),
@@ -194,10 +561,121 @@ where
 
                 (operand, temp_vars)
             }
-            _ => unimplemented!("Unsupported expression for operands"),
+            hir::ExpressionData::Literal { data } => {
+                // Type-checking already reported the literals that are
+                // out of range for their type.
+                let span = fn_body.span(expression);
+                let operand = match data.kind {
+                    hir::LiteralKind::Integer if self.is_lang_item(expression, LangItem::Int) => {
+                        match data.int_value(self.db) {
+                            Some(value) => mir::OperandData::ConstantInt(value),
+                            None => mir::OperandData::Error(self.add(span, mir::ErrorData::Misc)),
+                        }
+                    }
+                    hir::LiteralKind::Integer => match data.uint_value(self.db) {
+                        Some(value) => mir::OperandData::ConstantUint(value),
+                        None => mir::OperandData::Error(self.add(span, mir::ErrorData::Misc)),
+                    },
+                    hir::LiteralKind::String => {
+                        mir::OperandData::ConstantString(data.value.untern(self.db).to_string())
+                    }
+                };
+
+                (self.add(span, operand), vec![])
+            }
+            hir::ExpressionData::Let { .. }
+            | hir::ExpressionData::Sequence { .. }
+            | hir::ExpressionData::If { .. }
+            | hir::ExpressionData::Match { .. }
+            | hir::ExpressionData::Break { .. }
+            | hir::ExpressionData::Continue { .. }
+            | hir::ExpressionData::Return { .. }
+            | hir::ExpressionData::Binary { .. }
+            | hir::ExpressionData::Unary { .. }
+            | hir::ExpressionData::Aggregate { .. }
+            | hir::ExpressionData::ListLiteral { .. } => {
+                self.lower_into_temporary(fn_body, expression, statements)
+            }
+            hir::ExpressionData::Error { .. } => {
+                // Already reported when the HIR was constructed.
+                let span = fn_body.span(expression);
+                let error = self.add(span, mir::ErrorData::Misc);
+                (self.error_operand(span, error), vec![])
+            }
+            hir::ExpressionData::Unit {}
+            | hir::ExpressionData::Assignment { .. }
+            | hir::ExpressionData::While { .. }
+            | hir::ExpressionData::For { .. } => {
+                let span = fn_body.span(expression);
+                let error =
+                    self.report_unimplemented(span, "using `()` as a value is not yet supported");
+                (self.error_operand(span, error), vec![])
+            }
         }
     }
 
+    /// Like `lower_operand`, but for operands whose value is only
+    /// inspected (e.g., compared by `==`), which never move out of
+    /// their place.
+    fn lower_inspected_operand(
+        &mut self,
+        fn_body: &hir::FnBody,
+        expression: hir::Expression,
+        statements: &mut Vec<mir::Statement>,
+    ) -> (mir::Operand, Vec<mir::Variable>) {
+        match fn_body.tables[expression] {
+            hir::ExpressionData::Place { place, .. } => {
+                if let Some(operand) = self.constant_operand(fn_body, place) {
+                    return (operand, vec![]);
+                }
+
+                let (place, temp_vars) = self.lower_place(fn_body, place, statements);
+                (
+                    self.add(fn_body.span(expression), mir::OperandData::Copy(place)),
+                    temp_vars,
+                )
+            }
+            _ => self.lower_operand(fn_body, expression, statements),
+        }
+    }
+
+    /// Lowers `expression` so that its value is stored into a fresh
+    /// temporary, returning an operand that refers to the temporary.
+    fn lower_into_temporary(
+        &mut self,
+        fn_body: &hir::FnBody,
+        expression: hir::Expression,
+        statements: &mut Vec<mir::Statement>,
+    ) -> (mir::Operand, Vec<mir::Variable>) {
+        let (lvalue, temp_vars) = self.lower_into_temporary_place(fn_body, expression, statements);
+
+        let operand = self.add(fn_body.span(expression), mir::OperandData::Copy(lvalue));
+
+        (operand, temp_vars)
+    }
+
+    /// Like `lower_into_temporary`, but returns the place of the
+    /// temporary itself.
+    fn lower_into_temporary_place(
+        &mut self,
+        fn_body: &hir::FnBody,
+        expression: hir::Expression,
+        statements: &mut Vec<mir::Statement>,
+    ) -> (mir::Place, Vec<mir::Variable>) {
+        let new_temp_var = self.create_temporary(fn_body.span(expression), self.ty(expression));
+
+        // Start the variable scope
+        self.storage_live(fn_body.span(expression), new_temp_var, statements);
+
+        let lvalue = self.add(
+            fn_body.span(expression),
+            mir::PlaceData::Variable(new_temp_var),
+        );
+        self.lower_statement(fn_body, expression, Some(lvalue), statements);
+
+        (lvalue, vec![new_temp_var])
+    }
+
     fn lower_rvalue(
         &mut self,
         fn_body: &hir::FnBody,
@@ -206,29 +684,28 @@ where
     ) -> (mir::Rvalue, Vec<mir::Variable>) {
         match fn_body.tables[expression] {
             hir::ExpressionData::Place { place, .. } => {
-                let place = self.lower_place(fn_body, place);
-                let operand = self.add(fn_body.span(expression), mir::OperandData::Copy(place));
+                let (operand, temp_vars) = match self.constant_operand(fn_body, place) {
+                    Some(operand) => (operand, vec![]),
+                    None => {
+                        let (place, temp_vars) = self.lower_place(fn_body, place, statements);
+                        (self.place_operand(fn_body, expression, place), temp_vars)
+                    }
+                };
                 (
                     self.add(fn_body.span(expression), mir::RvalueData::Use(operand)),
-                    vec![],
+                    temp_vars,
                 )
             }
-            hir::ExpressionData::Call {
-                function,
-                arguments,
-            } => {
-                let (entity, call_arguments, temp_vars) =
-                    self.lower_call(fn_body, function, arguments, statements);
+            hir::ExpressionData::Call { .. } | hir::ExpressionData::MethodCall { .. } => {
+                let (call_rvalue, temp_vars) = self.lower_call(fn_body, expression, statements);
 
-                let rvalue = self.add(
-                    fn_body.span(expression),
-                    mir::RvalueData::Call(entity, call_arguments),
-                );
+                let rvalue = self.add(fn_body.span(expression), call_rvalue);
 
                 (rvalue, temp_vars)
             }
             hir::ExpressionData::Aggregate { entity, fields } => {
                 let mut mir_fields = HashMap::new();
+                let mut field_order = vec![];
                 let mut temp_vars = vec![];
 
                 for field in fields.iter(fn_body) {
@@ -243,6 +720,7 @@ where
                             match fn_body.tables[identifier] {
                                 hir::IdentifierData { text } => {
                                     mir_fields.insert(text, mir_expression);
+                                    field_order.push(text);
                                 }
                             }
 
@@ -251,12 +729,25 @@ where
                     }
                 }
 
-                let members = self.db.members(entity).unwrap();
-                let mut field_inits = vec![];
+                // The fields of a tuple are its elements, which the
+                // parser creates in order; those of a struct are
+                // initialized in the order in which they are declared.
+                let field_names = match entity.untern(self.db) {
+                    EntityData::LangItem(LangItem::Tuple(_)) => field_order,
+                    _ => self
+                        .db
+                        .members(entity)
+                        .unwrap()
+                        .iter()
+                        .filter(|m| m.kind == MemberKind::Field)
+                        .map(|m| m.name)
+                        .collect(),
+                };
 
-                for member in members.iter() {
-                    if mir_fields.contains_key(&member.name) {
-                        field_inits.push(*mir_fields.get(&member.name).unwrap());
+                let mut field_inits = vec![];
+                for name in field_names {
+                    if let Some(&init) = mir_fields.get(&name) {
+                        field_inits.push(init);
                     }
                 }
 
@@ -269,29 +760,141 @@ where
 
                 (rvalue, temp_vars)
             }
-            _ => unimplemented!("Unsupported expression for rvalues"),
+            hir::ExpressionData::ListLiteral { elements } => {
+                let mut element_operands = vec![];
+                let mut temp_vars = vec![];
+
+                for element in elements.iter(fn_body) {
+                    let (operand, mut element_temp_vars) =
+                        self.lower_operand(fn_body, element, statements);
+                    element_operands.push(operand);
+                    temp_vars.append(&mut element_temp_vars);
+                }
+
+                let list_entity = EntityData::LangItem(LangItem::List).intern(self.db);
+                let inits =
+                    mir::List::from_iterator(&mut self.fn_bytecode_tables, element_operands);
+
+                let rvalue = self.add(
+                    fn_body.span(expression),
+                    mir::RvalueData::Aggregate(list_entity, inits),
+                );
+
+                (rvalue, temp_vars)
+            }
+            hir::ExpressionData::Binary {
+                operator,
+                left,
+                right,
+            } => {
+                let (left, mut temp_vars) = self.lower_inspected_operand(fn_body, left, statements);
+                let (right, mut right_temp_vars) =
+                    self.lower_inspected_operand(fn_body, right, statements);
+                right_temp_vars.append(&mut temp_vars);
+
+                let op = match operator {
+                    hir::BinaryOperator::Add if self.is_lang_item(expression, LangItem::String) => {
+                        mir::BinOp::Concat
+                    }
+                    hir::BinaryOperator::Add => mir::BinOp::Add,
+                    hir::BinaryOperator::Subtract => mir::BinOp::Sub,
+                    hir::BinaryOperator::Multiply => mir::BinOp::Mul,
+                    hir::BinaryOperator::Divide => mir::BinOp::Div,
+                    hir::BinaryOperator::Equals => mir::BinOp::Eq,
+                    hir::BinaryOperator::NotEquals => mir::BinOp::Ne,
+                    hir::BinaryOperator::LessThan => mir::BinOp::Lt,
+                    hir::BinaryOperator::LessThanOrEqual => mir::BinOp::Le,
+                    hir::BinaryOperator::GreaterThan => mir::BinOp::Gt,
+                    hir::BinaryOperator::GreaterThanOrEqual => mir::BinOp::Ge,
+                    hir::BinaryOperator::And | hir::BinaryOperator::Or => {
+                        unreachable!("`&&` and `||` are lowered as branches")
+                    }
+                };
+
+                (
+                    self.add(
+                        fn_body.span(expression),
+                        mir::RvalueData::BinaryOp(op, left, right),
+                    ),
+                    right_temp_vars,
+                )
+            }
+            hir::ExpressionData::Unary { operator, value } => {
+                let (value, temp_vars) = self.lower_inspected_operand(fn_body, value, statements);
+
+                let op = match operator {
+                    hir::UnaryOperator::Not => mir::UnOp::Not,
+                    hir::UnaryOperator::Negate => mir::UnOp::Neg,
+                };
+
+                (
+                    self.add(
+                        fn_body.span(expression),
+                        mir::RvalueData::UnaryOp(op, value),
+                    ),
+                    temp_vars,
+                )
+            }
+            _ => {
+                let (operand, temp_vars) = self.lower_operand(fn_body, expression, statements);
+                (
+                    self.add(fn_body.span(expression), mir::RvalueData::Use(operand)),
+                    temp_vars,
+                )
+            }
         }
     }
 
-    fn lower_place(&mut self, fn_body: &hir::FnBody, place: hir::Place) -> mir::Place {
+    /// Lowers `place`, along with any temporaries needed to compute
+    /// it (e.g., for the index in `list[i + 1]`).
+    fn lower_place(
+        &mut self,
+        fn_body: &hir::FnBody,
+        place: hir::Place,
+        statements: &mut Vec<mir::Statement>,
+    ) -> (mir::Place, Vec<mir::Variable>) {
         match fn_body.tables[place] {
             hir::PlaceData::Variable(variable) => {
                 let mir_variable = self.lower_variable(fn_body, variable);
-                self.add(fn_body.span(place), mir::PlaceData::Variable(mir_variable))
-            }
-            hir::PlaceData::Entity(entity) => {
-                self.add(fn_body.span(place), mir::PlaceData::Entity(entity))
+                (
+                    self.add(fn_body.span(place), mir::PlaceData::Variable(mir_variable)),
+                    vec![],
+                )
             }
+            hir::PlaceData::Entity(entity) => (
+                self.add(fn_body.span(place), mir::PlaceData::Entity(entity)),
+                vec![],
+            ),
             hir::PlaceData::Field { owner, name } => {
-                let owner = self.lower_place(fn_body, owner);
+                let (owner, temp_vars) = self.lower_place(fn_body, owner, statements);
                 match fn_body.tables[name] {
                     hir::IdentifierData { text } => {
                         let name = self.add(fn_body.span(place), mir::IdentifierData { text });
-                        self.add(fn_body.span(place), mir::PlaceData::Field { owner, name })
+                        (
+                            self.add(fn_body.span(place), mir::PlaceData::Field { owner, name }),
+                            temp_vars,
+                        )
                     }
                 }
             }
-            x => unimplemented!("Do not yet support lowering place: {:#?}", x),
+            hir::PlaceData::Index { owner, index } => {
+                let (owner, mut temp_vars) = self.lower_place(fn_body, owner, statements);
+                let (index, mut index_temp_vars) =
+                    self.lower_inspected_operand(fn_body, index, statements);
+                index_temp_vars.append(&mut temp_vars);
+                (
+                    self.add(fn_body.span(place), mir::PlaceData::Index { owner, index }),
+                    index_temp_vars,
+                )
+            }
+            hir::PlaceData::Temporary(_) => {
+                let span = fn_body.span(place);
+                let error = self.report_unimplemented(
+                    span,
+                    "accessing fields of temporary values is not yet supported",
+                );
+                (self.add(span, mir::PlaceData::Error(error)), vec![])
+            }
         }
     }
 
@@ -302,117 +905,486 @@ where
         statements: &mut Vec<mir::Statement>,
     ) {
         for temp_var in temp_vars {
-            let statement = self.add(
-                span,
-                mir::StatementData {
-                    kind: mir::StatementKind::StorageDead(temp_var),
-                },
-            );
-            statements.push(statement);
+            self.storage_dead(span, temp_var, statements);
         }
     }
 
+    /// Lowers `expression` for its effects. If a `destination` is
+    /// given, the value of the expression is assigned to it; otherwise,
+    /// the value is left as the value of the last statement.
     fn lower_statement(
         &mut self,
         fn_body: &hir::FnBody,
         expression: hir::Expression,
+        destination: Option<mir::Place>,
         statements: &mut Vec<mir::Statement>,
     ) {
         match fn_body.tables[expression] {
-            hir::ExpressionData::Place { .. } | hir::ExpressionData::Call { .. } => {
-                let (rvalue, temp_vars) = self.lower_rvalue(fn_body, expression, statements);
-                let statement = self.add(
-                    fn_body.span(expression),
-                    mir::StatementData {
-                        kind: mir::StatementKind::Expression(rvalue),
-                    },
-                );
-
-                statements.push(statement);
-                self.drain_temp_variables(fn_body.span(expression), temp_vars, statements);
-            }
             hir::ExpressionData::Unit {} => {}
             hir::ExpressionData::Sequence { first, second } => {
-                self.lower_statement(fn_body, first, statements);
-                self.lower_statement(fn_body, second, statements);
+                self.lower_statement(fn_body, first, None, statements);
+                self.lower_statement(fn_body, second, destination, statements);
             }
             hir::ExpressionData::Let {
                 variable,
                 initializer,
                 body,
             } => {
-                let saved_scope = self.save_scope();
-
                 let mir_variable = self.lower_variable(fn_body, variable);
                 // Start the variable scope
-                let statement = self.add(
-                    fn_body.span(expression),
-                    mir::StatementData {
-                        kind: mir::StatementKind::StorageLive(mir_variable),
-                    },
-                );
-                statements.push(statement);
+                self.storage_live(fn_body.span(expression), mir_variable, statements);
 
                 // Initialize if there is an intializer
                 match initializer {
                     Some(initial_value) => {
-                        let (rvalue, temp_vars) =
-                            self.lower_rvalue(fn_body, initial_value, statements);
-
                         let lvalue = self.add(
                             fn_body.span(expression),
                             mir::PlaceData::Variable(mir_variable),
                         );
-                        let statement = self.add(
-                            fn_body.span(expression),
-                            mir::StatementData {
-                                kind: mir::StatementKind::Assign(lvalue, rvalue),
-                            },
-                        );
-                        statements.push(statement);
-                        self.drain_temp_variables(fn_body.span(expression), temp_vars, statements);
+                        self.lower_statement(fn_body, initial_value, Some(lvalue), statements);
                     }
                     None => {}
                 }
 
-                self.bring_into_scope(mir_variable);
-
                 // Body of the let
-                self.lower_statement(fn_body, body, statements);
-
-                self.restore_scope(saved_scope);
+                self.lower_statement(fn_body, body, destination, statements);
 
                 // End the variable scope
-                let statement = self.add(
-                    fn_body.span(expression),
-                    mir::StatementData {
-                        kind: mir::StatementKind::StorageDead(mir_variable),
+                self.storage_dead(fn_body.span(expression), mir_variable, statements);
+            }
+            hir::ExpressionData::If {
+                condition,
+                if_true,
+                if_false,
+            } => {
+                let (condition, temp_vars) =
+                    self.lower_inspected_operand(fn_body, condition, statements);
+
+                let true_block = self.new_basic_block(fn_body.span(if_true));
+                let false_block = self.new_basic_block(fn_body.span(if_false));
+                let join_block = self.new_basic_block(fn_body.span(expression));
+
+                self.terminate_block(
+                    statements,
+                    mir::Terminator::If {
+                        condition,
+                        if_true: true_block,
+                        if_false: false_block,
                     },
                 );
+
+                for (block, branch) in vec![(true_block, if_true), (false_block, if_false)] {
+                    self.current_block = block;
+                    self.lower_statement(fn_body, branch, destination, statements);
+                    self.terminate_block(statements, mir::Terminator::Goto(join_block));
+                }
+
+                // Both branches come back together, so the rest of the
+                // code goes into the join block.
+                self.current_block = join_block;
+                self.drain_temp_variables(fn_body.span(expression), temp_vars, statements);
+            }
+            hir::ExpressionData::Binary {
+                operator,
+                left,
+                right,
+            } if operator == hir::BinaryOperator::And || operator == hir::BinaryOperator::Or => {
+                // The right operand is only evaluated if the left one
+                // does not already decide the result, so these are
+                // lowered like `if left { right } else { false }` and
+                // `if left { true } else { right }`.
+                let (condition, temp_vars) =
+                    self.lower_inspected_operand(fn_body, left, statements);
+
+                let right_block = self.new_basic_block(fn_body.span(right));
+                let decided_block = self.new_basic_block(fn_body.span(left));
+                let join_block = self.new_basic_block(fn_body.span(expression));
+
+                let (if_true, if_false, decided_value) = match operator {
+                    hir::BinaryOperator::And => (right_block, decided_block, LangItem::False),
+                    _ => (decided_block, right_block, LangItem::True),
+                };
+                self.terminate_block(
+                    statements,
+                    mir::Terminator::If {
+                        condition,
+                        if_true,
+                        if_false,
+                    },
+                );
+
+                self.current_block = right_block;
+                self.lower_statement(fn_body, right, destination, statements);
+                self.terminate_block(statements, mir::Terminator::Goto(join_block));
+
+                self.current_block = decided_block;
+                let span = fn_body.span(left);
+                let entity = EntityData::LangItem(decided_value).intern(self.db);
+                let place = self.add(span, mir::PlaceData::Entity(entity));
+                let operand = self.add(span, mir::OperandData::Copy(place));
+                let rvalue = self.add(span, mir::RvalueData::Use(operand));
+                let kind = match destination {
+                    Some(lvalue) => mir::StatementKind::Assign(lvalue, rvalue),
+                    None => mir::StatementKind::Expression(rvalue),
+                };
+                let statement = self.add(span, mir::StatementData { kind });
                 statements.push(statement);
+                self.terminate_block(statements, mir::Terminator::Goto(join_block));
+
+                self.current_block = join_block;
+                self.drain_temp_variables(fn_body.span(expression), temp_vars, statements);
             }
-            x => unimplemented!("Expression kind not yet support in MIR: {:#?}", x),
-        }
-    }
+            hir::ExpressionData::Match { scrutinee, arms } => {
+                // The arms read the fields of the scrutinee, so it
+                // needs a place (but matching on it does not move it).
+                let (scrutinee_place, temp_vars) = match fn_body.tables[scrutinee] {
+                    hir::ExpressionData::Place { place } => {
+                        self.lower_place(fn_body, place, statements)
+                    }
+                    _ => self.lower_into_temporary_place(fn_body, scrutinee, statements),
+                };
+                let discriminant = self.add(
+                    fn_body.span(scrutinee),
+                    mir::OperandData::Copy(scrutinee_place),
+                );
 
-    fn lower_basic_block(
-        &mut self,
-        fn_body: &hir::FnBody,
-        expression: hir::Expression,
-    ) -> mir::BasicBlock {
-        let mut statements = vec![];
-        self.lower_statement(fn_body, expression, &mut statements);
+                let arm_blocks: Vec<_> = arms
+                    .iter(fn_body)
+                    .map(|arm| self.new_basic_block(fn_body.span(arm)))
+                    .collect();
+                let join_block = self.new_basic_block(fn_body.span(expression));
 
-        let statements = mir::List::from_iterator(&mut self.fn_bytecode_tables, statements);
+                // Each variant goes to the first arm that matches it.
+                let variants = match self.ty(scrutinee).base.untern(self.db).kind {
+                    BaseKind::Named(entity) => self.members_of_kind(entity, MemberKind::Variant),
+                    BaseKind::Placeholder(_) | BaseKind::Error => vec![],
+                };
+                let targets = variants
+                    .iter()
+                    .map(|variant| {
+                        let target = arms
+                            .iter_data(fn_body)
+                            .zip(&arm_blocks)
+                            .filter(|(arm_data, _)| match fn_body.tables[arm_data.pattern] {
+                                hir::PatternData::Variant { entity, .. } => {
+                                    entity == variant.entity
+                                }
+                                hir::PatternData::Wildcard | hir::PatternData::Error { .. } => true,
+                            })
+                            .map(|(_, &block)| block)
+                            .next()
+                            .unwrap_or(join_block);
+                        (variant.entity, target)
+                    })
+                    .collect();
 
-        //FIXME: Fix this span once we nail down terminators
-        self.add(
-            fn_body.span(expression),
-            mir::BasicBlockData {
-                statements,
-                terminator: mir::Terminator::Return,
+                self.terminate_block(
+                    statements,
+                    mir::Terminator::Switch {
+                        discriminant,
+                        targets,
+                    },
+                );
+
+                for (arm_data, block) in arms.iter_data(fn_body).zip(arm_blocks) {
+                    self.current_block = block;
+
+                    let mut bound_variables = vec![];
+                    if let hir::PatternData::Variant { entity, bindings } =
+                        fn_body.tables[arm_data.pattern]
+                    {
+                        let fields = self.members_of_kind(entity, MemberKind::Field);
+                        for (variable, field) in bindings.iter(fn_body).zip(fields) {
+                            let span = fn_body.span(variable);
+                            let mir_variable = self.lower_variable(fn_body, variable);
+
+                            // Start the variable scope
+                            self.storage_live(span, mir_variable, statements);
+
+                            // Initialize it from the field of the payload
+                            let name = self.add(span, mir::IdentifierData { text: field.name });
+                            let field_place = self.add(
+                                span,
+                                mir::PlaceData::VariantField {
+                                    owner: scrutinee_place,
+                                    variant: entity,
+                                    name,
+                                },
+                            );
+                            let operand = if self.moves_on_use(variable) {
+                                mir::OperandData::Move(field_place)
+                            } else {
+                                mir::OperandData::Copy(field_place)
+                            };
+                            let operand = self.add(span, operand);
+                            let rvalue = self.add(span, mir::RvalueData::Use(operand));
+                            let lvalue = self.add(span, mir::PlaceData::Variable(mir_variable));
+                            let statement = self.add(
+                                span,
+                                mir::StatementData {
+                                    kind: mir::StatementKind::Assign(lvalue, rvalue),
+                                },
+                            );
+                            statements.push(statement);
+
+                            bound_variables.push(mir_variable);
+                        }
+                    }
+
+                    self.lower_statement(fn_body, arm_data.body, destination, statements);
+
+                    // End the variable scopes
+                    for mir_variable in bound_variables.into_iter().rev() {
+                        self.storage_dead(fn_body.span(arm_data.body), mir_variable, statements);
+                    }
+
+                    self.terminate_block(statements, mir::Terminator::Goto(join_block));
+                }
+
+                // All the arms come back together, so the rest of the
+                // code goes into the join block.
+                self.current_block = join_block;
+                self.drain_temp_variables(fn_body.span(expression), temp_vars, statements);
+            }
+            hir::ExpressionData::While { condition, body } => {
+                let condition_block = self.new_basic_block(fn_body.span(condition));
+                let body_block = self.new_basic_block(fn_body.span(body));
+                let exit_block = self.new_basic_block(fn_body.span(expression));
+
+                self.terminate_block(statements, mir::Terminator::Goto(condition_block));
+                self.lowered_loops.push(mir::Loop {
+                    header: condition_block,
+                    exit: exit_block,
+                });
+
+                // The condition is evaluated before each iteration.
+                self.current_block = condition_block;
+                let (condition, temp_vars) =
+                    self.lower_inspected_operand(fn_body, condition, statements);
+                let done_block = self.new_basic_block(fn_body.span(expression));
+                self.terminate_block(
+                    statements,
+                    mir::Terminator::If {
+                        condition,
+                        if_true: body_block,
+                        if_false: done_block,
+                    },
+                );
+
+                // The temporaries of the condition end on both edges
+                // out of it, rather than in `exit_block`: a `break`
+                // also goes there, after they have already ended.
+                self.current_block = done_block;
+                self.drain_temp_variables(fn_body.span(expression), temp_vars.clone(), statements);
+                self.terminate_block(statements, mir::Terminator::Goto(exit_block));
+
+                // The body jumps back to the condition when it is done.
+                self.current_block = body_block;
+                self.drain_temp_variables(fn_body.span(expression), temp_vars, statements);
+                self.loops.push(LoopScope {
+                    continue_block: condition_block,
+                    break_block: exit_block,
+                    live_variables: self.live_variables.len(),
+                });
+                self.lower_statement(fn_body, body, None, statements);
+                self.loops.pop();
+                self.terminate_block(statements, mir::Terminator::Goto(condition_block));
+
+                self.current_block = exit_block;
+            }
+            hir::ExpressionData::For {
+                variable,
+                list,
+                body,
+            } => {
+                let span = fn_body.span(expression);
+
+                // The list is evaluated once and then indexed by a
+                // hidden counter, as if the loop were
+                // `let i = 0; while i < list.len() { let x = list[i]; i = i + 1; body }`.
+                let (list_place, list_temp_vars) = match fn_body.tables[list] {
+                    hir::ExpressionData::Place { place } => {
+                        self.lower_place(fn_body, place, statements)
+                    }
+                    _ => self.lower_into_temporary_place(fn_body, list, statements),
+                };
+
+                let uint_ty = self.primitive_ty(LangItem::Uint);
+                let counter = self.create_temporary(span, uint_ty);
+                self.storage_live(span, counter, statements);
+                let counter_place = self.add(span, mir::PlaceData::Variable(counter));
+                let zero = self.add(span, mir::OperandData::ConstantUint(0));
+                let zero = self.add(span, mir::RvalueData::Use(zero));
+                self.assign(span, counter_place, zero, statements);
+
+                let condition_block = self.new_basic_block(fn_body.span(list));
+                let body_block = self.new_basic_block(fn_body.span(body));
+                let exit_block = self.new_basic_block(span);
+
+                self.terminate_block(statements, mir::Terminator::Goto(condition_block));
+                self.lowered_loops.push(mir::Loop {
+                    header: condition_block,
+                    exit: exit_block,
+                });
+
+                // Each iteration starts by comparing the counter with
+                // the length of the list.
+                self.current_block = condition_block;
+                let length = self.create_temporary(span, uint_ty);
+                self.storage_live(span, length, statements);
+                let length_place = self.add(span, mir::PlaceData::Variable(length));
+                let list_operand = self.add(span, mir::OperandData::Copy(list_place));
+                let arguments =
+                    mir::List::from_iterator(&mut self.fn_bytecode_tables, vec![list_operand]);
+                let list_len = EntityData::LangItem(LangItem::ListLen).intern(self.db);
+                let call = self.add(span, mir::RvalueData::Call(list_len, arguments));
+                self.assign(span, length_place, call, statements);
+
+                let boolean_ty = self.primitive_ty(LangItem::Boolean);
+                let in_bounds = self.create_temporary(span, boolean_ty);
+                self.storage_live(span, in_bounds, statements);
+                let in_bounds_place = self.add(span, mir::PlaceData::Variable(in_bounds));
+                let counter_operand = self.add(span, mir::OperandData::Copy(counter_place));
+                let length_operand = self.add(span, mir::OperandData::Copy(length_place));
+                let comparison = self.add(
+                    span,
+                    mir::RvalueData::BinaryOp(mir::BinOp::Lt, counter_operand, length_operand),
+                );
+                self.assign(span, in_bounds_place, comparison, statements);
+
+                let condition = self.add(span, mir::OperandData::Copy(in_bounds_place));
+                let temp_vars = vec![length, in_bounds];
+                let done_block = self.new_basic_block(span);
+                self.terminate_block(
+                    statements,
+                    mir::Terminator::If {
+                        condition,
+                        if_true: body_block,
+                        if_false: done_block,
+                    },
+                );
+
+                // As for `while`, the temporaries of the comparison end
+                // on both edges out of it.
+                self.current_block = done_block;
+                self.drain_temp_variables(span, temp_vars.clone(), statements);
+                self.terminate_block(statements, mir::Terminator::Goto(exit_block));
+
+                // The body binds the element to the variable and
+                // advances the counter before running, so `continue`
+                // can go straight back to the comparison.
+                self.current_block = body_block;
+                self.drain_temp_variables(span, temp_vars, statements);
+                self.loops.push(LoopScope {
+                    continue_block: condition_block,
+                    break_block: exit_block,
+                    live_variables: self.live_variables.len(),
+                });
+
+                let variable_span = fn_body.span(variable);
+                let mir_variable = self.lower_variable(fn_body, variable);
+                self.storage_live(variable_span, mir_variable, statements);
+                let variable_place =
+                    self.add(variable_span, mir::PlaceData::Variable(mir_variable));
+                let index = self.add(span, mir::OperandData::Copy(counter_place));
+                let element_place = self.add(
+                    variable_span,
+                    mir::PlaceData::Index {
+                        owner: list_place,
+                        index,
+                    },
+                );
+
+                // The element stays in the list, so it is copied (or
+                // shared) rather than moved out.
+                let element = self.add(variable_span, mir::OperandData::Copy(element_place));
+                let element = self.add(variable_span, mir::RvalueData::Use(element));
+                self.assign(variable_span, variable_place, element, statements);
+
+                let counter_operand = self.add(span, mir::OperandData::Copy(counter_place));
+                let one = self.add(span, mir::OperandData::ConstantUint(1));
+                let next = self.add(
+                    span,
+                    mir::RvalueData::BinaryOp(mir::BinOp::Add, counter_operand, one),
+                );
+                self.assign(span, counter_place, next, statements);
+
+                self.lower_statement(fn_body, body, None, statements);
+                self.storage_dead(variable_span, mir_variable, statements);
+                self.loops.pop();
+                self.terminate_block(statements, mir::Terminator::Goto(condition_block));
+
+                self.current_block = exit_block;
+                self.storage_dead(span, counter, statements);
+                self.drain_temp_variables(span, list_temp_vars, statements);
+            }
+            hir::ExpressionData::Break {} | hir::ExpressionData::Continue {} => {
+                // The parser only accepts these inside of a loop.
+                let loop_scope = self.loops.last().unwrap();
+                let live_variables = loop_scope.live_variables;
+                let target = match fn_body.tables[expression] {
+                    hir::ExpressionData::Break {} => loop_scope.break_block,
+                    _ => loop_scope.continue_block,
+                };
+                self.lower_jump(
+                    fn_body.span(expression),
+                    live_variables,
+                    mir::Terminator::Goto(target),
+                    statements,
+                );
+            }
+            hir::ExpressionData::Assignment { place, value } => match fn_body.tables[place] {
+                hir::PlaceData::Variable(_) => {
+                    let (place, temp_vars) = self.lower_place(fn_body, place, statements);
+                    self.lower_statement(fn_body, value, Some(place), statements);
+                    self.drain_temp_variables(fn_body.span(expression), temp_vars, statements);
+                }
+                _ => {
+                    self.report_unimplemented(
+                        fn_body.span(place),
+                        "only local variables can be assigned to",
+                    );
+                }
             },
-        )
+            hir::ExpressionData::Return { value } => {
+                // The value of the function is the value of the last
+                // statement, so end with one that uses `value`. A bare
+                // `return` has no value to give back.
+                match fn_body.tables[value] {
+                    hir::ExpressionData::Unit {} => {}
+                    _ => {
+                        let (operand, temp_vars) = self.lower_operand(fn_body, value, statements);
+                        let rvalue = self.add(fn_body.span(value), mir::RvalueData::Use(operand));
+                        let statement = self.add(
+                            fn_body.span(value),
+                            mir::StatementData {
+                                kind: mir::StatementKind::Expression(rvalue),
+                            },
+                        );
+                        statements.push(statement);
+                        self.drain_temp_variables(fn_body.span(expression), temp_vars, statements);
+                    }
+                }
+
+                self.lower_jump(
+                    fn_body.span(expression),
+                    0,
+                    mir::Terminator::Return,
+                    statements,
+                );
+            }
+            _ => {
+                let (rvalue, temp_vars) = self.lower_rvalue(fn_body, expression, statements);
+                let kind = match destination {
+                    Some(lvalue) => mir::StatementKind::Assign(lvalue, rvalue),
+                    None => mir::StatementKind::Expression(rvalue),
+                };
+                let statement = self.add(fn_body.span(expression), mir::StatementData { kind });
+
+                statements.push(statement);
+                self.drain_temp_variables(fn_body.span(expression), temp_vars, statements);
+            }
+        }
     }
 
     fn lower_arguments(&mut self, fn_body: &hir::FnBody) -> Vec<mir::Variable> {
@@ -429,17 +1401,20 @@ where
         let fn_body = self.db.fn_body(self.item_entity).value;
         let arguments = self.lower_arguments(&fn_body);
 
-        for argument in &arguments {
-            self.bring_into_scope(*argument);
-        }
+        let entry_block = self.new_basic_block(fn_body.span(fn_body.root_expression));
+        assert_eq!(entry_block, self.current_block);
 
-        let basic_blocks = vec![self.lower_basic_block(&fn_body, fn_body.root_expression)];
+        let mut statements = vec![];
+        self.lower_statement(&fn_body, fn_body.root_expression, None, &mut statements);
+        self.terminate_block(&mut statements, mir::Terminator::Return);
 
+        let basic_blocks = std::mem::replace(&mut self.basic_blocks, vec![]);
         let mir_basic_blocks = mir::List::from_iterator(&mut self.fn_bytecode_tables, basic_blocks);
         let mir_arguments = mir::List::from_iterator(&mut self.fn_bytecode_tables, arguments);
 
         mir::FnBytecode {
             basic_blocks: mir_basic_blocks,
+            loops: self.lowered_loops,
             tables: self.fn_bytecode_tables,
             arguments: mir_arguments,
         }
@@ -7,15 +7,22 @@
 
 use lark_debug_derive::DebugWith;
 use lark_entity::Entity;
+use lark_error::ErrorReported;
+use lark_error::ErrorSentinel;
 use lark_error::WithError;
 use lark_indices::{IndexVec, U32Index};
 use lark_span::{FileName, Span, Spanned};
 use lark_string::GlobalIdentifier;
+use lark_ty::base_inferred::BaseInferred;
 use lark_ty::declaration::DeclarationTables;
+use lark_ty::Ty;
 use lark_type_check as typecheck;
 use std::sync::Arc;
 
+mod const_eval;
 mod fn_bytecode;
+pub mod ops;
+mod ownership_check;
 
 salsa::query_group! {
     pub trait MirDatabase: typecheck::TypeCheckDatabase + AsRef<DeclarationTables> {
@@ -23,9 +30,34 @@ salsa::query_group! {
             type FnBytecodeQuery;
             use fn fn_bytecode::fn_bytecode;
         }
+
+        /// Checks that the bytecode for a fn body never uses a value
+        /// after it has been moved, or a variable whose storage is
+        /// dead.
+        fn ownership_check(key: Entity) -> WithError<()> {
+            type OwnershipCheckQuery;
+            use fn ownership_check::ownership_check;
+        }
+
+        /// The value of the `const` item `key`, computed at compile
+        /// time. Uses of the constant are replaced by this value when
+        /// lowering to bytecode.
+        fn const_value(key: Entity) -> WithError<Result<ConstValue, ErrorReported>> {
+            type ConstValueQuery;
+            use fn const_eval::const_value;
+        }
     }
 }
 
+/// The value of a `const` item.
+#[derive(Clone, Debug, DebugWith, PartialEq, Eq, Hash)]
+pub enum ConstValue {
+    Bool(bool),
+    Int(i32),
+    Uint(u32),
+    String(String),
+}
+
 lark_indices::index_type! {
     pub struct Error { .. }
 }
@@ -61,6 +93,9 @@ pub struct FnBytecodeTables {
     /// Map each operand index to its associated data.
     pub operands: IndexVec<Operand, Spanned<OperandData, FileName>>,
 
+    /// Errors we encountered lowering the hir
+    pub errors: IndexVec<Error, Spanned<ErrorData, FileName>>,
+
     /// The data values for any `List<I>` values that appear elsewhere
     /// in the HIR; the way this works is that all of the list value
     /// are concatenated into one big vector, and each list just pulls
@@ -83,12 +118,63 @@ pub struct FnBytecode {
     /// is given by the function signature (which can be separately queried).
     pub arguments: List<Variable>,
 
-    /// The code of the function body, split into basic blocks
+    /// The code of the function body, split into basic blocks. The
+    /// first block is the entry block.
     pub basic_blocks: List<BasicBlock>,
 
+    /// The loops of the function body (from `while` and `for`), in the
+    /// order in which they start. Backends that have no `goto` use
+    /// these to rebuild structured loops from the basic blocks.
+    pub loops: Vec<Loop>,
+
     pub tables: FnBytecodeTables,
 }
 
+/// A loop of a function body.
+#[derive(Copy, Clone, Debug, DebugWith, PartialEq, Eq, Hash)]
+pub struct Loop {
+    /// The block that starts each iteration, which `continue` jumps to.
+    pub header: BasicBlock,
+
+    /// The block that follows the loop, which `break` jumps to.
+    pub exit: BasicBlock,
+}
+
+impl<DB> ErrorSentinel<&DB> for FnBytecode
+where
+    DB: ?Sized,
+{
+    fn error_sentinel(_db: &DB, err: ErrorReported) -> Self {
+        let mut tables = FnBytecodeTables::default();
+        let span = err.span();
+        let error = tables.add(span, ErrorData::Misc);
+        let operand = tables.add(span, OperandData::Error(error));
+        let rvalue = tables.add(span, RvalueData::Use(operand));
+        let statement = tables.add(
+            span,
+            StatementData {
+                kind: StatementKind::Expression(rvalue),
+            },
+        );
+        let statements = List::from_iterator(&mut tables, vec![statement]);
+        let basic_block = tables.add(
+            span,
+            BasicBlockData {
+                statements,
+                terminator: Terminator::Return,
+            },
+        );
+        let basic_blocks = List::from_iterator(&mut tables, vec![basic_block]);
+
+        FnBytecode {
+            arguments: List::default(),
+            basic_blocks,
+            loops: vec![],
+            tables,
+        }
+    }
+}
+
 lark_indices::index_type! {
     pub struct BasicBlock { .. }
 }
@@ -123,8 +209,48 @@ pub enum StatementKind {
 
 #[derive(Clone, Debug, DebugWith, PartialEq, Eq, Hash)]
 pub enum Terminator {
+    /// Return from the function. The value of the function is the
+    /// value of the last statement that was executed. Besides the end
+    /// of the function, this is used for `return` expressions, which
+    /// evaluate their value as the last statement before returning.
     Return,
-    PassThrough,
+
+    /// Continue execution in the given block.
+    Goto(BasicBlock),
+
+    /// Evaluate `condition`, which must be a boolean, and continue in
+    /// `if_true` or `if_false` accordingly.
+    If {
+        condition: Operand,
+        if_true: BasicBlock,
+        if_false: BasicBlock,
+    },
+
+    /// Evaluate `discriminant`, which must be a value of some enum,
+    /// and continue in the target for its variant. There is one
+    /// `(variant, target)` pair for each variant of the enum, in the
+    /// order in which the variants are declared.
+    Switch {
+        discriminant: Operand,
+        targets: Vec<(Entity, BasicBlock)>,
+    },
+}
+
+impl Terminator {
+    /// The blocks that execution may continue in after this
+    /// terminator.
+    pub fn successors(&self) -> Vec<BasicBlock> {
+        match *self {
+            Terminator::Return => vec![],
+            Terminator::Goto(target) => vec![target],
+            Terminator::If {
+                if_true, if_false, ..
+            } => vec![if_true, if_false],
+            Terminator::Switch { ref targets, .. } => {
+                targets.iter().map(|&(_, target)| target).collect()
+            }
+        }
+    }
 }
 
 lark_indices::index_type! {
@@ -133,7 +259,8 @@ lark_indices::index_type! {
 #[derive(Clone, Debug, DebugWith, PartialEq, Eq, Hash)]
 pub enum RvalueData {
     Use(Operand),
-    BinaryOp(BinOp, Variable, Variable),
+    BinaryOp(BinOp, Operand, Operand),
+    UnaryOp(UnOp, Operand),
     //FIXME: MIR has this as a TerminatorData, presumably because stack can unwind
     Call(Entity, List<Operand>),
     Aggregate(Entity, List<Operand>),
@@ -148,13 +275,36 @@ pub enum OperandData {
     Move(Place),
     //FIXME: Move to Box<Constant>
     ConstantInt(i32),
+    ConstantUint(u32),
     ConstantString(String),
+
+    /// Stands in for an expression that could not be lowered.
+    Error(Error),
 }
 
-#[derive(Clone, Debug, DebugWith, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, DebugWith, PartialEq, Eq, Hash)]
 pub enum BinOp {
     Add,
     Sub,
+    Mul,
+    Div,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+
+    /// `+` on strings
+    Concat,
+}
+
+#[derive(Copy, Clone, Debug, DebugWith, PartialEq, Eq, Hash)]
+pub enum UnOp {
+    Not,
+
+    /// `-` on signed integers
+    Neg,
 }
 
 /// Trait implemented by the various kinds of indices that reach into
@@ -227,7 +377,27 @@ lark_indices::index_type! {
 pub enum PlaceData {
     Variable(Variable),
     Entity(Entity),
-    Field { owner: Place, name: Identifier },
+    Field {
+        owner: Place,
+        name: Identifier,
+    },
+
+    /// The field `name` of the payload of `owner`, which must be a
+    /// value of the enum variant `variant`.
+    VariantField {
+        owner: Place,
+        variant: Entity,
+        name: Identifier,
+    },
+
+    /// The element of the list `owner` at position `index`.
+    Index {
+        owner: Place,
+        index: Operand,
+    },
+
+    /// Stands in for a place that could not be lowered.
+    Error(Error),
 }
 
 #[derive(Copy, Clone, Debug, DebugWith, PartialEq, Eq, Hash)]
@@ -242,6 +412,9 @@ lark_indices::index_type! {
 #[derive(Copy, Clone, Debug, DebugWith, PartialEq, Eq, Hash)]
 pub struct VariableData {
     pub name: Identifier,
+
+    /// The type of the variable, as inferred by the type checker.
+    pub ty: Ty<BaseInferred>,
 }
 
 lark_indices::index_type! {
@@ -264,9 +437,19 @@ impl FnBytecode {
     pub fn span(&self, index: impl SpanIndex) -> Span<FileName> {
         index.span_from(&self.tables)
     }
+
+    /// The block where execution of the function begins.
+    pub fn entry_block(&self) -> BasicBlock {
+        self.basic_blocks.iter(self).next().unwrap()
+    }
 }
 
 impl FnBytecodeTables {
+    /// Adds `node` to the appropriate table, returning its index.
+    pub fn add<D: MirIndexData>(&mut self, span: Span<FileName>, node: D) -> D::Index {
+        D::index_vec_mut(self).push(Spanned::new(node, span))
+    }
+
     /// Get the span for the given part of the HIR.
     pub fn span(&self, index: impl SpanIndex) -> Span<FileName> {
         index.span_from(self)
@@ -341,6 +524,7 @@ define_meta_index! {
     (Identifier, IdentifierData, identifiers),
     (Operand, OperandData, operands),
     (Rvalue, RvalueData, rvalues),
+    (Error, ErrorData, errors),
 }
 
 /// A list of "MIR indices" of type `I`.
@@ -0,0 +1,92 @@
+//! The meaning of the integer operators, shared by everything that
+//! evaluates them: the constant evaluator and the interpreter. When an
+//! operation fails (overflow, division by zero), these return the
+//! message to report.
+
+use crate::BinOp;
+use std::cmp::Ordering;
+
+/// The integer types that arithmetic applies to.
+pub trait Integer: Copy + Ord {
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn checked_div(self, other: Self) -> Option<Self>;
+    fn is_zero(self) -> bool;
+}
+
+macro_rules! integer_impls {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, other)
+                }
+
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, other)
+                }
+
+                fn checked_div(self, other: Self) -> Option<Self> {
+                    <$t>::checked_div(self, other)
+                }
+
+                fn is_zero(self) -> bool {
+                    self == 0
+                }
+            }
+        )*
+    };
+}
+
+integer_impls!(i32, u32);
+
+/// Applies one of the arithmetic operators (`+`, `-`, `*`, `/`) to
+/// two integers.
+pub fn checked_arithmetic<T: Integer>(op: BinOp, left: T, right: T) -> Result<T, String> {
+    let (result, operation) = match op {
+        BinOp::Add => (left.checked_add(right), "add"),
+        BinOp::Sub => (left.checked_sub(right), "subtract"),
+        BinOp::Mul => (left.checked_mul(right), "multiply"),
+        BinOp::Div => {
+            if right.is_zero() {
+                return Err("attempt to divide by zero".to_string());
+            }
+            (left.checked_div(right), "divide")
+        }
+        BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge | BinOp::Concat => {
+            panic!("`{:?}` is not an arithmetic operator", op)
+        }
+    };
+
+    result.ok_or_else(|| format!("attempt to {} with overflow", operation))
+}
+
+/// Applies one of the ordering comparisons (`<`, `<=`, `>`, `>=`).
+pub fn compare<T: Ord>(op: BinOp, left: &T, right: &T) -> bool {
+    let ordering = left.cmp(right);
+    match op {
+        BinOp::Lt => ordering == Ordering::Less,
+        BinOp::Le => ordering != Ordering::Greater,
+        BinOp::Gt => ordering == Ordering::Greater,
+        BinOp::Ge => ordering != Ordering::Less,
+        BinOp::Add
+        | BinOp::Sub
+        | BinOp::Mul
+        | BinOp::Div
+        | BinOp::Eq
+        | BinOp::Ne
+        | BinOp::Concat => panic!("`{:?}` is not an ordering comparison", op),
+    }
+}
+
+/// Applies unary `-` to an integer.
+pub fn checked_negate(value: i32) -> Result<i32, String> {
+    value
+        .checked_neg()
+        .ok_or_else(|| "attempt to negate with overflow".to_string())
+}
@@ -0,0 +1,302 @@
+use crate as mir;
+use crate::MirDatabase;
+use lark_collections::FxIndexSet;
+use lark_entity::Entity;
+use lark_error::Diagnostic;
+use lark_error::WithError;
+use lark_indices::IndexVec;
+use lark_intern::Untern;
+use lark_string::GlobalIdentifier;
+
+/// Checks the bytecode for `item_entity`, reporting every place where
+/// a value is used after it was moved and every use of a variable
+/// whose storage is dead.
+///
+/// This is a forward dataflow analysis: we first compute, for the
+/// start of each basic block, which paths *may* have been moved and
+/// which variables *may* be dead by then (along any path through the
+/// function), and then walk each block once more to report the uses
+/// that conflict with that state.
+crate fn ownership_check(db: &impl MirDatabase, item_entity: Entity) -> WithError<()> {
+    let fn_bytecode = db.fn_bytecode(item_entity).into_value();
+    let mut checker = OwnershipChecker {
+        db,
+        fn_bytecode: &fn_bytecode,
+        errors: vec![],
+    };
+
+    let entry_states = checker.compute_entry_states();
+
+    // Now that the states have reached a fixed point, walk each block
+    // once more, this time reporting errors.
+    for basic_block in fn_bytecode.basic_blocks.iter(&fn_bytecode) {
+        if let Some(state) = &entry_states[basic_block] {
+            let mut state = state.clone();
+            checker.apply_block(basic_block, &mut state, true);
+        }
+    }
+
+    WithError {
+        value: (),
+        errors: checker.errors,
+    }
+}
+
+/// A path to (part of) the value in a variable, like `p` or
+/// `p.first`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct MovePath {
+    variable: mir::Variable,
+    fields: Vec<GlobalIdentifier>,
+}
+
+impl MovePath {
+    /// True if `self` is `other` or a part of it (e.g., `p.first`
+    /// is part of `p`).
+    fn starts_with(&self, other: &MovePath) -> bool {
+        self.variable == other.variable && self.fields.starts_with(&other.fields)
+    }
+
+    /// True if `self` and `other` share some of their data.
+    fn overlaps(&self, other: &MovePath) -> bool {
+        self.starts_with(other) || other.starts_with(self)
+    }
+}
+
+/// What we know at some point in the function.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct OwnershipState {
+    /// Paths that may have been moved out of.
+    moved: FxIndexSet<MovePath>,
+
+    /// Variables whose storage may be dead.
+    dead: FxIndexSet<mir::Variable>,
+}
+
+impl OwnershipState {
+    /// Merges in the state from another predecessor; returns true if
+    /// anything changed.
+    fn join(&mut self, other: &OwnershipState) -> bool {
+        let len_before = (self.moved.len(), self.dead.len());
+        self.moved.extend(other.moved.iter().cloned());
+        self.dead.extend(other.dead.iter().cloned());
+        len_before != (self.moved.len(), self.dead.len())
+    }
+
+    /// Forgets about any moves out of `path` (or its parts), because
+    /// it has been given a new value.
+    fn reinitialize(&mut self, path: &MovePath) {
+        self.moved.retain(|moved| !moved.starts_with(path));
+    }
+}
+
+struct OwnershipChecker<'me, DB: MirDatabase> {
+    db: &'me DB,
+    fn_bytecode: &'me mir::FnBytecode,
+    errors: Vec<Diagnostic>,
+}
+
+impl<DB> OwnershipChecker<'me, DB>
+where
+    DB: MirDatabase,
+{
+    /// Computes the state at the start of each basic block that is
+    /// reachable from the entry block.
+    fn compute_entry_states(&mut self) -> IndexVec<mir::BasicBlock, Option<OwnershipState>> {
+        let fn_bytecode = self.fn_bytecode;
+        let mut entry_states = IndexVec::from_elem(None, &fn_bytecode.tables.basic_blocks);
+
+        // On entry, only the arguments have live storage.
+        let mut entry_state = OwnershipState::default();
+        entry_state
+            .dead
+            .extend(fn_bytecode.tables.variables.indices());
+        for argument in fn_bytecode.arguments.iter(fn_bytecode) {
+            entry_state.dead.remove(&argument);
+        }
+
+        let entry_block = fn_bytecode.entry_block();
+        entry_states[entry_block] = Some(entry_state);
+
+        let mut worklist = vec![entry_block];
+        while let Some(basic_block) = worklist.pop() {
+            let mut state = entry_states[basic_block].clone().unwrap();
+            self.apply_block(basic_block, &mut state, false);
+
+            for successor in fn_bytecode[basic_block].terminator.successors() {
+                let changed = if let Some(successor_state) = &mut entry_states[successor] {
+                    successor_state.join(&state)
+                } else {
+                    entry_states[successor] = Some(state.clone());
+                    true
+                };
+
+                if changed {
+                    worklist.push(successor);
+                }
+            }
+        }
+
+        entry_states
+    }
+
+    /// Updates `state` to reflect the effects of `basic_block`,
+    /// reporting conflicting uses if `report` is true.
+    fn apply_block(
+        &mut self,
+        basic_block: mir::BasicBlock,
+        state: &mut OwnershipState,
+        report: bool,
+    ) {
+        let fn_bytecode = self.fn_bytecode;
+        let basic_block_data = &fn_bytecode[basic_block];
+
+        for (statement, statement_data) in basic_block_data
+            .statements
+            .iter_enumerated_data(fn_bytecode)
+        {
+            match statement_data.kind {
+                mir::StatementKind::Assign(place, rvalue) => {
+                    self.apply_rvalue(rvalue, state, report);
+
+                    if let Some(path) = self.move_path(place) {
+                        if report && state.dead.contains(&path.variable) {
+                            self.report_dead(statement, place);
+                        }
+                        state.reinitialize(&path);
+                    }
+                }
+
+                mir::StatementKind::StorageLive(variable) => {
+                    state.dead.remove(&variable);
+                    state.reinitialize(&MovePath {
+                        variable,
+                        fields: vec![],
+                    });
+                }
+
+                mir::StatementKind::StorageDead(variable) => {
+                    state.dead.insert(variable);
+                }
+
+                mir::StatementKind::Expression(rvalue) => {
+                    self.apply_rvalue(rvalue, state, report);
+                }
+            }
+        }
+
+        match basic_block_data.terminator {
+            mir::Terminator::If { condition, .. } => {
+                self.apply_operand(condition, state, report);
+            }
+            mir::Terminator::Switch { discriminant, .. } => {
+                self.apply_operand(discriminant, state, report);
+            }
+            mir::Terminator::Return | mir::Terminator::Goto(_) => {}
+        }
+    }
+
+    fn apply_rvalue(&mut self, rvalue: mir::Rvalue, state: &mut OwnershipState, report: bool) {
+        let fn_bytecode = self.fn_bytecode;
+        let operands = match fn_bytecode[rvalue] {
+            mir::RvalueData::Use(operand) | mir::RvalueData::UnaryOp(_, operand) => vec![operand],
+            mir::RvalueData::BinaryOp(_, left, right) => vec![left, right],
+            mir::RvalueData::Call(_, arguments) | mir::RvalueData::Aggregate(_, arguments) => {
+                arguments.iter(fn_bytecode).collect()
+            }
+        };
+
+        for operand in operands {
+            self.apply_operand(operand, state, report);
+        }
+    }
+
+    fn apply_operand(&mut self, operand: mir::Operand, state: &mut OwnershipState, report: bool) {
+        let (place, is_move) = match self.fn_bytecode[operand] {
+            mir::OperandData::Copy(place) => (place, false),
+            mir::OperandData::Move(place) => (place, true),
+            mir::OperandData::ConstantInt(_)
+            | mir::OperandData::ConstantUint(_)
+            | mir::OperandData::ConstantString(_)
+            | mir::OperandData::Error(_) => return,
+        };
+
+        let path = match self.move_path(place) {
+            Some(path) => path,
+            None => return,
+        };
+
+        if report {
+            if state.dead.contains(&path.variable) {
+                self.report_dead(operand, place);
+            } else if state.moved.iter().any(|moved| moved.overlaps(&path)) {
+                let label = if is_move {
+                    format!("value `{}` was already moved", self.place_text(place))
+                } else {
+                    format!("use of moved value `{}`", self.place_text(place))
+                };
+                self.errors
+                    .push(Diagnostic::new(label, self.fn_bytecode.span(operand)));
+            }
+        }
+
+        if is_move {
+            state.moved.insert(path);
+        }
+    }
+
+    fn report_dead(&mut self, index: impl mir::SpanIndex, place: mir::Place) {
+        let label = format!(
+            "use of `{}` after its storage is dead",
+            self.place_text(place)
+        );
+        self.errors
+            .push(Diagnostic::new(label, self.fn_bytecode.span(index)));
+    }
+
+    /// The path of the data stored in `place`, if it is in a variable.
+    fn move_path(&self, place: mir::Place) -> Option<MovePath> {
+        match self.fn_bytecode[place] {
+            mir::PlaceData::Variable(variable) => Some(MovePath {
+                variable,
+                fields: vec![],
+            }),
+
+            mir::PlaceData::Field { owner, name }
+            | mir::PlaceData::VariantField { owner, name, .. } => {
+                let mut path = self.move_path(owner)?;
+                path.fields.push(self.fn_bytecode[name].text);
+                Some(path)
+            }
+
+            // We do not track the elements of a list separately, so
+            // moving one of them moves the whole list.
+            mir::PlaceData::Index { owner, .. } => self.move_path(owner),
+
+            mir::PlaceData::Entity(_) | mir::PlaceData::Error(_) => None,
+        }
+    }
+
+    /// The text of `place` for use in error messages, like `p.first`.
+    fn place_text(&self, place: mir::Place) -> String {
+        match self.fn_bytecode[place] {
+            mir::PlaceData::Variable(variable) => {
+                let name = self.fn_bytecode[variable].name;
+                self.fn_bytecode[name].text.untern(self.db).to_string()
+            }
+
+            mir::PlaceData::Field { owner, name }
+            | mir::PlaceData::VariantField { owner, name, .. } => format!(
+                "{}.{}",
+                self.place_text(owner),
+                self.fn_bytecode[name].text.untern(self.db)
+            ),
+
+            mir::PlaceData::Index { owner, .. } => format!("{}[..]", self.place_text(owner)),
+
+            mir::PlaceData::Entity(entity) => format!("{:?}", entity),
+
+            mir::PlaceData::Error(_) => String::from("<error>"),
+        }
+    }
+}
@@ -10,7 +10,8 @@ use lark_error::ErrorReported;
 use lark_error::WithError;
 use lark_hir as hir;
 use lark_seq::Seq;
-use lark_span::{FileName, Span};
+use lark_span::{FileName, Span, Spanned};
+use lark_string::GlobalIdentifier;
 use lark_ty as ty;
 use lark_ty::declaration::Declaration;
 use std::sync::Arc;
@@ -68,4 +69,12 @@ impl LazyParsedEntity for ParsedFile {
     ) -> WithError<hir::FnBody> {
         InvalidParsedEntity.parse_fn_body(entity, db)
     }
+
+    fn parse_use_path(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> Seq<Spanned<GlobalIdentifier, FileName>> {
+        InvalidParsedEntity.parse_use_path(entity, db)
+    }
 }
@@ -14,7 +14,12 @@ crate enum LexerState {
     StartIdent,
     ContinueIdent,
     StringLiteral,
+    StringEscape,
     Sigil,
+    SigilGreaterThan,
+    Minus,
+    ExclamationPoint,
+    Logical(char),
     Slash,
     Number,
     Comment(u32),
@@ -35,11 +40,14 @@ impl LexerDelegateTrait for LexerState {
             LexerState::Top => match c {
                 None => LexerNext::EOF,
                 Some(c) => match c {
-                    '/' => LexerNext::begin(Slash),
-                    c if UnicodeXID::is_xid_start(c) => LexerNext::begin(StartIdent),
+                    '/' => consume(c).and_transition(Slash),
+                    c if UnicodeXID::is_xid_start(c) || c == '_' => LexerNext::begin(StartIdent),
                     c if is_delimiter_sigil_char(c) => {
                         consume(c).and_emit(LexToken::Sigil).and_remain()
                     }
+                    '-' => consume(c).and_transition(Minus),
+                    '!' => consume(c).and_transition(ExclamationPoint),
+                    '&' | '|' => consume(c).and_transition(Logical(c)),
                     c if is_sigil_char(c) => {
                         LexerNext::begin(Sigil)
                         // LexerNext::dynamic_sigil(Token::Sigil)
@@ -53,27 +61,81 @@ impl LexerDelegateTrait for LexerState {
                 },
             },
 
+            // Just after a `/`, which starts a comment if another `/`
+            // or a `*` follows and is the division operator otherwise.
             LexerState::Slash => match c {
-                None => reconsume()
+                Some('/') => consume('/').and_transition(LexerState::EolComment),
+                Some('*') => consume('*').and_transition(LexerState::Comment(1)),
+                _ => reconsume()
                     .and_emit(LexToken::Sigil)
                     .and_transition(LexerState::Top),
-                Some('/') => reconsume().and_transition(LexerState::EolComment),
-                Some(c) => consume(c).and_transition(LexerState::Sigil),
             },
 
             LexerState::Sigil => match c {
                 None => reconsume()
                     .and_emit(LexToken::Sigil)
                     .and_transition(LexerState::Top),
-                Some(c) if is_delimiter_sigil_char(c) => reconsume()
+                Some(c) if is_delimiter_sigil_char(c) || starts_own_sigil(c) => reconsume()
                     .and_emit(LexToken::Sigil)
                     .and_transition(LexerState::Top),
+                Some('>') => consume('>').and_transition(LexerState::SigilGreaterThan),
                 Some(c) if is_sigil_char(c) => consume(c).and_remain(),
                 _ => reconsume()
                     .and_emit(LexToken::Sigil)
                     .and_transition(LexerState::Top),
             },
 
+            // Like `Sigil`, but just after a `>`. A second `>` starts
+            // a new sigil, so that nested generic arguments like
+            // `Vec<Vec<T>>` can be closed one at a time.
+            LexerState::SigilGreaterThan => match c {
+                None => reconsume()
+                    .and_emit(LexToken::Sigil)
+                    .and_transition(LexerState::Top),
+                Some(c) if is_delimiter_sigil_char(c) || c == '>' || starts_own_sigil(c) => {
+                    reconsume()
+                        .and_emit(LexToken::Sigil)
+                        .and_transition(LexerState::Top)
+                }
+                Some(c) if is_sigil_char(c) => consume(c).and_transition(LexerState::Sigil),
+                _ => reconsume()
+                    .and_emit(LexToken::Sigil)
+                    .and_transition(LexerState::Top),
+            },
+
+            // Just after a `-`, which is a sigil of its own (so that
+            // `x*-1` is `x * -1`), unless it starts a `->`.
+            LexerState::Minus => match c {
+                Some('>') => consume('>')
+                    .and_emit(LexToken::Sigil)
+                    .and_transition(LexerState::Top),
+                _ => reconsume()
+                    .and_emit(LexToken::Sigil)
+                    .and_transition(LexerState::Top),
+            },
+
+            // Just after a `!`, which is a sigil of its own (so that
+            // `a && !b` works without spaces), unless it starts a `!=`.
+            LexerState::ExclamationPoint => match c {
+                Some('=') => consume('=')
+                    .and_emit(LexToken::Sigil)
+                    .and_transition(LexerState::Top),
+                _ => reconsume()
+                    .and_emit(LexToken::Sigil)
+                    .and_transition(LexerState::Top),
+            },
+
+            // Just after a `&` or `|`, which pairs up only with another
+            // one of the same, so that `a&&!b` is `a && !b`.
+            LexerState::Logical(first) => match c {
+                Some(c) if c == *first => consume(c)
+                    .and_emit(LexToken::Sigil)
+                    .and_transition(LexerState::Top),
+                _ => reconsume()
+                    .and_emit(LexToken::Sigil)
+                    .and_transition(LexerState::Top),
+            },
+
             LexerState::Number => match c {
                 None => reconsume()
                     .and_emit(LexToken::Integer)
@@ -93,10 +155,20 @@ impl LexerDelegateTrait for LexerState {
                     '"' => consume(c)
                         .and_emit(LexToken::String)
                         .and_transition(LexerState::Top),
+                    '\\' => consume(c).and_transition(LexerState::StringEscape),
                     _ => consume(c).and_remain(),
                 },
             },
 
+            // The character after a `\` is part of the string, even
+            // if it is a `"`; escapes are interpreted by the parser.
+            LexerState::StringEscape => match c {
+                None => reconsume()
+                    .and_emit(LexToken::Error)
+                    .and_transition(LexerState::Top),
+                Some(c) => consume(c).and_transition(LexerState::StringLiteral),
+            },
+
             LexerState::StartIdent => match c {
                 None => LexerNext::emit(LexToken::Identifier, LexerState::Top),
                 Some(c) => match c {
@@ -188,14 +260,24 @@ impl LexerDelegateTrait for LexerState {
 
 fn is_sigil_char(c: char) -> bool {
     match c {
-        '{' | '}' | '(' | ')' | '+' | '-' | '*' | '/' | ':' | ',' | '>' | '<' | '=' | '.' => true,
+        '{' | '}' | '(' | ')' | '[' | ']' | '+' | '-' | '*' | '/' | ':' | ',' | '>' | '<' | '='
+        | '.' | '!' | '&' | '|' => true,
+        _ => false,
+    }
+}
+
+/// True for the characters that always start a new sigil, rather than
+/// continuing the one before them (so `==-` is `==` followed by `-`).
+fn starts_own_sigil(c: char) -> bool {
+    match c {
+        '-' | '!' | '&' | '|' => true,
         _ => false,
     }
 }
 
 fn is_delimiter_sigil_char(c: char) -> bool {
     match c {
-        '{' | '}' | '(' | ')' => true,
+        '{' | '}' | '(' | ')' | '[' | ']' | ',' => true,
         _ => false,
     }
 }
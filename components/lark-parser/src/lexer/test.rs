@@ -75,3 +75,79 @@ fn test_quicklex() -> Result<(), Span<CurrentFile>> {
 
     Ok(())
 }
+
+#[test]
+fn test_generic_arguments() -> Result<(), Span<CurrentFile>> {
+    let source = unindent(
+        r##"
+            Pair<A, B>,
+            00001234567 Identifier Sigil Identifier Sigil Whitespace Identifier Sigil Sigil
+            Vec<Vec<T>>
+            00012223456 Identifier Sigil Identifier Sigil Identifier Sigil Sigil
+            a->b>=c
+            0112334 Identifier Sigil Identifier Sigil Identifier
+            "##,
+    );
+
+    process(&source)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_division() -> Result<(), Span<CurrentFile>> {
+    let source = unindent(
+        r##"
+            a / b
+            01234 Identifier Whitespace Sigil Whitespace Identifier
+            a/b // c
+            01234444 Identifier Sigil Identifier Whitespace Comment
+            "##,
+    );
+
+    process(&source)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_minus() -> Result<(), Span<CurrentFile>> {
+    let source = unindent(
+        r##"
+            x*-1
+            0123 Identifier Sigil Sigil Integer
+            a==-1
+            01123 Identifier Sigil Sigil Integer
+            a - -1
+            012345 Identifier Whitespace Sigil Whitespace Sigil Integer
+            f()->int
+            01233444 Identifier Sigil Sigil Sigil Identifier
+            "##,
+    );
+
+    process(&source)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_logical_operators() -> Result<(), Span<CurrentFile>> {
+    let source = unindent(
+        r##"
+            a&&!b
+            01123 Identifier Sigil Sigil Identifier
+            a||!b
+            01123 Identifier Sigil Sigil Identifier
+            a!=b
+            0112 Identifier Sigil Identifier
+            !!a
+            012 Sigil Sigil Identifier
+            a&&-1
+            01123 Identifier Sigil Sigil Integer
+            "##,
+    );
+
+    process(&source)?;
+
+    Ok(())
+}
@@ -8,6 +8,8 @@
 
 use crate::lexer::token::LexToken;
 use crate::macros::EntityMacroDefinition;
+use crate::macros::RegisteredEntityMacro;
+use crate::syntax::entity::LazyParsedEntityDatabase;
 use crate::syntax::entity::ParsedEntity;
 use lark_collections::FxIndexMap;
 use lark_entity::Entity;
@@ -39,13 +41,14 @@ mod fn_body;
 mod ir;
 mod lexer;
 pub mod macros;
-mod parser;
+pub mod parser;
 mod query_definitions;
 mod scope;
 pub mod syntax;
 mod type_conversion;
 
 pub use self::ir::ParsedFile;
+pub use self::lexer::token::LexToken;
 
 salsa::query_group! {
     pub trait ParserDatabase: AsRef<GlobalIdentifierTables>
@@ -63,6 +66,14 @@ salsa::query_group! {
             storage input;
         }
 
+        /// Entity macros registered in addition to the built-in ones
+        /// (see `ParserDatabaseExt::register_entity_macro`), in order
+        /// of registration.
+        fn registered_entity_macros() -> Seq<RegisteredEntityMacro> {
+            type RegisteredEntityMacrosQuery;
+            storage input;
+        }
+
         fn entity_span(entity: Entity) -> Span<FileName> {
             type EntitySpanQuery;
             use fn query_definitions::entity_span;
@@ -167,12 +178,30 @@ salsa::query_group! {
             type ResolveNameQuery;
             use fn scope::resolve_name;
         }
+
+        /// The top-level items of all input files, which are visible
+        /// from every file. Files are visited in the order of
+        /// `file_names`; if a name is defined more than once, only the
+        /// first definition is included, and the rest are reported as
+        /// errors.
+        fn project_items() -> WithError<Seq<Entity>> {
+            type ProjectItemsQuery;
+            use fn scope::project_items;
+        }
+
+        /// The item that a `use` declaration imports.
+        fn use_target(entity: Entity) -> WithError<Entity> {
+            type UseTargetQuery;
+            use fn scope::use_target;
+        }
     }
 }
 
 pub trait ParserDatabaseExt: ParserDatabase {
     fn init_parser_db(&mut self) {
         self.query_mut(FileNamesQuery).set((), Default::default());
+        self.query_mut(RegisteredEntityMacrosQuery)
+            .set((), Default::default());
     }
 
     fn add_file(&mut self, path: impl IntoFileName, contents: impl Into<Text>) {
@@ -186,6 +215,29 @@ pub trait ParserDatabaseExt: ParserDatabase {
             .set(file_name, contents.into());
     }
 
+    /// Makes the entity macro `definition` available under `name`,
+    /// either everywhere (if `scope` is `None`) or only within the
+    /// given entity and its descendants. A registration shadows the
+    /// built-in macros and any earlier registration of the same name.
+    fn register_entity_macro(
+        &mut self,
+        scope: Option<Entity>,
+        name: &str,
+        definition: Arc<dyn EntityMacroDefinition>,
+    ) {
+        let name = name.intern(self);
+
+        let mut registered_entity_macros = ParserDatabase::registered_entity_macros(self);
+        registered_entity_macros.extend(Some(RegisteredEntityMacro {
+            scope,
+            name,
+            definition,
+        }));
+
+        self.query_mut(RegisteredEntityMacrosQuery)
+            .set((), registered_entity_macros);
+    }
+
     /// Returns the "top-level" entities defined in the given file --
     /// does not descend to visit the children of those entities etc.
     fn top_level_entities_in_file(&self, file: impl IntoFileName) -> Seq<Entity> {
@@ -199,12 +251,12 @@ fn diagnostic(message: impl Into<String>, span: Span<FileName>) -> Diagnostic {
     Diagnostic::new(message.into(), span)
 }
 
-/// Set of macro definitions in scope for `entity`. For now, this is
-/// always the default set. This function really just exists as a
-/// placeholder for us to change later.
+/// Set of macro definitions in scope for `entity`: the built-in
+/// macros, overridden by any registered macros whose scope includes
+/// `entity`.
 fn macro_definitions(
-    db: &dyn AsRef<GlobalIdentifierTables>,
-    _entity: Entity,
+    db: &dyn LazyParsedEntityDatabase,
+    entity: Entity,
 ) -> FxIndexMap<GlobalIdentifier, Arc<dyn EntityMacroDefinition>> {
     macro_rules! declare_macro {
         (
@@ -222,11 +274,23 @@ fn macro_definitions(
         }
     }
 
-    declare_macro!(
-        db(db),
+    let mut map = declare_macro!(
+        db(&db),
         macros(
             "struct" => macros::struct_declaration::StructDeclaration,
             "def" => macros::function_declaration::FunctionDeclaration,
+            "enum" => macros::enum_declaration::EnumDeclaration,
+            "mod" => macros::module_declaration::ModuleDeclaration,
+            "use" => macros::use_declaration::UseDeclaration,
+            "const" => macros::const_declaration::ConstDeclaration,
         ),
-    )
+    );
+
+    for registered in db.registered_entity_macros().iter() {
+        if registered.applies_to(&db, entity) {
+            map.insert(registered.name, registered.definition.clone());
+        }
+    }
+
+    map
 }
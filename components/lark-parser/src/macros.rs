@@ -1,15 +1,21 @@
 use crate::parser::Parser;
 use crate::syntax::entity::ParsedEntity;
 use lark_entity::Entity;
+use lark_entity::EntityTables;
 use lark_error::ErrorReported;
 use lark_span::FileName;
 use lark_span::Spanned;
 use lark_string::GlobalIdentifier;
+use std::sync::Arc;
 
+crate mod const_declaration;
+crate mod enum_declaration;
 crate mod function_declaration;
+crate mod module_declaration;
 crate mod struct_declaration;
+crate mod use_declaration;
 
-crate trait EntityMacroDefinition: Send {
+pub trait EntityMacroDefinition: Send + Sync {
     /// Invoked when the macro name has been recognized and
     /// consumed. Has the job of parsing the rest of the entity (using
     /// the helper methods on `parser` to do so) and ultimately
@@ -29,3 +35,62 @@ crate trait EntityMacroDefinition: Send {
         macro_name: Spanned<GlobalIdentifier, FileName>,
     ) -> Result<ParsedEntity, ErrorReported>;
 }
+
+/// An entity macro registered through
+/// `ParserDatabaseExt::register_entity_macro`, in addition to the
+/// built-in ones.
+#[derive(Clone)]
+pub struct RegisteredEntityMacro {
+    /// If `Some`, the macro is only available within this entity
+    /// (e.g., a given input file) and the entities nested inside of
+    /// it; if `None`, it is available everywhere.
+    pub scope: Option<Entity>,
+
+    /// The name that invokes the macro.
+    pub name: GlobalIdentifier,
+
+    pub definition: Arc<dyn EntityMacroDefinition>,
+}
+
+impl RegisteredEntityMacro {
+    /// True if this macro may be invoked within `entity`.
+    crate fn applies_to(&self, db: &dyn AsRef<EntityTables>, entity: Entity) -> bool {
+        match self.scope {
+            None => true,
+            Some(scope) => entity.is_within(db, scope),
+        }
+    }
+
+    fn definition_ptr(&self) -> *const () {
+        &*self.definition as *const dyn EntityMacroDefinition as *const ()
+    }
+}
+
+// Definitions are compared by identity: registering the same
+// definition again is a no-op as far as salsa is concerned.
+impl PartialEq for RegisteredEntityMacro {
+    fn eq(&self, other: &Self) -> bool {
+        self.scope == other.scope
+            && self.name == other.name
+            && self.definition_ptr() == other.definition_ptr()
+    }
+}
+
+impl Eq for RegisteredEntityMacro {}
+
+impl std::hash::Hash for RegisteredEntityMacro {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.scope.hash(state);
+        self.name.hash(state);
+        self.definition_ptr().hash(state);
+    }
+}
+
+impl std::fmt::Debug for RegisteredEntityMacro {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt.debug_struct("RegisteredEntityMacro")
+            .field("scope", &self.scope)
+            .field("name", &self.name)
+            .finish()
+    }
+}
@@ -0,0 +1,209 @@
+use crate::lexer::token::LexToken;
+use crate::macros::EntityMacroDefinition;
+use crate::parser::Parser;
+use crate::syntax::entity::{
+    ErrorParsedEntity, InvalidParsedEntity, LazyParsedEntity, LazyParsedEntityDatabase,
+    ParsedEntity, ParsedEntityThunk,
+};
+use crate::syntax::field::{Field, ParsedField};
+use crate::syntax::fn_body;
+use crate::syntax::matched::ParsedMatch;
+use crate::syntax::sigil::{
+    CloseCurly, CloseParenthesis, CloseSquare, Equals, OpenCurly, OpenParenthesis, OpenSquare,
+};
+use crate::syntax::skip_newline::SkipNewline;
+use crate::syntax::type_reference::ParsedTypeReference;
+use lark_debug_with::DebugWith;
+use lark_entity::Entity;
+use lark_entity::EntityData;
+use lark_entity::ItemKind;
+use lark_error::ErrorReported;
+use lark_error::WithError;
+use lark_hir as hir;
+use lark_intern::Intern;
+use lark_intern::Untern;
+use lark_seq::Seq;
+use lark_span::FileName;
+use lark_span::Spanned;
+use lark_string::GlobalIdentifier;
+use lark_ty as ty;
+use lark_ty::declaration::Declaration;
+use std::sync::Arc;
+
+/// ```ignore
+/// `const` <id> `:` <ty> `=` <expr>
+/// ```
+///
+/// The initializer extends until the end of the line, and is
+/// evaluated at compile time.
+#[derive(Default)]
+pub struct ConstDeclaration;
+
+impl EntityMacroDefinition for ConstDeclaration {
+    fn expect(
+        &self,
+        parser: &mut Parser<'_>,
+        base: Entity,
+        macro_name: Spanned<GlobalIdentifier, FileName>,
+    ) -> Result<ParsedEntity, ErrorReported> {
+        log::trace!(
+            "ConstDeclaration::parse(base={}, macro_name={})",
+            base.debug_with(parser),
+            macro_name.debug_with(parser)
+        );
+
+        let Spanned {
+            value: ParsedField { name, ty },
+            ..
+        } = parser.expect(SkipNewline(Field))?;
+
+        let initializer = match parser.expect(Equals) {
+            Ok(_) => expect_initializer(parser),
+            Err(err) => Err(err),
+        };
+
+        let entity = EntityData::ItemName {
+            base,
+            kind: ItemKind::Const,
+            id: name.value,
+        }
+        .intern(parser);
+
+        let full_span = macro_name.span.extended_until_end_of(parser.last_span());
+        let characteristic_span = name.span;
+
+        Ok(ParsedEntity::new(
+            entity,
+            full_span,
+            characteristic_span,
+            ParsedEntityThunk::new(ParsedConstDeclaration { ty, initializer }),
+        ))
+    }
+}
+
+/// Consumes the tokens of the initializer, which go until the end of
+/// the line (not counting newlines within parentheses and the like).
+fn expect_initializer(
+    parser: &mut Parser<'_>,
+) -> Result<Spanned<ParsedMatch, FileName>, ErrorReported> {
+    let start_token = parser.peek_index();
+    let start_span = parser.peek_span();
+
+    let mut depth = 0;
+    while !parser.is(LexToken::EOF) && !(depth == 0 && parser.is(LexToken::Newline)) {
+        if parser.test(OpenParenthesis) || parser.test(OpenSquare) || parser.test(OpenCurly) {
+            depth += 1;
+        } else if parser.test(CloseParenthesis)
+            || parser.test(CloseSquare)
+            || parser.test(CloseCurly)
+        {
+            if depth == 0 {
+                break;
+            }
+            depth -= 1;
+        }
+        parser.shift();
+    }
+
+    let end_token = parser.peek_index();
+    if start_token == end_token {
+        return Err(parser.report_error("expected an initializer", start_span));
+    }
+
+    let full_span = start_span.extended_until_end_of(parser.last_span());
+    let range = ParsedMatch {
+        start_token,
+        end_token,
+    };
+    Ok(Spanned::new(range, full_span))
+}
+
+struct ParsedConstDeclaration {
+    ty: ParsedTypeReference,
+    initializer: Result<Spanned<ParsedMatch, FileName>, ErrorReported>,
+}
+
+impl LazyParsedEntity for ParsedConstDeclaration {
+    fn parse_children(
+        &self,
+        _entity: Entity,
+        _db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<Seq<ParsedEntity>> {
+        WithError::ok(Seq::default())
+    }
+
+    fn parse_generic_declarations(
+        &self,
+        _entity: Entity,
+        _db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<Result<Arc<ty::GenericDeclarations>, ErrorReported>> {
+        WithError::ok(Ok(ty::GenericDeclarations::empty(None)))
+    }
+
+    fn parse_type(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<ty::Ty<Declaration>> {
+        self.ty.parse_type(entity, db)
+    }
+
+    /// The initializer is type-checked like the body of a function
+    /// with no parameters that returns the declared type.
+    fn parse_signature(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<Result<ty::Signature<Declaration>, ErrorReported>> {
+        // Any errors in the type are reported by `parse_type`.
+        let output = self.ty.parse_type(entity, db).into_value();
+
+        WithError::ok(Ok(ty::Signature {
+            inputs: Seq::default(),
+            output,
+        }))
+    }
+
+    fn parse_fn_body(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<hir::FnBody> {
+        match self.initializer {
+            Err(err) => ErrorParsedEntity { err }.parse_fn_body(entity, db),
+
+            Ok(Spanned {
+                span: _,
+                value:
+                    ParsedMatch {
+                        start_token,
+                        end_token,
+                    },
+            }) => {
+                let file_name = entity.untern(&db).file_name(&db).unwrap();
+                let input = db.file_text(file_name);
+                let tokens = db
+                    .file_tokens(file_name)
+                    .into_value()
+                    .extract(start_token..end_token);
+                let entity_macro_definitions = crate::macro_definitions(db, entity);
+                fn_body::parse_fn_body(
+                    entity,
+                    db,
+                    &entity_macro_definitions,
+                    &input,
+                    &tokens,
+                    Seq::default(),
+                )
+            }
+        }
+    }
+
+    fn parse_use_path(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> Seq<Spanned<GlobalIdentifier, FileName>> {
+        InvalidParsedEntity.parse_use_path(entity, db)
+    }
+}
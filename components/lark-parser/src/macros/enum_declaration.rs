@@ -0,0 +1,318 @@
+use crate::macros::EntityMacroDefinition;
+use crate::parser::Parser;
+use crate::syntax::delimited::Delimited;
+use crate::syntax::entity::{
+    InvalidParsedEntity, LazyParsedEntity, LazyParsedEntityDatabase, ParsedEntity,
+    ParsedEntityThunk,
+};
+use crate::syntax::field::{Field, ParsedField};
+use crate::syntax::generics::GenericParameters;
+use crate::syntax::identifier::SpannedGlobalIdentifier;
+use crate::syntax::list::CommaList;
+use crate::syntax::sigil::{Curlies, Parentheses};
+use crate::syntax::skip_newline::SkipNewline;
+use crate::syntax::Syntax;
+use lark_debug_derive::DebugWith;
+use lark_debug_with::DebugWith;
+use lark_entity::Entity;
+use lark_entity::EntityData;
+use lark_entity::ItemKind;
+use lark_entity::MemberKind;
+use lark_error::ErrorReported;
+use lark_error::ErrorSentinel;
+use lark_error::WithError;
+use lark_hir as hir;
+use lark_intern::{Intern, Untern};
+use lark_seq::Seq;
+use lark_span::FileName;
+use lark_span::Spanned;
+use lark_string::GlobalIdentifier;
+use lark_ty as ty;
+use lark_ty::declaration::Declaration;
+use std::sync::Arc;
+
+/// ```ignore
+/// enum <id> [ `<` <id> `>` ] {
+///   <id> [ `(` <id>: <ty>, ... `)` ] // separated by `,` or newline
+/// }
+/// ```
+#[derive(Default)]
+pub struct EnumDeclaration;
+
+impl EntityMacroDefinition for EnumDeclaration {
+    fn expect(
+        &self,
+        parser: &mut Parser<'_>,
+        base: Entity,
+        macro_name: Spanned<GlobalIdentifier, FileName>,
+    ) -> Result<ParsedEntity, ErrorReported> {
+        log::trace!(
+            "EnumDeclaration::parse(base={}, macro_name={})",
+            base.debug_with(parser),
+            macro_name.debug_with(parser)
+        );
+
+        log::trace!("EnumDeclaration::parse: parsing name");
+        let enum_name = parser.expect(SkipNewline(SpannedGlobalIdentifier))?;
+
+        log::trace!("EnumDeclaration::parse: parsing generic parameters");
+        let generic_parameters = match parser.parse_if_present(GenericParameters) {
+            Some(parameters) => parameters.unwrap_or_else(|ErrorReported(_)| Seq::default()),
+            None => Seq::default(),
+        };
+
+        log::trace!("EnumDeclaration::parse: parsing variants");
+        let variants = parser
+            .expect(SkipNewline(Delimited(Curlies, CommaList(Variant))))
+            .unwrap_or_else(|ErrorReported(_)| Seq::default());
+
+        log::trace!("EnumDeclaration::parse: done");
+        let entity = EntityData::ItemName {
+            base,
+            kind: ItemKind::Enum,
+            id: enum_name.value,
+        }
+        .intern(parser);
+
+        let full_span = macro_name.span.extended_until_end_of(parser.last_span());
+        let characteristic_span = enum_name.span;
+
+        Ok(ParsedEntity::new(
+            entity,
+            full_span,
+            characteristic_span,
+            ParsedEntityThunk::new(ParsedEnumDeclaration {
+                generic_parameters,
+                variants,
+            }),
+        ))
+    }
+}
+
+/// A variant within the body of an enum, like `Error(code: uint)`.
+#[derive(DebugWith)]
+struct Variant;
+
+#[derive(Clone)]
+struct ParsedVariant {
+    name: Spanned<GlobalIdentifier, FileName>,
+    fields: Seq<Spanned<ParsedField, FileName>>,
+}
+
+impl Syntax<'parse> for Variant {
+    type Data = Spanned<ParsedVariant, FileName>;
+
+    fn test(&mut self, parser: &Parser<'parse>) -> bool {
+        parser.test(SpannedGlobalIdentifier)
+    }
+
+    fn expect(&mut self, parser: &mut Parser<'parse>) -> Result<Self::Data, ErrorReported> {
+        let name = parser.expect(SpannedGlobalIdentifier)?;
+
+        let fields = match parser.parse_if_present(Delimited(Parentheses, CommaList(Field))) {
+            Some(fields) => fields.unwrap_or_else(|ErrorReported(_)| Seq::default()),
+            None => Seq::default(),
+        };
+
+        let span = name.span.extended_until_end_of(parser.last_span());
+
+        Ok(Spanned {
+            value: ParsedVariant { name, fields },
+            span,
+        })
+    }
+}
+
+struct ParsedEnumDeclaration {
+    generic_parameters: Seq<Spanned<GlobalIdentifier, FileName>>,
+    variants: Seq<Spanned<ParsedVariant, FileName>>,
+}
+
+impl LazyParsedEntity for ParsedEnumDeclaration {
+    fn parse_children(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<Seq<ParsedEntity>> {
+        WithError::ok(
+            self.variants
+                .iter()
+                .map(
+                    |Spanned {
+                         value: variant,
+                         span,
+                     }| {
+                        let variant_entity = EntityData::MemberName {
+                            base: entity,
+                            kind: MemberKind::Variant,
+                            id: variant.name.value,
+                        }
+                        .intern(&db);
+
+                        ParsedEntity::new(
+                            variant_entity,
+                            *span,
+                            variant.name.span,
+                            ParsedEntityThunk::new(variant.clone()),
+                        )
+                    },
+                )
+                .collect(),
+        )
+    }
+
+    fn parse_generic_declarations(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<Result<Arc<ty::GenericDeclarations>, ErrorReported>> {
+        crate::type_conversion::generic_declarations_from_parameters(
+            db,
+            entity,
+            &self.generic_parameters,
+        )
+    }
+
+    fn parse_signature(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<Result<ty::Signature<Declaration>, ErrorReported>> {
+        InvalidParsedEntity.parse_signature(entity, db)
+    }
+
+    fn parse_type(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<ty::Ty<Declaration>> {
+        // For each enum `Foo<A, B>`, the "type" is just `own Foo<A, B>`
+        match db.generic_declarations(entity).into_value() {
+            Ok(generic_declarations) => {
+                assert!(generic_declarations.parent_item.is_none());
+                let ty = crate::type_conversion::declaration_ty_named(
+                    &db,
+                    entity,
+                    ty::declaration::DeclaredPermKind::Own,
+                    ty::ReprKind::Direct,
+                    crate::type_conversion::bound_var_generics(
+                        &db,
+                        generic_declarations.declarations.len(),
+                    ),
+                );
+                WithError::ok(ty)
+            }
+            Err(err) => WithError::error_sentinel(&db, err),
+        }
+    }
+
+    fn parse_fn_body(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<hir::FnBody> {
+        panic!(
+            "cannot parse fn body of an enum: {:?}",
+            entity.debug_with(db)
+        )
+    }
+
+    fn parse_use_path(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> Seq<Spanned<GlobalIdentifier, FileName>> {
+        InvalidParsedEntity.parse_use_path(entity, db)
+    }
+}
+
+impl LazyParsedEntity for ParsedVariant {
+    fn parse_children(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<Seq<ParsedEntity>> {
+        WithError::ok(
+            self.fields
+                .iter()
+                .map(|Spanned { value: field, span }| {
+                    let field_entity = EntityData::MemberName {
+                        base: entity,
+                        kind: MemberKind::Field,
+                        id: field.name.value,
+                    }
+                    .intern(&db);
+
+                    ParsedEntity::new(
+                        field_entity,
+                        *span,
+                        field.name.span,
+                        ParsedEntityThunk::new(field.clone()),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    fn parse_generic_declarations(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<Result<Arc<ty::GenericDeclarations>, ErrorReported>> {
+        // The fields of a variant can reference the generic
+        // parameters of its enum.
+        let parent_item = match entity.untern(&db) {
+            EntityData::MemberName { base, .. } => Some(base),
+            _ => None,
+        };
+        WithError::ok(Ok(ty::GenericDeclarations::empty(parent_item)))
+    }
+
+    fn parse_signature(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<Result<ty::Signature<Declaration>, ErrorReported>> {
+        InvalidParsedEntity.parse_signature(entity, db)
+    }
+
+    /// For a variant of an enum `Foo<A, B>`, the type is `own Foo<A, B>`.
+    fn parse_type(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<ty::Ty<Declaration>> {
+        let enum_entity = match entity.untern(&db) {
+            EntityData::MemberName { base, .. } => base,
+            _ => panic!("not a variant: {:?}", entity.debug_with(db)),
+        };
+
+        let ty = crate::type_conversion::declaration_ty_named(
+            &db,
+            enum_entity,
+            ty::declaration::DeclaredPermKind::Own,
+            ty::ReprKind::Direct,
+            crate::type_conversion::bound_var_generics(
+                &db,
+                crate::type_conversion::generic_count(db, entity),
+            ),
+        );
+        WithError::ok(ty)
+    }
+
+    fn parse_fn_body(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<hir::FnBody> {
+        InvalidParsedEntity.parse_fn_body(entity, db)
+    }
+
+    fn parse_use_path(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> Seq<Spanned<GlobalIdentifier, FileName>> {
+        InvalidParsedEntity.parse_use_path(entity, db)
+    }
+}
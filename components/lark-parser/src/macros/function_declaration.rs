@@ -2,6 +2,7 @@ use crate::macros::EntityMacroDefinition;
 use crate::parser::Parser;
 use crate::syntax::delimited::Delimited;
 use crate::syntax::entity::ErrorParsedEntity;
+use crate::syntax::entity::InvalidParsedEntity;
 use crate::syntax::entity::LazyParsedEntity;
 use crate::syntax::entity::LazyParsedEntityDatabase;
 use crate::syntax::entity::ParsedEntity;
@@ -9,14 +10,18 @@ use crate::syntax::entity::ParsedEntityThunk;
 use crate::syntax::field::Field;
 use crate::syntax::field::ParsedField;
 use crate::syntax::fn_body;
+use crate::syntax::generics::GenericParameters;
 use crate::syntax::guard::Guard;
 use crate::syntax::identifier::SpannedGlobalIdentifier;
 use crate::syntax::list::CommaList;
 use crate::syntax::matched::{Matched, ParsedMatch};
-use crate::syntax::sigil::{Curlies, Parentheses, RightArrow};
+use crate::syntax::sigil::{Curlies, Def, Parentheses, RightArrow, SelfKeyword};
 use crate::syntax::skip_newline::SkipNewline;
 use crate::syntax::type_reference::ParsedTypeReference;
+use crate::syntax::type_reference::Permission;
 use crate::syntax::type_reference::TypeReference;
+use crate::syntax::Syntax;
+use lark_debug_derive::DebugWith;
 use lark_debug_with::DebugWith;
 use lark_entity::Entity;
 use lark_entity::EntityData;
@@ -30,15 +35,17 @@ use lark_intern::Intern;
 use lark_intern::Untern;
 use lark_seq::Seq;
 use lark_span::FileName;
+use lark_span::Span;
 use lark_span::Spanned;
 use lark_string::GlobalIdentifier;
 use lark_ty as ty;
 use lark_ty::declaration::Declaration;
+use lark_ty::declaration::DeclaredPermKind;
 use lark_ty::GenericDeclarations;
 use std::sync::Arc;
 
 /// ```ignore
-/// `def` <id> `(` <id> `:` <ty> `)` [ `->` <ty> ] <block>
+/// `def` <id> [ `<` <id> `>` ] `(` <id> `:` <ty> `)` [ `->` <ty> ] <block>
 /// ```
 #[derive(Default)]
 pub struct FunctionDeclaration;
@@ -58,18 +65,16 @@ impl EntityMacroDefinition for FunctionDeclaration {
 
         let function_name = parser.expect(SkipNewline(SpannedGlobalIdentifier))?;
 
+        let generic_parameters = match parser.parse_if_present(GenericParameters) {
+            Some(parameters) => parameters.unwrap_or_else(|ErrorReported(_)| Seq::default()),
+            None => Seq::default(),
+        };
+
         let parameters = parser
             .expect(SkipNewline(Delimited(Parentheses, CommaList(Field))))
             .unwrap_or_else(|ErrorReported(_)| Seq::default());
 
-        let return_type = match parser
-            .parse_if_present(SkipNewline(Guard(RightArrow, SkipNewline(TypeReference))))
-        {
-            Some(ty) => ty.unwrap_or_error_sentinel(&*parser),
-            None => ParsedTypeReference::Elided(parser.elided_span()),
-        };
-
-        let body = parser.expect(SkipNewline(Matched(Curlies)));
+        let (return_type, body) = expect_return_type_and_body(parser);
 
         let entity = EntityData::ItemName {
             base,
@@ -86,6 +91,8 @@ impl EntityMacroDefinition for FunctionDeclaration {
             full_span,
             characteristic_span,
             ParsedEntityThunk::new(ParsedFunctionDeclaration {
+                generic_parameters,
+                self_parameter: None,
                 parameters,
                 return_type,
                 body,
@@ -94,7 +101,215 @@ impl EntityMacroDefinition for FunctionDeclaration {
     }
 }
 
-struct ParsedFunctionDeclaration {
+/// Parses the part of a function or method declaration that follows
+/// its parameters: `[ -> <ty> ] <block>`.
+fn expect_return_type_and_body(
+    parser: &mut Parser<'_>,
+) -> (
+    ParsedTypeReference,
+    Result<Spanned<ParsedMatch, FileName>, ErrorReported>,
+) {
+    let return_type =
+        match parser.parse_if_present(SkipNewline(Guard(RightArrow, SkipNewline(TypeReference)))) {
+            Some(ty) => ty.unwrap_or_error_sentinel(&*parser),
+            None => ParsedTypeReference::Elided(parser.elided_span()),
+        };
+
+    let body = parser.expect(SkipNewline(Matched(Curlies)));
+
+    (return_type, body)
+}
+
+/// ```ignore
+/// `def` <id> `(` [ <perm> ] `self` { `,` <id> `:` <ty> } `)` [ `->` <ty> ] <block>
+/// ```
+///
+/// A method, declared within the body of a struct. The type of `self`
+/// is the struct itself, with the permission written before `self`
+/// (or `own` if there is none).
+#[derive(DebugWith)]
+crate struct Method;
+
+crate struct ParsedMethod {
+    crate name: Spanned<GlobalIdentifier, FileName>,
+    crate declaration: ParsedFunctionDeclaration,
+}
+
+impl Syntax<'parse> for Method {
+    type Data = Spanned<ParsedMethod, FileName>;
+
+    fn test(&mut self, parser: &Parser<'parse>) -> bool {
+        parser.test(Def)
+    }
+
+    fn expect(&mut self, parser: &mut Parser<'parse>) -> Result<Self::Data, ErrorReported> {
+        let def_keyword = parser.expect(Def)?;
+        let method_name = parser.expect(SkipNewline(SpannedGlobalIdentifier))?;
+
+        let generics_span = parser.peek_span();
+        if parser.parse_if_present(GenericParameters).is_some() {
+            parser.report_error(
+                "methods cannot declare generic parameters (yet)",
+                generics_span.extended_until_end_of(parser.last_span()),
+            );
+        }
+
+        let (self_parameter, parameters) = match parser.expect(SkipNewline(Delimited(
+            Parentheses,
+            CommaList(MethodParameter),
+        ))) {
+            Ok(parameters) => split_method_parameters(parser, method_name, parameters),
+            Err(ErrorReported(_)) => (
+                SelfParameter::implicit(parser, method_name.span),
+                Seq::default(),
+            ),
+        };
+
+        let (return_type, body) = expect_return_type_and_body(parser);
+
+        let span = def_keyword.span.extended_until_end_of(parser.last_span());
+
+        Ok(Spanned {
+            value: ParsedMethod {
+                name: method_name,
+                declaration: ParsedFunctionDeclaration {
+                    generic_parameters: Seq::default(),
+                    self_parameter: Some(self_parameter),
+                    parameters,
+                    return_type,
+                    body,
+                },
+            },
+            span,
+        })
+    }
+}
+
+/// Separates the `self` parameter of a method, which must come first,
+/// from the rest of its parameters.
+fn split_method_parameters(
+    parser: &mut Parser<'_>,
+    method_name: Spanned<GlobalIdentifier, FileName>,
+    parameters: Seq<ParsedMethodParameter>,
+) -> (SelfParameter, Seq<Spanned<ParsedField, FileName>>) {
+    let mut self_parameter = None;
+    let mut fields = vec![];
+
+    for (index, parameter) in parameters.iter().enumerate() {
+        match parameter {
+            ParsedMethodParameter::SelfParameter(parameter) => {
+                if index == 0 {
+                    self_parameter = Some(parameter.clone());
+                } else {
+                    parser.report_error(
+                        "`self` must be the first parameter of a method",
+                        parameter.name.span,
+                    );
+                }
+            }
+
+            ParsedMethodParameter::Field(field) => fields.push(field.clone()),
+        }
+    }
+
+    let self_parameter = self_parameter.unwrap_or_else(|| {
+        parser.report_error(
+            "the first parameter of a method must be `self`",
+            method_name.span,
+        );
+        SelfParameter::implicit(parser, method_name.span)
+    });
+
+    (self_parameter, Seq::from(fields))
+}
+
+/// A parameter of a method: either `self` (like `share self`) or an
+/// ordinary `<id>: <ty>`.
+#[derive(DebugWith)]
+struct MethodParameter;
+
+enum ParsedMethodParameter {
+    SelfParameter(SelfParameter),
+    Field(Spanned<ParsedField, FileName>),
+}
+
+impl Syntax<'parse> for MethodParameter {
+    type Data = ParsedMethodParameter;
+
+    fn test(&mut self, parser: &Parser<'parse>) -> bool {
+        parser.test(Permission) || parser.test(SelfKeyword) || parser.test(Field)
+    }
+
+    fn expect(&mut self, parser: &mut Parser<'parse>) -> Result<Self::Data, ErrorReported> {
+        let perm = match parser.parse_if_present(Permission) {
+            Some(perm) => Some(perm?),
+            None => None,
+        };
+
+        if perm.is_none() && !parser.test(SelfKeyword) {
+            return Ok(ParsedMethodParameter::Field(parser.expect(Field)?));
+        }
+
+        let self_keyword = parser.expect(SelfKeyword)?;
+        let name = Spanned {
+            value: SelfKeyword::TEXT.intern(parser),
+            span: match perm {
+                Some(perm) => perm.span.extended_until_end_of(self_keyword.span),
+                None => self_keyword.span,
+            },
+        };
+
+        Ok(ParsedMethodParameter::SelfParameter(SelfParameter {
+            perm: perm.map_or(DeclaredPermKind::Own, |perm| perm.value),
+            name,
+        }))
+    }
+}
+
+/// The `self` parameter of a method.
+#[derive(Clone)]
+struct SelfParameter {
+    perm: DeclaredPermKind,
+    name: Spanned<GlobalIdentifier, FileName>,
+}
+
+impl SelfParameter {
+    /// The `self` we assume when it was missing or failed to parse,
+    /// so that the rest of the method can still be checked.
+    fn implicit(parser: &Parser<'_>, span: Span<FileName>) -> Self {
+        SelfParameter {
+            perm: DeclaredPermKind::Own,
+            name: Spanned {
+                value: SelfKeyword::TEXT.intern(parser),
+                span,
+            },
+        }
+    }
+
+    /// For a struct `Foo<A, B>`, the type of `self` is `<perm> Foo<A, B>`.
+    fn parse_type(&self, entity: Entity, db: &dyn LazyParsedEntityDatabase) -> ty::Ty<Declaration> {
+        let struct_entity = match entity.untern(&db) {
+            EntityData::MemberName { base, .. } => base,
+            _ => panic!("not a method: {:?}", entity.debug_with(db)),
+        };
+
+        crate::type_conversion::declaration_ty_named(
+            &db,
+            struct_entity,
+            self.perm,
+            ty::ReprKind::Direct,
+            crate::type_conversion::bound_var_generics(
+                &db,
+                crate::type_conversion::generic_count(db, struct_entity),
+            ),
+        )
+    }
+}
+
+#[derive(Clone)]
+crate struct ParsedFunctionDeclaration {
+    generic_parameters: Seq<Spanned<GlobalIdentifier, FileName>>,
+    self_parameter: Option<SelfParameter>,
     parameters: Seq<Spanned<ParsedField, FileName>>,
     return_type: ParsedTypeReference,
     body: Result<Spanned<ParsedMatch, FileName>, ErrorReported>,
@@ -111,11 +326,22 @@ impl LazyParsedEntity for ParsedFunctionDeclaration {
 
     fn parse_generic_declarations(
         &self,
-        _entity: Entity,
-        _db: &dyn LazyParsedEntityDatabase,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
     ) -> WithError<Result<Arc<GenericDeclarations>, ErrorReported>> {
-        // FIXME -- no support for generics yet
-        WithError::ok(Ok(GenericDeclarations::empty(None)))
+        match entity.untern(&db) {
+            // Methods do not declare generic parameters of their own,
+            // but can reference those of their struct.
+            EntityData::MemberName { base, .. } => {
+                WithError::ok(Ok(GenericDeclarations::empty(Some(base))))
+            }
+
+            _ => crate::type_conversion::generic_declarations_from_parameters(
+                db,
+                entity,
+                &self.generic_parameters,
+            ),
+        }
     }
 
     fn parse_type(
@@ -124,16 +350,19 @@ impl LazyParsedEntity for ParsedFunctionDeclaration {
         db: &dyn LazyParsedEntityDatabase,
     ) -> WithError<ty::Ty<Declaration>> {
         // For each function `foo`, create a unique type `foo` as in
-        // Rust.
+        // Rust. (For methods, this includes the generics of the
+        // struct.)
         match db.generic_declarations(entity).into_value() {
-            Ok(generic_declarations) => {
-                assert!(generic_declarations.is_empty());
+            Ok(_) => {
                 let ty = crate::type_conversion::declaration_ty_named(
                     &db,
                     entity,
                     ty::declaration::DeclaredPermKind::Own,
                     ty::ReprKind::Direct,
-                    ty::Generics::empty(),
+                    crate::type_conversion::bound_var_generics(
+                        &db,
+                        crate::type_conversion::generic_count(db, entity),
+                    ),
                 );
                 WithError::ok(ty)
             }
@@ -148,13 +377,16 @@ impl LazyParsedEntity for ParsedFunctionDeclaration {
     ) -> WithError<Result<ty::Signature<Declaration>, ErrorReported>> {
         let mut errors = vec![];
 
-        let inputs: Seq<_> = self
-            .parameters
-            .iter()
-            .map(|p| {
+        let self_input = self
+            .self_parameter
+            .as_ref()
+            .map(|self_parameter| self_parameter.parse_type(entity, db));
+        let inputs: Seq<_> = self_input
+            .into_iter()
+            .chain(self.parameters.iter().map(|p| {
                 p.ty.parse_type(entity, db)
                     .accumulate_errors_into(&mut errors)
-            })
+            }))
             .collect();
 
         let output = self
@@ -190,8 +422,13 @@ impl LazyParsedEntity for ParsedFunctionDeclaration {
                     .file_tokens(file_name)
                     .into_value()
                     .extract(start_token..end_token);
-                let entity_macro_definitions = crate::macro_definitions(&db, entity);
-                let arguments: Seq<_> = self.parameters.iter().map(|f| f.value.name).collect();
+                let entity_macro_definitions = crate::macro_definitions(db, entity);
+                let arguments: Seq<_> = self
+                    .self_parameter
+                    .iter()
+                    .map(|self_parameter| self_parameter.name)
+                    .chain(self.parameters.iter().map(|f| f.value.name))
+                    .collect();
                 fn_body::parse_fn_body(
                     entity,
                     db,
@@ -203,4 +440,12 @@ impl LazyParsedEntity for ParsedFunctionDeclaration {
             }
         }
     }
+
+    fn parse_use_path(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> Seq<Spanned<GlobalIdentifier, FileName>> {
+        InvalidParsedEntity.parse_use_path(entity, db)
+    }
 }
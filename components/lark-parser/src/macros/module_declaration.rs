@@ -0,0 +1,132 @@
+use crate::macros::EntityMacroDefinition;
+use crate::parser::Parser;
+use crate::syntax::delimited::Delimited;
+use crate::syntax::entity::{
+    EntitySyntax, InvalidParsedEntity, LazyParsedEntity, LazyParsedEntityDatabase, ParsedEntity,
+    ParsedEntityThunk,
+};
+use crate::syntax::identifier::SpannedGlobalIdentifier;
+use crate::syntax::list::SeparatedList;
+use crate::syntax::sigil::{Curlies, Semicolon};
+use crate::syntax::skip_newline::SkipNewline;
+use lark_debug_with::DebugWith;
+use lark_entity::Entity;
+use lark_entity::EntityData;
+use lark_entity::ItemKind;
+use lark_error::ErrorReported;
+use lark_error::WithError;
+use lark_hir as hir;
+use lark_intern::Intern;
+use lark_seq::Seq;
+use lark_span::FileName;
+use lark_span::Spanned;
+use lark_string::GlobalIdentifier;
+use lark_ty as ty;
+use lark_ty::declaration::Declaration;
+use std::sync::Arc;
+
+/// ```ignore
+/// mod <id> {
+///   <entity> // separated by `;` or newline
+/// }
+/// ```
+#[derive(Default)]
+pub struct ModuleDeclaration;
+
+impl EntityMacroDefinition for ModuleDeclaration {
+    fn expect(
+        &self,
+        parser: &mut Parser<'_>,
+        base: Entity,
+        macro_name: Spanned<GlobalIdentifier, FileName>,
+    ) -> Result<ParsedEntity, ErrorReported> {
+        log::trace!(
+            "ModuleDeclaration::parse(base={}, macro_name={})",
+            base.debug_with(parser),
+            macro_name.debug_with(parser)
+        );
+
+        let module_name = parser.expect(SkipNewline(SpannedGlobalIdentifier))?;
+
+        // The entities within the module are parsed right away (much
+        // as the fields of a struct are), and so we need to know the
+        // module's own entity first, as it is their base.
+        let entity = EntityData::ItemName {
+            base,
+            kind: ItemKind::Module,
+            id: module_name.value,
+        }
+        .intern(parser);
+
+        let entities = parser
+            .expect(SkipNewline(Delimited(
+                Curlies,
+                SeparatedList(EntitySyntax::new(entity), Semicolon),
+            )))
+            .unwrap_or_else(|ErrorReported(_)| Seq::default());
+
+        let full_span = macro_name.span.extended_until_end_of(parser.last_span());
+        let characteristic_span = module_name.span;
+
+        Ok(ParsedEntity::new(
+            entity,
+            full_span,
+            characteristic_span,
+            ParsedEntityThunk::new(ParsedModule { entities }),
+        ))
+    }
+}
+
+struct ParsedModule {
+    entities: Seq<ParsedEntity>,
+}
+
+impl LazyParsedEntity for ParsedModule {
+    fn parse_children(
+        &self,
+        _entity: Entity,
+        _db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<Seq<ParsedEntity>> {
+        WithError::ok(self.entities.clone())
+    }
+
+    fn parse_generic_declarations(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<Result<Arc<ty::GenericDeclarations>, ErrorReported>> {
+        InvalidParsedEntity.parse_generic_declarations(entity, db)
+    }
+
+    fn parse_type(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<ty::Ty<Declaration>> {
+        InvalidParsedEntity.parse_type(entity, db)
+    }
+
+    fn parse_signature(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<Result<ty::Signature<Declaration>, ErrorReported>> {
+        InvalidParsedEntity.parse_signature(entity, db)
+    }
+
+    fn parse_fn_body(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<hir::FnBody> {
+        InvalidParsedEntity.parse_fn_body(entity, db)
+    }
+
+    fn parse_use_path(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> Seq<Spanned<GlobalIdentifier, FileName>> {
+        InvalidParsedEntity.parse_use_path(entity, db)
+    }
+}
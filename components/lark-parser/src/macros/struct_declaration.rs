@@ -1,3 +1,4 @@
+use crate::macros::function_declaration::{Method, ParsedMethod};
 use crate::macros::EntityMacroDefinition;
 use crate::parser::Parser;
 use crate::syntax::delimited::Delimited;
@@ -6,10 +7,13 @@ use crate::syntax::entity::{
     ParsedEntityThunk,
 };
 use crate::syntax::field::{Field, ParsedField};
+use crate::syntax::generics::GenericParameters;
 use crate::syntax::identifier::SpannedGlobalIdentifier;
 use crate::syntax::list::CommaList;
 use crate::syntax::sigil::Curlies;
 use crate::syntax::skip_newline::SkipNewline;
+use crate::syntax::Syntax;
+use lark_debug_derive::DebugWith;
 use lark_debug_with::DebugWith;
 use lark_entity::Entity;
 use lark_entity::EntityData;
@@ -29,8 +33,9 @@ use lark_ty::declaration::Declaration;
 use std::sync::Arc;
 
 /// ```ignore
-/// struct <id> {
+/// struct <id> [ `<` <id> `>` ] {
 ///   <id>: <ty> // separated by `,` or newline
+///   def <id>(self, ...) { ... } // methods, in any order with the fields
 /// }
 /// ```
 #[derive(Default)]
@@ -52,9 +57,15 @@ impl EntityMacroDefinition for StructDeclaration {
         log::trace!("StructDeclaration::parse: parsing name");
         let struct_name = parser.expect(SkipNewline(SpannedGlobalIdentifier))?;
 
-        log::trace!("StructDeclaration::parse: parsing fields");
-        let fields = parser
-            .expect(SkipNewline(Delimited(Curlies, CommaList(Field))))
+        log::trace!("StructDeclaration::parse: parsing generic parameters");
+        let generic_parameters = match parser.parse_if_present(GenericParameters) {
+            Some(parameters) => parameters.unwrap_or_else(|ErrorReported(_)| Seq::default()),
+            None => Seq::default(),
+        };
+
+        log::trace!("StructDeclaration::parse: parsing members");
+        let members = parser
+            .expect(SkipNewline(Delimited(Curlies, CommaList(StructMember))))
             .unwrap_or_else(|ErrorReported(_)| Seq::default());
 
         log::trace!("StructDeclaration::parse: done");
@@ -72,13 +83,42 @@ impl EntityMacroDefinition for StructDeclaration {
             entity,
             full_span,
             characteristic_span,
-            ParsedEntityThunk::new(ParsedStructDeclaration { fields }),
+            ParsedEntityThunk::new(ParsedStructDeclaration {
+                generic_parameters,
+                members,
+            }),
         ))
     }
 }
 
+/// A field or a method within the body of a struct.
+#[derive(DebugWith)]
+struct StructMember;
+
+enum ParsedStructMember {
+    Field(Spanned<ParsedField, FileName>),
+    Method(Spanned<ParsedMethod, FileName>),
+}
+
+impl Syntax<'parse> for StructMember {
+    type Data = ParsedStructMember;
+
+    fn test(&mut self, parser: &Parser<'parse>) -> bool {
+        parser.test(Method) || parser.test(Field)
+    }
+
+    fn expect(&mut self, parser: &mut Parser<'parse>) -> Result<Self::Data, ErrorReported> {
+        if parser.test(Method) {
+            Ok(ParsedStructMember::Method(parser.expect(Method)?))
+        } else {
+            Ok(ParsedStructMember::Field(parser.expect(Field)?))
+        }
+    }
+}
+
 struct ParsedStructDeclaration {
-    fields: Seq<Spanned<ParsedField, FileName>>,
+    generic_parameters: Seq<Spanned<GlobalIdentifier, FileName>>,
+    members: Seq<ParsedStructMember>,
 }
 
 impl LazyParsedEntity for ParsedStructDeclaration {
@@ -88,22 +128,43 @@ impl LazyParsedEntity for ParsedStructDeclaration {
         db: &dyn LazyParsedEntityDatabase,
     ) -> WithError<Seq<ParsedEntity>> {
         WithError::ok(
-            self.fields
+            self.members
                 .iter()
-                .map(|Spanned { value: field, span }| {
-                    let field_entity = EntityData::MemberName {
-                        base: entity,
-                        kind: MemberKind::Field,
-                        id: field.name.value,
+                .map(|member| match member {
+                    ParsedStructMember::Field(Spanned { value: field, span }) => {
+                        let field_entity = EntityData::MemberName {
+                            base: entity,
+                            kind: MemberKind::Field,
+                            id: field.name.value,
+                        }
+                        .intern(&db);
+
+                        ParsedEntity::new(
+                            field_entity,
+                            *span,
+                            field.name.span,
+                            ParsedEntityThunk::new(field.clone()),
+                        )
+                    }
+
+                    ParsedStructMember::Method(Spanned {
+                        value: method,
+                        span,
+                    }) => {
+                        let method_entity = EntityData::MemberName {
+                            base: entity,
+                            kind: MemberKind::Method,
+                            id: method.name.value,
+                        }
+                        .intern(&db);
+
+                        ParsedEntity::new(
+                            method_entity,
+                            *span,
+                            method.name.span,
+                            ParsedEntityThunk::new(method.declaration.clone()),
+                        )
                     }
-                    .intern(&db);
-
-                    ParsedEntity::new(
-                        field_entity,
-                        *span,
-                        field.name.span,
-                        ParsedEntityThunk::new(field.clone()),
-                    )
                 })
                 .collect(),
         )
@@ -111,11 +172,14 @@ impl LazyParsedEntity for ParsedStructDeclaration {
 
     fn parse_generic_declarations(
         &self,
-        _entity: Entity,
-        _db: &dyn LazyParsedEntityDatabase,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
     ) -> WithError<Result<Arc<ty::GenericDeclarations>, ErrorReported>> {
-        // FIXME -- no support for generics yet
-        WithError::ok(Ok(ty::GenericDeclarations::empty(None)))
+        crate::type_conversion::generic_declarations_from_parameters(
+            db,
+            entity,
+            &self.generic_parameters,
+        )
     }
 
     fn parse_signature(
@@ -131,16 +195,19 @@ impl LazyParsedEntity for ParsedStructDeclaration {
         entity: Entity,
         db: &dyn LazyParsedEntityDatabase,
     ) -> WithError<ty::Ty<Declaration>> {
-        // For each struct `Foo`, the "type" is just `own Foo`
+        // For each struct `Foo<A, B>`, the "type" is just `own Foo<A, B>`
         match db.generic_declarations(entity).into_value() {
             Ok(generic_declarations) => {
-                assert!(generic_declarations.is_empty());
+                assert!(generic_declarations.parent_item.is_none());
                 let ty = crate::type_conversion::declaration_ty_named(
                     &db,
                     entity,
                     ty::declaration::DeclaredPermKind::Own,
                     ty::ReprKind::Direct,
-                    ty::Generics::empty(),
+                    crate::type_conversion::bound_var_generics(
+                        &db,
+                        generic_declarations.declarations.len(),
+                    ),
                 );
                 WithError::ok(ty)
             }
@@ -158,4 +225,12 @@ impl LazyParsedEntity for ParsedStructDeclaration {
             entity.debug_with(db)
         )
     }
+
+    fn parse_use_path(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> Seq<Spanned<GlobalIdentifier, FileName>> {
+        InvalidParsedEntity.parse_use_path(entity, db)
+    }
 }
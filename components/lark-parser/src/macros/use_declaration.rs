@@ -0,0 +1,122 @@
+use crate::macros::EntityMacroDefinition;
+use crate::parser::Parser;
+use crate::syntax::entity::{
+    InvalidParsedEntity, LazyParsedEntity, LazyParsedEntityDatabase, ParsedEntity,
+    ParsedEntityThunk,
+};
+use crate::syntax::path::ItemPath;
+use crate::syntax::skip_newline::SkipNewline;
+use lark_debug_with::DebugWith;
+use lark_entity::Entity;
+use lark_entity::EntityData;
+use lark_entity::ItemKind;
+use lark_error::ErrorReported;
+use lark_error::WithError;
+use lark_hir as hir;
+use lark_intern::Intern;
+use lark_seq::Seq;
+use lark_span::FileName;
+use lark_span::Spanned;
+use lark_string::GlobalIdentifier;
+use lark_ty as ty;
+use lark_ty::declaration::Declaration;
+use std::sync::Arc;
+
+/// ```ignore
+/// use <id> `::` <id> ... `::` <id>
+/// ```
+///
+/// Brings the item at the end of the path into scope under its own
+/// name. The path starts from the items at the top of the project's
+/// files.
+#[derive(Default)]
+pub struct UseDeclaration;
+
+impl EntityMacroDefinition for UseDeclaration {
+    fn expect(
+        &self,
+        parser: &mut Parser<'_>,
+        base: Entity,
+        macro_name: Spanned<GlobalIdentifier, FileName>,
+    ) -> Result<ParsedEntity, ErrorReported> {
+        log::trace!(
+            "UseDeclaration::parse(base={}, macro_name={})",
+            base.debug_with(parser),
+            macro_name.debug_with(parser)
+        );
+
+        let path = parser.expect(SkipNewline(ItemPath))?;
+        let imported_name = *path.last().unwrap();
+
+        let entity = EntityData::ItemName {
+            base,
+            kind: ItemKind::Use,
+            id: imported_name.value,
+        }
+        .intern(parser);
+
+        let full_span = macro_name.span.extended_until_end_of(parser.last_span());
+        let characteristic_span = imported_name.span;
+
+        Ok(ParsedEntity::new(
+            entity,
+            full_span,
+            characteristic_span,
+            ParsedEntityThunk::new(ParsedUseDeclaration { path }),
+        ))
+    }
+}
+
+struct ParsedUseDeclaration {
+    path: Seq<Spanned<GlobalIdentifier, FileName>>,
+}
+
+impl LazyParsedEntity for ParsedUseDeclaration {
+    fn parse_children(
+        &self,
+        _entity: Entity,
+        _db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<Seq<ParsedEntity>> {
+        WithError::ok(Seq::default())
+    }
+
+    fn parse_generic_declarations(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<Result<Arc<ty::GenericDeclarations>, ErrorReported>> {
+        InvalidParsedEntity.parse_generic_declarations(entity, db)
+    }
+
+    fn parse_type(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<ty::Ty<Declaration>> {
+        InvalidParsedEntity.parse_type(entity, db)
+    }
+
+    fn parse_signature(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<Result<ty::Signature<Declaration>, ErrorReported>> {
+        InvalidParsedEntity.parse_signature(entity, db)
+    }
+
+    fn parse_fn_body(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<hir::FnBody> {
+        InvalidParsedEntity.parse_fn_body(entity, db)
+    }
+
+    fn parse_use_path(
+        &self,
+        _entity: Entity,
+        _db: &dyn LazyParsedEntityDatabase,
+    ) -> Seq<Spanned<GlobalIdentifier, FileName>> {
+        self.path.clone()
+    }
+}
@@ -12,6 +12,10 @@ use lark_span::{FileName, Span, Spanned};
 use lark_string::{GlobalIdentifier, GlobalIdentifierTables, Text};
 use std::sync::Arc;
 
+/// Token-level parser over a single file. This is what entity macros
+/// (see `EntityMacroDefinition`) use to consume their input, typically
+/// through `expect` and `parse_if_present` with the syntax in
+/// the `syntax` module.
 pub struct Parser<'parse> {
     /// The source file name for the file being parsed; used in error reporting
     file_name: FileName,
@@ -129,7 +133,7 @@ impl Parser<'parse> {
 
     /// Consume the current token and load the next one.  Return the
     /// old token.
-    crate fn shift(&mut self) -> Spanned<LexToken, FileName> {
+    pub fn shift(&mut self) -> Spanned<LexToken, FileName> {
         assert!(!self.is(LexToken::EOF));
 
         self.last_span = self.lookahead_token.span;
@@ -171,14 +175,14 @@ impl Parser<'parse> {
     }
 
     /// Peek at the current lookahead token.
-    crate fn peek(&self) -> Spanned<LexToken, FileName> {
+    pub fn peek(&self) -> Spanned<LexToken, FileName> {
         self.lookahead_token
     }
 
     /// Span covering the space *in between* the previous token
     /// and the current token. This is the span where something
     /// elided would go.
-    crate fn elided_span(&self) -> Span<FileName> {
+    pub fn elided_span(&self) -> Span<FileName> {
         // FIXME -- what should we do regarding whitespace etc?
         Span::new(
             self.file_name,
@@ -188,7 +192,7 @@ impl Parser<'parse> {
     }
 
     /// Span of the current lookahead token.
-    crate fn peek_span(&self) -> Span<FileName> {
+    pub fn peek_span(&self) -> Span<FileName> {
         self.peek().span
     }
 
@@ -197,17 +201,17 @@ impl Parser<'parse> {
     /// things we are looking at.  You basically consume tokens until
     /// the lookahead tells you that you are at the end, and then you
     /// can look at the `last_span`
-    crate fn last_span(&self) -> Span<FileName> {
+    pub fn last_span(&self) -> Span<FileName> {
         self.last_span
     }
 
     /// Peek at the string reprsentation of the current token.
-    crate fn peek_str(&self) -> &'parse str {
+    pub fn peek_str(&self) -> &'parse str {
         &self.input[self.peek_span()]
     }
 
     /// Test if the current token is of the given kind.
-    crate fn is(&self, kind: LexToken) -> bool {
+    pub fn is(&self, kind: LexToken) -> bool {
         kind == self.lookahead_token.value
     }
 
@@ -228,7 +232,7 @@ impl Parser<'parse> {
 
     /// Consumes all subsequent newline characters, returning true if
     /// at least one newline was found.
-    crate fn skip_newlines(&mut self) -> bool {
+    pub fn skip_newlines(&mut self) -> bool {
         let mut count = 0;
         while self.is(LexToken::Newline) {
             self.shift();
@@ -238,7 +242,7 @@ impl Parser<'parse> {
     }
 
     /// Tests whether the syntax applies at the current point.
-    crate fn test(&self, mut syntax: impl Syntax<'parse>) -> bool {
+    pub fn test(&self, mut syntax: impl Syntax<'parse>) -> bool {
         log::trace!(
             "test({}) at token `{}({})`",
             syntax.debug_with(self),
@@ -256,7 +260,7 @@ impl Parser<'parse> {
 
     /// Parses a `T` if we can and returns true if so; otherwise,
     /// reports an error and returns false.
-    crate fn expect<T>(&'s mut self, mut syntax: T) -> Result<T::Data, ErrorReported>
+    pub fn expect<T>(&'s mut self, mut syntax: T) -> Result<T::Data, ErrorReported>
     where
         T: Syntax<'parse>,
     {
@@ -272,7 +276,7 @@ impl Parser<'parse> {
 
     /// Parse a piece of syntax (if it is present), otherwise returns
     /// `None`. A combination of `test` and `expect`.
-    crate fn parse_if_present<T>(&mut self, mut syntax: T) -> Option<Result<T::Data, ErrorReported>>
+    pub fn parse_if_present<T>(&mut self, mut syntax: T) -> Option<Result<T::Data, ErrorReported>>
     where
         T: Syntax<'parse>,
     {
@@ -291,7 +295,7 @@ impl Parser<'parse> {
     }
 
     /// Report an error with the given message at the given span.
-    crate fn report_error(
+    pub fn report_error(
         &mut self,
         message: impl Into<String>,
         span: Span<FileName>,
@@ -3,13 +3,13 @@ use crate::lexer::definition::LexerState;
 use crate::lexer::token::LexToken;
 use crate::lexer::tools::Tokenizer;
 use crate::parser::Parser;
-use crate::syntax::entity::{EntitySyntax, ParsedEntity, ParsedEntityThunk};
+use crate::syntax::entity::{EntitySyntax, ParsedEntity, ParsedEntityThunk, Visibility};
 use crate::syntax::skip_newline::SkipNewline;
 use crate::ParserDatabase;
 
 use lark_debug_with::DebugWith;
 use lark_entity::MemberKind;
-use lark_entity::{Entity, EntityData};
+use lark_entity::{Entity, EntityData, LangItem};
 use lark_error::ErrorReported;
 use lark_error::ErrorSentinel;
 use lark_error::WithError;
@@ -51,7 +51,7 @@ crate fn parsed_file(db: &impl ParserDatabase, file_name: FileName) -> WithError
     log::debug!("parsed_file({})", file_name.debug_with(db));
 
     let file_entity = EntityData::InputFile { file: file_name }.intern(db);
-    let entity_macro_definitions = crate::macro_definitions(&db, file_entity);
+    let entity_macro_definitions = crate::macro_definitions(db, file_entity);
     let input = &db.file_text(file_name);
     let tokens = &db.file_tokens(file_name).into_value();
     let parser = Parser::new(file_name, db, &entity_macro_definitions, input, tokens, 0);
@@ -89,6 +89,7 @@ crate fn parsed_entity(db: &impl ParserDatabase, entity: Entity) -> ParsedEntity
                 entity: entity,
                 full_span: parsed_file.span,
                 characteristic_span: parsed_file.span,
+                visibility: Visibility::Public,
                 thunk: ParsedEntityThunk::new(parsed_file),
             }
         }
@@ -234,6 +235,18 @@ crate fn member_entity(
     kind: MemberKind,
     name: GlobalIdentifier,
 ) -> Option<Entity> {
+    // The methods of built-in types have no syntax of their own.
+    if let EntityData::LangItem(item) = owner.untern(db) {
+        let method = match (item, kind, &*name.untern(db)) {
+            (LangItem::List, MemberKind::Method, "len") => LangItem::ListLen,
+            (LangItem::List, MemberKind::Method, "push") => LangItem::ListPush,
+            (LangItem::String, MemberKind::Method, "len") => LangItem::StringLen,
+            (LangItem::String, MemberKind::Method, "substring") => LangItem::StringSubstring,
+            _ => return None,
+        };
+        return Some(EntityData::LangItem(method).intern(db));
+    }
+
     match db.members(owner) {
         Err(report) => Some(Entity::error_sentinel(db, report)),
 
@@ -1,10 +1,18 @@
+use crate::syntax::entity::Visibility;
 use crate::ParserDatabase;
 use lark_entity::Entity;
 use lark_entity::EntityData;
+use lark_entity::ItemKind;
 use lark_entity::LangItem;
+use lark_error::Diagnostic;
+use lark_error::ErrorReported;
+use lark_error::WithError;
 use lark_intern::Intern;
 use lark_intern::Untern;
+use lark_seq::Seq;
+use lark_span::{FileName, Spanned};
 use lark_string::GlobalIdentifier;
+use std::collections::HashMap;
 
 crate fn resolve_name(
     db: &impl ParserDatabase,
@@ -12,44 +20,21 @@ crate fn resolve_name(
     name: GlobalIdentifier,
 ) -> Option<Entity> {
     match scope.untern(db) {
-        EntityData::InputFile { .. } => {
-            db.child_entities(scope)
-                .iter()
-                .cloned()
-                .filter(|entity| match entity.untern(db) {
-                    EntityData::ItemName { id, .. } | EntityData::MemberName { id, .. } => {
-                        id == name
-                    }
-
-                    EntityData::LangItem(_)
-                    | EntityData::Error(_)
-                    | EntityData::InputFile { .. } => false,
-                })
-                .next()
-                .or_else(|| {
-                    // Implicit root scope:
-                    let bool_id = "bool".intern(db);
-                    let int_id = "int".intern(db);
-                    let uint_id = "uint".intern(db);
-                    let false_id = "false".intern(db);
-                    let true_id = "true".intern(db);
-                    let debug_id = "debug".intern(db);
-                    if name == bool_id {
-                        Some(EntityData::LangItem(LangItem::Boolean).intern(db))
-                    } else if name == int_id {
-                        Some(EntityData::LangItem(LangItem::Int).intern(db))
-                    } else if name == uint_id {
-                        Some(EntityData::LangItem(LangItem::Uint).intern(db))
-                    } else if name == false_id {
-                        Some(EntityData::LangItem(LangItem::False).intern(db))
-                    } else if name == true_id {
-                        Some(EntityData::LangItem(LangItem::True).intern(db))
-                    } else if name == debug_id {
-                        Some(EntityData::LangItem(LangItem::Debug).intern(db))
-                    } else {
-                        None
-                    }
-                })
+        EntityData::InputFile { .. } => child_named(db, scope, name)
+            .or_else(|| {
+                // Items from the other files in the project:
+                project_item_named(db, scope, name)
+            })
+            .or_else(|| root_scope_item(db, name)),
+
+        EntityData::ItemName {
+            kind: ItemKind::Module,
+            base,
+            ..
+        } => {
+            // Items within the module, then whatever is in scope
+            // where the module itself is declared:
+            child_named(db, scope, name).or_else(|| db.resolve_name(base, name))
         }
 
         EntityData::ItemName { base, .. } => {
@@ -64,3 +49,279 @@ crate fn resolve_name(
         EntityData::Error(_) => Some(scope),
     }
 }
+
+/// Resolves a path like `a::b::C` that appears within `scope`. The
+/// first segment is resolved like any other name (if it cannot be
+/// found, we return `Ok(None)` and leave it to the caller to report
+/// that in whatever way suits it). Each later segment must name an
+/// item, visible from `scope`, within the module named by the
+/// segment before it (or a variant of the enum named by it).
+crate fn resolve_path(
+    db: &impl ParserDatabase,
+    scope: Entity,
+    path: &[Spanned<GlobalIdentifier, FileName>],
+) -> Result<Option<Entity>, Diagnostic> {
+    let (first, rest) = path.split_first().unwrap();
+    match db.resolve_name(scope, first.value) {
+        Some(entity) => resolve_path_within(db, scope, entity, first, rest).map(Some),
+        None => Ok(None),
+    }
+}
+
+crate fn use_target(db: &impl ParserDatabase, entity: Entity) -> WithError<Entity> {
+    let parsed_entity = db.parsed_entity(entity);
+    let path = parsed_entity.thunk.parse_use_path(entity, db);
+    let (first, rest) = match path.split_first() {
+        Some(split) => split,
+        None => {
+            // Only happens if the declaration failed to parse, in
+            // which case an error was already reported.
+            let report = ErrorReported::at_span(parsed_entity.characteristic_span);
+            return WithError::error_sentinel(db, report);
+        }
+    };
+
+    // Paths in `use` declarations start from the top of the project,
+    // and not from the scope of the declaration; in particular, they
+    // never go through other `use` declarations, so there is no
+    // danger of an import that (indirectly) imports itself.
+    let start = match project_item_named(db, entity, first.value)
+        .or_else(|| root_scope_item(db, first.value))
+    {
+        Some(start) => start,
+        None => {
+            let message = format!("cannot find `{}` in this project", first.value.untern(db));
+            return WithError::report_error(db, message, first.span);
+        }
+    };
+
+    match resolve_path_within(db, entity, start, first, rest) {
+        Ok(target) => WithError::ok(target),
+        Err(diagnostic) => WithError::report_error(db, diagnostic.label, diagnostic.span),
+    }
+}
+
+/// Resolves the remaining segments `rest` of a path that appears
+/// within `scope`, where `entity` is the item named by the segment
+/// `previous`.
+fn resolve_path_within(
+    db: &impl ParserDatabase,
+    scope: Entity,
+    mut entity: Entity,
+    mut previous: &Spanned<GlobalIdentifier, FileName>,
+    rest: &[Spanned<GlobalIdentifier, FileName>],
+) -> Result<Entity, Diagnostic> {
+    for segment in rest {
+        // The variants of an enum are named like the items of a
+        // module, as in `Level::Warning`.
+        let container = match entity.untern(db) {
+            EntityData::ItemName {
+                kind: ItemKind::Module,
+                ..
+            } => "module",
+
+            EntityData::ItemName {
+                kind: ItemKind::Enum,
+                ..
+            } => "enum",
+
+            // Some error was already reported about the path so far.
+            EntityData::Error(_) => return Ok(entity),
+
+            _ => {
+                return Err(crate::diagnostic(
+                    format!("`{}` is not a module", previous.value.untern(db)),
+                    previous.span,
+                ));
+            }
+        };
+
+        let item = match declared_child_named(db, entity, segment.value) {
+            Some(item) => item,
+            None => {
+                return Err(crate::diagnostic(
+                    format!(
+                        "cannot find `{}` in {} `{}`",
+                        segment.value.untern(db),
+                        container,
+                        previous.value.untern(db),
+                    ),
+                    segment.span,
+                ));
+            }
+        };
+
+        if !is_visible(db, item, scope) {
+            return Err(crate::diagnostic(
+                format!("`{}` is private", segment.value.untern(db)),
+                segment.span,
+            ));
+        }
+
+        entity = follow_use(db, item);
+        previous = segment;
+    }
+
+    Ok(entity)
+}
+
+/// Whether `item` may be named from within `scope`: `pub` items can
+/// be named anywhere, others only within the module (or file) that
+/// declares them, including any modules nested inside of it.
+fn is_visible(db: &impl ParserDatabase, item: Entity, scope: Entity) -> bool {
+    if db.parsed_entity(item).visibility == Visibility::Public {
+        return true;
+    }
+
+    let declared_in = match item.untern(db) {
+        EntityData::ItemName { base, .. } => base,
+        _ => return true,
+    };
+
+    let mut scope = Some(scope);
+    while let Some(s) = scope {
+        if s == declared_in {
+            return true;
+        }
+
+        scope = match s.untern(db) {
+            EntityData::ItemName { base, .. } | EntityData::MemberName { base, .. } => Some(base),
+            EntityData::InputFile { .. } | EntityData::LangItem(_) | EntityData::Error(_) => None,
+        };
+    }
+
+    false
+}
+
+/// The entity named `name` that is declared directly within `scope`,
+/// if any. A `use` declaration is returned as itself, and not as the
+/// item that it imports.
+fn declared_child_named(
+    db: &impl ParserDatabase,
+    scope: Entity,
+    name: GlobalIdentifier,
+) -> Option<Entity> {
+    db.child_entities(scope)
+        .iter()
+        .cloned()
+        .filter(|entity| match entity.untern(db) {
+            EntityData::ItemName { id, .. } | EntityData::MemberName { id, .. } => id == name,
+
+            EntityData::LangItem(_) | EntityData::Error(_) | EntityData::InputFile { .. } => false,
+        })
+        .next()
+}
+
+/// Like `declared_child_named`, but a `use` declaration stands for
+/// the item that it imports.
+fn child_named(db: &impl ParserDatabase, scope: Entity, name: GlobalIdentifier) -> Option<Entity> {
+    declared_child_named(db, scope, name).map(|entity| follow_use(db, entity))
+}
+
+/// If `entity` is a `use` declaration, the item that it imports;
+/// otherwise, `entity` itself.
+fn follow_use(db: &impl ParserDatabase, entity: Entity) -> Entity {
+    match entity.untern(db) {
+        EntityData::ItemName {
+            kind: ItemKind::Use,
+            ..
+        } => db.use_target(entity).into_value(),
+        _ => entity,
+    }
+}
+
+/// The item named `name` that is declared at the top of one of the
+/// files of the project, if it is visible from `scope`: only `pub`
+/// items can be named from the other files.
+fn project_item_named(
+    db: &impl ParserDatabase,
+    scope: Entity,
+    name: GlobalIdentifier,
+) -> Option<Entity> {
+    db.project_items()
+        .value
+        .iter()
+        .cloned()
+        .filter(|entity| match entity.untern(db) {
+            EntityData::ItemName { id, .. } => id == name,
+            _ => false,
+        })
+        .find(|&entity| is_visible(db, entity, scope))
+}
+
+/// The implicit root scope, which encloses every file.
+fn root_scope_item(db: &impl ParserDatabase, name: GlobalIdentifier) -> Option<Entity> {
+    let bool_id = "bool".intern(db);
+    let int_id = "int".intern(db);
+    let uint_id = "uint".intern(db);
+    let false_id = "false".intern(db);
+    let true_id = "true".intern(db);
+    let debug_id = "debug".intern(db);
+    let list_id = "List".intern(db);
+    let string_id = "String".intern(db);
+    let format_id = "format".intern(db);
+    if name == bool_id {
+        Some(EntityData::LangItem(LangItem::Boolean).intern(db))
+    } else if name == int_id {
+        Some(EntityData::LangItem(LangItem::Int).intern(db))
+    } else if name == uint_id {
+        Some(EntityData::LangItem(LangItem::Uint).intern(db))
+    } else if name == false_id {
+        Some(EntityData::LangItem(LangItem::False).intern(db))
+    } else if name == true_id {
+        Some(EntityData::LangItem(LangItem::True).intern(db))
+    } else if name == debug_id {
+        Some(EntityData::LangItem(LangItem::Debug).intern(db))
+    } else if name == list_id {
+        Some(EntityData::LangItem(LangItem::List).intern(db))
+    } else if name == string_id {
+        Some(EntityData::LangItem(LangItem::String).intern(db))
+    } else if name == format_id {
+        Some(EntityData::LangItem(LangItem::Format).intern(db))
+    } else {
+        None
+    }
+}
+
+crate fn project_items(db: &impl ParserDatabase) -> WithError<Seq<Entity>> {
+    let mut items = vec![];
+    let mut errors = vec![];
+    let mut defined_in = HashMap::new();
+
+    for &file in db.file_names().iter() {
+        let file_entity = EntityData::InputFile { file }.intern(db);
+
+        for &entity in db.child_entities(file_entity).iter() {
+            let id = match entity.untern(db) {
+                // Imports are only in scope in the file that contains them.
+                EntityData::ItemName {
+                    kind: ItemKind::Use,
+                    ..
+                } => continue,
+                EntityData::ItemName { id, .. } => id,
+                _ => continue,
+            };
+
+            if let Some(&previous_file) = defined_in.get(&id) {
+                let span = db.parsed_entity(entity).characteristic_span;
+                errors.push(crate::diagnostic(
+                    format!(
+                        "`{}` is already defined in `{}`",
+                        id.untern(db),
+                        previous_file.untern(db),
+                    ),
+                    span,
+                ));
+                continue;
+            }
+
+            defined_in.insert(id, file);
+            items.push(entity);
+        }
+    }
+
+    WithError {
+        value: Seq::from(items),
+        errors,
+    }
+}
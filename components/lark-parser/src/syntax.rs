@@ -8,10 +8,12 @@ pub mod delimited;
 pub mod entity;
 pub mod field;
 pub mod fn_body;
+pub mod generics;
 pub mod guard;
 pub mod identifier;
 pub mod list;
 pub mod matched;
+pub mod path;
 pub mod sigil;
 pub mod skip_newline;
 pub mod type_reference;
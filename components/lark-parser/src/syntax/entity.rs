@@ -1,4 +1,5 @@
 use crate::lexer::token::LexToken;
+use crate::macros::RegisteredEntityMacro;
 use crate::parser::Parser;
 use crate::syntax::identifier::SpannedGlobalIdentifier;
 use crate::syntax::NonEmptySyntax;
@@ -8,10 +9,12 @@ use lark_debug_derive::DebugWith;
 use lark_debug_with::DebugWith;
 use lark_entity::Entity;
 use lark_entity::EntityTables;
+use lark_error::Diagnostic;
 use lark_error::ErrorReported;
 use lark_error::ErrorSentinel;
 use lark_error::WithError;
 use lark_hir as hir;
+use lark_intern::Intern;
 use lark_seq::Seq;
 use lark_span::FileName;
 use lark_span::Span;
@@ -52,7 +55,16 @@ impl Syntax<'parse> for EntitySyntax {
         // parsed by us
         // ```
 
-        let macro_name = parser.expect(SpannedGlobalIdentifier)?;
+        let mut macro_name = parser.expect(SpannedGlobalIdentifier)?;
+
+        // A leading `pub` is not a macro, but makes the entity that
+        // follows it visible outside of its module.
+        let visibility = if macro_name.value == "pub".intern(parser) {
+            macro_name = parser.expect(SpannedGlobalIdentifier)?;
+            Visibility::Public
+        } else {
+            Visibility::Private
+        };
 
         log::debug!(
             "EntitySyntax::parse(macro_name = {:?})",
@@ -64,7 +76,9 @@ impl Syntax<'parse> for EntitySyntax {
             None => Err(parser.report_error("no macro with this name", macro_name.span))?,
         };
 
-        Ok(macro_definition.expect(parser, self.parent_entity, macro_name)?)
+        let mut parsed_entity = macro_definition.expect(parser, self.parent_entity, macro_name)?;
+        parsed_entity.visibility = visibility;
+        Ok(parsed_entity)
     }
 }
 
@@ -84,12 +98,15 @@ pub struct ParsedEntity {
     /// messages, which are kind of a pain.
     pub characteristic_span: Span<FileName>,
 
+    /// Whether the entity may be named from outside of its module.
+    pub visibility: Visibility,
+
     /// Thunk to extract contents
     pub thunk: ParsedEntityThunk,
 }
 
 impl ParsedEntity {
-    crate fn new(
+    pub fn new(
         entity: Entity,
         full_span: Span<FileName>,
         characteristic_span: Span<FileName>,
@@ -99,11 +116,21 @@ impl ParsedEntity {
             entity,
             full_span,
             characteristic_span,
+            visibility: Visibility::Private,
             thunk,
         }
     }
 }
 
+#[derive(Copy, Clone, Debug, DebugWith, PartialEq, Eq)]
+pub enum Visibility {
+    /// Declared with `pub`: visible everywhere.
+    Public,
+
+    /// Visible only within the module (or file) that declares it.
+    Private,
+}
+
 /// The "parsed entity thunk" contains methods that will recursively
 /// parse the contents of this entity in response to salsa queries
 /// (or, if the contents are already parsed, return pre-parsed bits
@@ -166,6 +193,15 @@ impl ParsedEntityThunk {
     ) -> WithError<hir::FnBody> {
         self.object.parse_fn_body(entity, db)
     }
+
+    /// See [`LazyParsedEntity::parse_use_path`]
+    crate fn parse_use_path(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> Seq<Spanned<GlobalIdentifier, FileName>> {
+        self.object.parse_use_path(entity, db)
+    }
 }
 
 impl std::fmt::Debug for ParsedEntityThunk {
@@ -235,6 +271,14 @@ pub trait LazyParsedEntity {
         entity: Entity,
         db: &dyn LazyParsedEntityDatabase,
     ) -> WithError<hir::FnBody>;
+
+    /// For a `use` declaration, the path of the item that it imports,
+    /// panicking if this entity is not a `use` declaration.
+    fn parse_use_path(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> Seq<Spanned<GlobalIdentifier, FileName>>;
 }
 
 /// The trait given to the [`LazyParsedEntity`] methods. It is a "dyn
@@ -245,6 +289,16 @@ pub trait LazyParsedEntityDatabase:
     /// Looks up a name `name` to see if it matches any entities in the scope of `item_entity`.
     fn resolve_name(&self, item_entity: Entity, name: GlobalIdentifier) -> Option<Entity>;
 
+    /// Resolves a path like `a::b::C` that appears in the scope of
+    /// `item_entity`. Returns `Ok(None)` if the first segment of the
+    /// path is not in scope, and an error if a later segment cannot
+    /// be found or is not visible.
+    fn resolve_path(
+        &self,
+        item_entity: Entity,
+        path: &[Spanned<GlobalIdentifier, FileName>],
+    ) -> Result<Option<Entity>, Diagnostic>;
+
     /// The `file_text` query
     fn file_text(&self, id: FileName) -> Text;
 
@@ -256,6 +310,9 @@ pub trait LazyParsedEntityDatabase:
         &self,
         entity: Entity,
     ) -> WithError<Result<Arc<ty::GenericDeclarations>, ErrorReported>>;
+
+    /// The `registered_entity_macros` query
+    fn registered_entity_macros(&self) -> Seq<RegisteredEntityMacro>;
 }
 
 impl<T: ParserDatabase> LazyParsedEntityDatabase for T {
@@ -267,6 +324,14 @@ impl<T: ParserDatabase> LazyParsedEntityDatabase for T {
         ParserDatabase::resolve_name(self, item_entity, name)
     }
 
+    fn resolve_path(
+        &self,
+        item_entity: Entity,
+        path: &[Spanned<GlobalIdentifier, FileName>],
+    ) -> Result<Option<Entity>, Diagnostic> {
+        crate::scope::resolve_path(self, item_entity, path)
+    }
+
     fn file_tokens(&self, id: FileName) -> WithError<Seq<Spanned<LexToken, FileName>>> {
         ParserDatabase::file_tokens(self, id)
     }
@@ -277,6 +342,10 @@ impl<T: ParserDatabase> LazyParsedEntityDatabase for T {
     ) -> WithError<Result<Arc<ty::GenericDeclarations>, ErrorReported>> {
         ParserDatabase::generic_declarations(self, entity)
     }
+
+    fn registered_entity_macros(&self) -> Seq<RegisteredEntityMacro> {
+        ParserDatabase::registered_entity_macros(self)
+    }
 }
 
 crate struct ErrorParsedEntity {
@@ -323,12 +392,20 @@ impl LazyParsedEntity for ErrorParsedEntity {
     ) -> WithError<hir::FnBody> {
         WithError::ok(ErrorSentinel::error_sentinel(&db, self.err))
     }
+
+    fn parse_use_path(
+        &self,
+        _entity: Entity,
+        _db: &dyn LazyParsedEntityDatabase,
+    ) -> Seq<Spanned<GlobalIdentifier, FileName>> {
+        Seq::default()
+    }
 }
 
 /// Convenience type: implemnts `LazyParsedEntityDatabase` but just
 /// panics.  Use as the impl for methods you don't support on a
 /// certain kind of entity.
-crate struct InvalidParsedEntity;
+pub struct InvalidParsedEntity;
 
 impl LazyParsedEntity for InvalidParsedEntity {
     fn parse_children(
@@ -382,4 +459,15 @@ impl LazyParsedEntity for InvalidParsedEntity {
             entity.debug_with(db)
         )
     }
+
+    fn parse_use_path(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> Seq<Spanned<GlobalIdentifier, FileName>> {
+        panic!(
+            "cannot invoke `parse_use_path` on {:?}",
+            entity.debug_with(db)
+        )
+    }
 }
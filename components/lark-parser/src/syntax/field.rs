@@ -11,10 +11,12 @@ use crate::syntax::type_reference::{ParsedTypeReference, TypeReference};
 use crate::syntax::Syntax;
 use lark_debug_derive::DebugWith;
 use lark_entity::Entity;
+use lark_entity::EntityData;
 use lark_error::ErrorReported;
 use lark_error::ResultExt;
 use lark_error::WithError;
 use lark_hir as hir;
+use lark_intern::Untern;
 use lark_seq::Seq;
 use lark_span::FileName;
 use lark_span::Spanned;
@@ -27,7 +29,7 @@ use std::sync::Arc;
 pub struct Field;
 
 /// Represents a parse of something like `foo: Type`
-#[derive(Copy, Clone, DebugWith)]
+#[derive(Clone, DebugWith)]
 pub struct ParsedField {
     pub name: Spanned<GlobalIdentifier, FileName>,
     pub ty: ParsedTypeReference,
@@ -67,10 +69,16 @@ impl LazyParsedEntity for ParsedField {
 
     fn parse_generic_declarations(
         &self,
-        _entity: Entity,
-        _db: &dyn LazyParsedEntityDatabase,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
     ) -> WithError<Result<Arc<ty::GenericDeclarations>, ErrorReported>> {
-        WithError::ok(Ok(ty::GenericDeclarations::empty(None)))
+        // The type of a field can reference the generic parameters
+        // of its struct.
+        let parent_item = match entity.untern(&db) {
+            EntityData::MemberName { base, .. } => Some(base),
+            _ => None,
+        };
+        WithError::ok(Ok(ty::GenericDeclarations::empty(parent_item)))
     }
 
     fn parse_type(
@@ -96,4 +104,12 @@ impl LazyParsedEntity for ParsedField {
     ) -> WithError<hir::FnBody> {
         InvalidParsedEntity.parse_fn_body(entity, db)
     }
+
+    fn parse_use_path(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> Seq<Spanned<GlobalIdentifier, FileName>> {
+        InvalidParsedEntity.parse_use_path(entity, db)
+    }
 }
@@ -8,15 +8,26 @@ use crate::syntax::identifier::SpannedGlobalIdentifier;
 use crate::syntax::identifier::SpannedLocalIdentifier;
 use crate::syntax::list::CommaList;
 use crate::syntax::list::SeparatedList;
+use crate::syntax::sigil::CloseParenthesis;
 use crate::syntax::sigil::Colon;
+use crate::syntax::sigil::Comma;
 use crate::syntax::sigil::Curlies;
 use crate::syntax::sigil::Dot;
+use crate::syntax::sigil::DoubleColon;
+use crate::syntax::sigil::Else;
 use crate::syntax::sigil::Equals;
 use crate::syntax::sigil::ExclamationPoint;
+use crate::syntax::sigil::FatArrow;
+use crate::syntax::sigil::If;
+use crate::syntax::sigil::In;
 use crate::syntax::sigil::Let;
+use crate::syntax::sigil::Minus;
 use crate::syntax::sigil::OpenParenthesis;
+use crate::syntax::sigil::OpenSquare;
 use crate::syntax::sigil::Parentheses;
 use crate::syntax::sigil::Semicolon;
+use crate::syntax::sigil::Squares;
+use crate::syntax::sigil::Underscore;
 use crate::syntax::skip_newline::SkipNewline;
 use crate::syntax::Syntax;
 use derive_new::new;
@@ -24,6 +35,9 @@ use lark_collections::FxIndexMap;
 use lark_debug_derive::DebugWith;
 use lark_debug_with::DebugWith;
 use lark_entity::Entity;
+use lark_entity::EntityData;
+use lark_entity::LangItem;
+use lark_entity::MemberKind;
 use lark_error::ErrorReported;
 use lark_error::WithError;
 use lark_hir as hir;
@@ -53,15 +67,30 @@ use std::sync::Arc;
 // Place = Identifier
 //    | Value // temporary
 //    | Place "." Identifier // field
+//    | Place "[" Expression "]" // element of a list
 //
 // # Factored into "almost LL" form:
 //
-// Expression = Expression5
+// Expression = Expression7
+//
+// Expression7 = {
+//   Expression6,
+//   Expression7 \n* `||` Expression6,
+// }
+//
+// Expression6 = {
+//   Expression5,
+//   Expression6 \n* `&&` Expression5,
+// }
 //
 // Expression5 = {
 //   Expression4,
-//   Expression5 \n* `==` Expression4,
-//   Expression5 \n* `!=` Expression4,
+//   Expression4 \n* `==` Expression4,
+//   Expression4 \n* `!=` Expression4,
+//   Expression4 \n* `<` Expression4,
+//   Expression4 \n* `<=` Expression4,
+//   Expression4 \n* `>` Expression4,
+//   Expression4 \n* `>=` Expression4,
 // }
 //
 // Expression4 = {
@@ -81,6 +110,8 @@ use std::sync::Arc;
 //   UnaryOp Expression0,
 // }
 //
+// UnaryOp = `!` | `-`
+//
 // Expression1 = {
 //   Expression0 "(" Comma(Expression) ")"
 //   Expression0 "(" Comma(Field) ")"
@@ -89,6 +120,8 @@ use std::sync::Arc;
 //
 // MemberAccess = {
 //   \n* "." Identifier,
+//   \n* "." Integer, // element of a tuple
+//   "[" Expression "]", // element of a list
 //   "(" Comma(Expression) ")",
 // }
 //
@@ -96,8 +129,25 @@ use std::sync::Arc;
 //   Literal
 //   Identifier,
 //   "(" \n* Expression \n* ")",  // Should we allow newlines *anywhere* here?
+//   "(" \n* Expression "," Comma(Expression) ")", // tuple
+//   "[" Comma(Expression) "]", // list
 //   Block,
-//   "if" Expression Block [ "else" Block ]
+//   "if" Expression Block [ "else" (Block | Expression0) ]
+//   "match" Expression `{` Comma(MatchArm) `}`
+//   "while" Expression Block
+//   "for" Identifier "in" Expression Block
+//   "break"
+//   "continue"
+//   "return" [ Expression ]
+// }
+//
+// MatchArm = {
+//   Pattern `=>` Expression
+// }
+//
+// Pattern = {
+//   `_`
+//   Path [ "(" Comma(Identifier) ")" ]
 // }
 //
 // Block = {
@@ -106,7 +156,9 @@ use std::sync::Arc;
 //
 // Statement = {
 //   \n* Expression Terminator,
+//   \n* Expression `=` Expression Terminator, // the left side must be a place
 //   \n* `let` Identifier [`:` Ty ] `=` Expression Terminator,
+//   \n* `let` `(` Comma(Identifier) `)` `=` Expression Terminator,
 // }
 //
 // Terminator = {
@@ -130,6 +182,7 @@ crate fn parse_fn_body(
         db,
         item_entity,
         variables: Default::default(),
+        loop_depth: 0,
         fn_body_tables: Default::default(),
     };
 
@@ -212,6 +265,15 @@ impl hir::SpanIndex for ParsedExpression {
 enum ParsedStatement {
     Expression(hir::Expression),
     Let(Span<FileName>, hir::Variable, Option<hir::Expression>),
+
+    /// `let (a, b) = pair`: the hidden variable that holds the tuple,
+    /// its initializer and the variables bound to its elements.
+    LetTuple(
+        Span<FileName>,
+        hir::Variable,
+        hir::Expression,
+        hir::List<hir::Variable>,
+    ),
 }
 
 struct ExpressionScope<'parse> {
@@ -224,6 +286,10 @@ struct ExpressionScope<'parse> {
     // should do so).
     variables: Rc<FxIndexMap<GlobalIdentifier, hir::Variable>>,
 
+    /// The number of `while` and `for` loops enclosing the expression being
+    /// parsed; `break` and `continue` are only legal inside of one.
+    loop_depth: usize,
+
     fn_body_tables: hir::FnBodyTables,
 }
 
@@ -298,6 +364,47 @@ impl ExpressionScope<'parse> {
     fn unit_expression(&mut self, span: Span<FileName>) -> hir::Expression {
         self.add(span, hir::ExpressionData::Unit {})
     }
+
+    /// Creates the expression for `let (a, b) = initializer` followed
+    /// by `body`: the tuple is stored in the variable `tuple`, and then
+    /// each element is bound to its variable, like `let a = tuple.0`.
+    fn let_tuple_expression(
+        &mut self,
+        span: Span<FileName>,
+        tuple: hir::Variable,
+        initializer: hir::Expression,
+        elements: hir::List<hir::Variable>,
+        body: hir::Expression,
+    ) -> hir::Expression {
+        let elements: Vec<hir::Variable> = elements.iter(&*self).collect();
+
+        let mut body = body;
+        for (index, &element) in elements.iter().enumerate().rev() {
+            let element_span = self.span(element);
+            let owner = self.add(element_span, hir::PlaceData::Variable(tuple));
+            let text = index.to_string().intern(&self.db);
+            let name = self.add(element_span, hir::IdentifierData { text });
+            let place = self.add(element_span, hir::PlaceData::Field { owner, name });
+            let element_initializer = self.add(element_span, hir::ExpressionData::Place { place });
+            body = self.add(
+                span,
+                hir::ExpressionData::Let {
+                    variable: element,
+                    initializer: Some(element_initializer),
+                    body,
+                },
+            );
+        }
+
+        self.add(
+            span,
+            hir::ExpressionData::Let {
+                variable: tuple,
+                initializer: Some(initializer),
+                body,
+            },
+        )
+    }
 }
 
 impl AsRef<hir::FnBodyTables> for ExpressionScope<'_> {
@@ -360,12 +467,58 @@ struct Expression<'me, 'parse> {
 impl Syntax<'parse> for Expression<'me, 'parse> {
     type Data = ParsedExpression;
 
+    fn test(&mut self, parser: &Parser<'parse>) -> bool {
+        parser.test(Expression7::new(self.scope))
+    }
+
+    fn expect(&mut self, parser: &mut Parser<'parse>) -> Result<Self::Data, ErrorReported> {
+        parser.expect(Expression7::new(self.scope))
+    }
+}
+
+#[derive(new, DebugWith)]
+struct Expression7<'me, 'parse> {
+    scope: &'me mut ExpressionScope<'parse>,
+}
+
+impl Syntax<'parse> for Expression7<'me, 'parse> {
+    type Data = ParsedExpression;
+
+    fn test(&mut self, parser: &Parser<'parse>) -> bool {
+        parser.test(Expression6::new(self.scope))
+    }
+
+    fn expect(&mut self, parser: &mut Parser<'parse>) -> Result<Self::Data, ErrorReported> {
+        parser.expect(BinaryOperatorExpression {
+            expr: Expression6::new(self.scope),
+            op: BinaryOperator::new(BINARY_OPERATORS_EXPR7),
+        })
+    }
+}
+
+#[derive(new, DebugWith)]
+struct Expression6<'me, 'parse> {
+    scope: &'me mut ExpressionScope<'parse>,
+}
+
+impl AsMut<ExpressionScope<'parse>> for Expression6<'_, 'parse> {
+    fn as_mut(&mut self) -> &mut ExpressionScope<'parse> {
+        self.scope
+    }
+}
+
+impl Syntax<'parse> for Expression6<'me, 'parse> {
+    type Data = ParsedExpression;
+
     fn test(&mut self, parser: &Parser<'parse>) -> bool {
         parser.test(Expression5::new(self.scope))
     }
 
     fn expect(&mut self, parser: &mut Parser<'parse>) -> Result<Self::Data, ErrorReported> {
-        parser.expect(Expression5::new(self.scope))
+        parser.expect(BinaryOperatorExpression {
+            expr: Expression5::new(self.scope),
+            op: BinaryOperator::new(BINARY_OPERATORS_EXPR6),
+        })
     }
 }
 
@@ -374,6 +527,12 @@ struct Expression5<'me, 'parse> {
     scope: &'me mut ExpressionScope<'parse>,
 }
 
+impl AsMut<ExpressionScope<'parse>> for Expression5<'_, 'parse> {
+    fn as_mut(&mut self) -> &mut ExpressionScope<'parse> {
+        self.scope
+    }
+}
+
 impl Syntax<'parse> for Expression5<'me, 'parse> {
     type Data = ParsedExpression;
 
@@ -496,15 +655,22 @@ where
                 );
 
                 match operator {
-                    hir::BinaryOperator::Equals | hir::BinaryOperator::NotEquals => {
-                        // Do not parse `a == b == c` etc
+                    hir::BinaryOperator::Equals
+                    | hir::BinaryOperator::NotEquals
+                    | hir::BinaryOperator::LessThan
+                    | hir::BinaryOperator::LessThanOrEqual
+                    | hir::BinaryOperator::GreaterThan
+                    | hir::BinaryOperator::GreaterThanOrEqual => {
+                        // Do not parse `a == b == c` or `a < b < c` etc
                         break;
                     }
 
                     hir::BinaryOperator::Add
                     | hir::BinaryOperator::Subtract
                     | hir::BinaryOperator::Multiply
-                    | hir::BinaryOperator::Divide => {
+                    | hir::BinaryOperator::Divide
+                    | hir::BinaryOperator::And
+                    | hir::BinaryOperator::Or => {
                         // `a + b + c` is ok
                     }
                 }
@@ -524,14 +690,22 @@ const BINARY_OPERATORS_EXPR3: &[(&str, hir::BinaryOperator)] = &[
 
 const BINARY_OPERATORS_EXPR4: &[(&str, hir::BinaryOperator)] = &[
     ("+", hir::BinaryOperator::Add),
-    ("_", hir::BinaryOperator::Subtract),
+    ("-", hir::BinaryOperator::Subtract),
 ];
 
 const BINARY_OPERATORS_EXPR5: &[(&str, hir::BinaryOperator)] = &[
     ("==", hir::BinaryOperator::Equals),
     ("!=", hir::BinaryOperator::NotEquals),
+    ("<", hir::BinaryOperator::LessThan),
+    ("<=", hir::BinaryOperator::LessThanOrEqual),
+    (">", hir::BinaryOperator::GreaterThan),
+    (">=", hir::BinaryOperator::GreaterThanOrEqual),
 ];
 
+const BINARY_OPERATORS_EXPR6: &[(&str, hir::BinaryOperator)] = &[("&&", hir::BinaryOperator::And)];
+
+const BINARY_OPERATORS_EXPR7: &[(&str, hir::BinaryOperator)] = &[("||", hir::BinaryOperator::Or)];
+
 #[derive(new, DebugWith)]
 struct BinaryOperator {
     operators: &'static [(&'static str, hir::BinaryOperator)],
@@ -595,6 +769,27 @@ impl Syntax<'parse> for Expression2<'me, 'parse> {
                 .expect(SkipNewline(Expression2::new(self.scope)))?
                 .to_hir_expression(self.scope);
             let span = operator.span.extended_until_end_of(self.scope.span(value));
+
+            // A `-` right before an integer literal is part of the
+            // literal, so that `-2147483648` fits in an `int`.
+            if let hir::ExpressionData::Literal { data } = self.scope[value] {
+                let text = data.value.untern(&self.scope.db);
+                if operator.value == hir::UnaryOperator::Negate
+                    && data.kind == hir::LiteralKind::Integer
+                    && !text.starts_with('-')
+                {
+                    let text = format!("-{}", text);
+                    let data = hir::LiteralData {
+                        kind: hir::LiteralKind::Integer,
+                        value: text.intern(&self.scope.db),
+                    };
+                    self.scope.fn_body_tables.expressions[value] =
+                        hir::ExpressionData::Literal { data };
+                    self.scope.fn_body_tables.spans.insert(value.into(), span);
+                    return Ok(ParsedExpression::Expression(value));
+                }
+            }
+
             return Ok(ParsedExpression::Expression(self.scope.add(
                 span,
                 hir::ExpressionData::Unary {
@@ -615,10 +810,14 @@ impl Syntax<'parse> for UnaryOperator {
     type Data = Spanned<hir::UnaryOperator, FileName>;
 
     fn test(&mut self, parser: &Parser<'parse>) -> bool {
-        parser.test(ExclamationPoint)
+        parser.test(ExclamationPoint) || parser.test(Minus)
     }
 
     fn expect(&mut self, parser: &mut Parser<'parse>) -> Result<Self::Data, ErrorReported> {
+        if let Some(spanned) = parser.parse_if_present(Minus) {
+            return Ok(spanned?.map(|_| hir::UnaryOperator::Negate));
+        }
+
         let spanned = parser.expect(ExclamationPoint)?;
         Ok(spanned.map(|_| hir::UnaryOperator::Not))
     }
@@ -696,8 +895,27 @@ impl Syntax<'parse> for Expression1<'me, 'parse> {
             return Ok(ParsedExpression::Expression(expression));
         }
 
+        // Level::Warning -- a variant without any fields
+        if let ParsedExpression::Place(place) = expr {
+            if let hir::PlaceData::Entity(entity) = self.scope[place] {
+                if let EntityData::MemberName {
+                    kind: MemberKind::Variant,
+                    ..
+                } = entity.untern(&self.scope.db)
+                {
+                    let span = self.scope.span(place);
+                    let fields = hir::List::default();
+                    let expression = self
+                        .scope
+                        .add(span, hir::ExpressionData::Aggregate { entity, fields });
+                    expr = ParsedExpression::Expression(expression);
+                }
+            }
+        }
+
         // foo.bar.baz
         // foo.bar.baz(a, b, c)
+        // foo[i]
         while let Some(member_access) = parser.parse_if_present(MemberAccess::new(expr, self.scope))
         {
             expr = member_access?;
@@ -727,11 +945,40 @@ impl Syntax<'parse> for MemberAccess<'me, 'parse> {
     type Data = ParsedExpression;
 
     fn test(&mut self, parser: &Parser<'parse>) -> bool {
-        parser.test(SkipNewline(Dot))
+        // Unlike `.`, a `[` on the next line starts a new list
+        // expression, so we do not skip newlines before it.
+        parser.test(SkipNewline(Dot)) || parser.test(OpenSquare)
     }
 
     fn expect(&mut self, parser: &mut Parser<'parse>) -> Result<Self::Data, ErrorReported> {
+        // `list[index]`
+        if parser.test(OpenSquare) {
+            let index = parser.expect(Delimited(Squares, HirExpression::new(self.scope)))?;
+            let owner = self.owner.to_hir_place(self.scope);
+            let span = self
+                .scope
+                .span(owner)
+                .extended_until_end_of(parser.last_span());
+            return Ok(ParsedExpression::Place(
+                self.scope.add(span, hir::PlaceData::Index { owner, index }),
+            ));
+        }
+
         parser.expect(SkipNewline(Dot))?;
+
+        // `pair.0` -- the elements of a tuple are fields named by
+        // their index
+        if parser.is(LexToken::Integer) {
+            let text = parser.peek_str().intern(parser);
+            let index = parser.shift();
+            let name = self.scope.add(index.span, hir::IdentifierData { text });
+            let owner = self.owner.to_hir_place(self.scope);
+            let span = self.scope.span(owner).extended_until_end_of(index.span);
+            return Ok(ParsedExpression::Place(
+                self.scope.add(span, hir::PlaceData::Field { owner, name }),
+            ));
+        }
+
         let member_name = parser.expect(HirIdentifier::new(self.scope))?;
 
         if let Some(arguments) =
@@ -890,12 +1137,20 @@ impl Syntax<'parse> for Expression0<'me, 'parse> {
     type Data = ParsedExpression;
 
     fn test(&mut self, parser: &Parser<'parse>) -> bool {
-        SpannedLocalIdentifier.test(parser) || Literal::new(self.scope).test(parser)
+        SpannedLocalIdentifier.test(parser)
+            || Literal::new(self.scope).test(parser)
+            || parser.test(OpenParenthesis)
+            || parser.test(OpenSquare)
     }
 
     fn expect(&mut self, parser: &mut Parser<'parse>) -> Result<Self::Data, ErrorReported> {
         // Expression0 = Identifier
-        // Expression0 = "if" Expression Block [ "else" Block ]
+        // Expression0 = "if" Expression Block [ "else" (Block | Expression0) ]
+        // Expression0 = "match" Expression `{` Comma(MatchArm) `}`
+        // Expression0 = "while" Expression Block
+        // Expression0 = "for" Identifier "in" Expression Block
+        // Expression0 = "break" | "continue"
+        // Expression0 = "return" [ Expression ]
         if parser.test(SpannedLocalIdentifier) {
             let text = parser.expect(SpannedLocalIdentifier)?;
 
@@ -903,8 +1158,17 @@ impl Syntax<'parse> for Expression0<'me, 'parse> {
             if text.value == "if" {
                 let condition = parser.expect(HirExpression::new(self.scope))?;
                 let if_true = parser.expect(Block::new(self.scope))?;
-                let if_false = if let Some(b) = parser.parse_if_present(Block::new(self.scope)) {
-                    b?
+                let if_false = if let Some(else_keyword) = parser.parse_if_present(Else) {
+                    else_keyword?;
+
+                    // `else if ...` chains onto another `if` expression
+                    if parser.test(If) {
+                        parser
+                            .expect(Expression0::new(self.scope))?
+                            .to_hir_expression(self.scope)
+                    } else {
+                        parser.expect(Block::new(self.scope))?
+                    }
                 } else {
                     self.scope.unit_expression(parser.elided_span())
                 };
@@ -921,17 +1185,140 @@ impl Syntax<'parse> for Expression0<'me, 'parse> {
                 return Ok(ParsedExpression::Expression(expression));
             }
 
-            if let Some(variable) = self.scope.lookup_variable(text.value) {
-                let place = self
+            if text.value == "match" {
+                let scrutinee = parser.expect(HirExpression::new(self.scope))?;
+                let arms = parser.expect(SkipNewline(Delimited(
+                    Curlies,
+                    CommaList(MatchArm::new(self.scope)),
+                )))?;
+                let arms =
+                    hir::List::from_iterator(&mut self.scope.fn_body_tables, arms.iter().cloned());
+
+                let expression = self
+                    .scope
+                    .add(text.span, hir::ExpressionData::Match { scrutinee, arms });
+
+                return Ok(ParsedExpression::Expression(expression));
+            }
+
+            if text.value == "while" {
+                let condition = parser.expect(HirExpression::new(self.scope))?;
+
+                self.scope.loop_depth += 1;
+                let body = parser.expect(Block::new(self.scope));
+                self.scope.loop_depth -= 1;
+                let body = body?;
+
+                let expression = self
                     .scope
-                    .add(text.span, hir::PlaceData::Variable(variable));
-                return Ok(ParsedExpression::Place(place));
+                    .add(text.span, hir::ExpressionData::While { condition, body });
+
+                return Ok(ParsedExpression::Expression(expression));
+            }
+
+            if text.value == "for" {
+                let name = parser.expect(HirIdentifier::new(self.scope))?;
+                parser.expect(In)?;
+                let list = parser.expect(HirExpression::new(self.scope))?;
+
+                // The variable is only in scope in the body.
+                let variables_on_entry = self.scope.save_scope();
+                let name_span = self.scope.span(name);
+                let variable = self.scope.add(name_span, hir::VariableData { name });
+                self.scope.introduce_variable(variable);
+
+                self.scope.loop_depth += 1;
+                let body = parser.expect(Block::new(self.scope));
+                self.scope.loop_depth -= 1;
+                self.scope.restore_scope(variables_on_entry);
+                let body = body?;
+
+                let expression = self.scope.add(
+                    text.span,
+                    hir::ExpressionData::For {
+                        variable,
+                        list,
+                        body,
+                    },
+                );
+
+                return Ok(ParsedExpression::Expression(expression));
+            }
+
+            if text.value == "break" || text.value == "continue" {
+                if self.scope.loop_depth == 0 {
+                    parser.report_error(format!("`{}` outside of a loop", text.value), text.span);
+                    let error_expression = self
+                        .scope
+                        .already_reported_error_expression(text.span, hir::ErrorData::Misc);
+                    return Ok(ParsedExpression::Expression(error_expression));
+                }
+
+                let data = if text.value == "break" {
+                    hir::ExpressionData::Break {}
+                } else {
+                    hir::ExpressionData::Continue {}
+                };
+                let expression = self.scope.add(text.span, data);
+
+                return Ok(ParsedExpression::Expression(expression));
+            }
+
+            if text.value == "return" {
+                // A bare `return` gives back `()`.
+                let value = match parser.parse_if_present(HirExpression::new(self.scope)) {
+                    Some(value) => value?,
+                    None => self.scope.unit_expression(parser.elided_span()),
+                };
+                let span = text.span.extended_until_end_of(parser.last_span());
+
+                let expression = self.scope.add(span, hir::ExpressionData::Return { value });
+
+                return Ok(ParsedExpression::Expression(expression));
             }
 
-            let id = text.value.intern(&self.scope.db);
-            if let Some(entity) = self.scope.db.resolve_name(self.scope.item_entity, id) {
-                let place = self.scope.add(text.span, hir::PlaceData::Entity(entity));
-                return Ok(ParsedExpression::Place(place));
+            if parser.test(DoubleColon) {
+                // A path like `geometry::origin`, naming an item in a module.
+                let mut path = vec![Spanned {
+                    value: text.value.intern(&self.scope.db),
+                    span: text.span,
+                }];
+                while let Some(colons) = parser.parse_if_present(DoubleColon) {
+                    colons?;
+                    path.push(parser.expect(SpannedGlobalIdentifier)?);
+                }
+                let span = text.span.extended_until_end_of(parser.last_span());
+
+                match self.scope.db.resolve_path(self.scope.item_entity, &path) {
+                    Ok(Some(entity)) => {
+                        let place = self.scope.add(span, hir::PlaceData::Entity(entity));
+                        return Ok(ParsedExpression::Place(place));
+                    }
+
+                    // The first segment is unknown; reported below.
+                    Ok(None) => {}
+
+                    Err(diagnostic) => {
+                        parser.report_error(diagnostic.label, diagnostic.span);
+                        let error_expression = self
+                            .scope
+                            .already_reported_error_expression(span, hir::ErrorData::Misc);
+                        return Ok(ParsedExpression::Expression(error_expression));
+                    }
+                }
+            } else {
+                if let Some(variable) = self.scope.lookup_variable(text.value) {
+                    let place = self
+                        .scope
+                        .add(text.span, hir::PlaceData::Variable(variable));
+                    return Ok(ParsedExpression::Place(place));
+                }
+
+                let id = text.value.intern(&self.scope.db);
+                if let Some(entity) = self.scope.db.resolve_name(self.scope.item_entity, id) {
+                    let place = self.scope.add(text.span, hir::PlaceData::Entity(entity));
+                    return Ok(ParsedExpression::Place(place));
+                }
             }
 
             let error_expression = self.scope.report_error_expression(
@@ -951,13 +1338,27 @@ impl Syntax<'parse> for Expression0<'me, 'parse> {
         }
 
         // Expression0 = `(` Expression ')'
-        if let Some(expr) = parser.parse_if_present(Delimited(
-            Parentheses,
-            SkipNewline(Expression::new(self.scope)),
-        )) {
+        // Expression0 = `(` Expression `,` Comma(Expression) ')'
+        if let Some(expr) = parser.parse_if_present(ParenthesizedExpression::new(self.scope)) {
             return Ok(expr?);
         }
 
+        // Expression0 = `[` Comma(Expression) `]`
+        let start_span = parser.peek_span();
+        if let Some(elements) = parser.parse_if_present(Delimited(
+            Squares,
+            CommaList(HirExpression::new(self.scope)),
+        )) {
+            let elements = elements?;
+            let elements =
+                hir::List::from_iterator(&mut self.scope.fn_body_tables, elements.iter().cloned());
+            let span = start_span.extended_until_end_of(parser.last_span());
+            let expression = self
+                .scope
+                .add(span, hir::ExpressionData::ListLiteral { elements });
+            return Ok(ParsedExpression::Expression(expression));
+        }
+
         // Expression0 = `{` Block `}`
         if let Some(block) = parser.parse_if_present(Block::new(self.scope)) {
             return Ok(ParsedExpression::Expression(block?));
@@ -968,6 +1369,190 @@ impl Syntax<'parse> for Expression0<'me, 'parse> {
     }
 }
 
+/// An expression in parentheses, which is a tuple if the expression
+/// is followed by a comma, as in `(a, b)` or `(a,)`.
+#[derive(new, DebugWith)]
+struct ParenthesizedExpression<'me, 'parse> {
+    scope: &'me mut ExpressionScope<'parse>,
+}
+
+impl Syntax<'parse> for ParenthesizedExpression<'me, 'parse> {
+    type Data = ParsedExpression;
+
+    fn test(&mut self, parser: &Parser<'parse>) -> bool {
+        parser.test(OpenParenthesis)
+    }
+
+    fn expect(&mut self, parser: &mut Parser<'parse>) -> Result<Self::Data, ErrorReported> {
+        let open = parser.expect(OpenParenthesis)?;
+        let first = parser.expect(SkipNewline(Expression::new(self.scope)))?;
+
+        if parser.parse_if_present(Comma).is_none() {
+            parser.expect(SkipNewline(CloseParenthesis))?;
+            return Ok(first);
+        }
+
+        let first = first.to_hir_expression(self.scope);
+        let rest = parser.expect(CommaList(HirExpression::new(self.scope)))?;
+        parser.expect(CloseParenthesis)?;
+        let span = open.span.extended_until_end_of(parser.last_span());
+
+        // A tuple is an aggregate whose fields are named by the index
+        // of each element, like the `0` in `pair.0`.
+        let elements: Vec<hir::Expression> =
+            std::iter::once(first).chain(rest.iter().cloned()).collect();
+        let fields: Vec<hir::IdentifiedExpression> = elements
+            .iter()
+            .enumerate()
+            .map(|(index, &expression)| {
+                let element_span = self.scope.span(expression);
+                let text = index.to_string().intern(&self.scope.db);
+                let identifier = self.scope.add(element_span, hir::IdentifierData { text });
+                self.scope.add(
+                    element_span,
+                    hir::IdentifiedExpressionData {
+                        identifier,
+                        expression,
+                    },
+                )
+            })
+            .collect();
+        let fields = hir::List::from_iterator(&mut self.scope.fn_body_tables, fields);
+
+        let entity = EntityData::LangItem(LangItem::Tuple(elements.len())).intern(&self.scope.db);
+        Ok(ParsedExpression::Expression(self.scope.add(
+            span,
+            hir::ExpressionData::Aggregate { entity, fields },
+        )))
+    }
+}
+
+#[derive(new, DebugWith)]
+struct MatchArm<'me, 'parse> {
+    scope: &'me mut ExpressionScope<'parse>,
+}
+
+impl Syntax<'parse> for MatchArm<'me, 'parse> {
+    type Data = hir::MatchArm;
+
+    fn test(&mut self, parser: &Parser<'parse>) -> bool {
+        parser.test(Pattern::new(self.scope))
+    }
+
+    fn expect(&mut self, parser: &mut Parser<'parse>) -> Result<Self::Data, ErrorReported> {
+        // The variables bound by the pattern are only in scope
+        // within the body of the arm.
+        let variables_on_entry = self.scope.save_scope();
+
+        let pattern = parser.expect(Pattern::new(self.scope))?;
+        parser.expect(SkipNewline(FatArrow))?;
+        let body = parser.expect(SkipNewline(HirExpression::new(self.scope)))?;
+
+        self.scope.restore_scope(variables_on_entry);
+
+        let span = self
+            .scope
+            .span(pattern)
+            .extended_until_end_of(self.scope.span(body));
+        Ok(self.scope.add(span, hir::MatchArmData { pattern, body }))
+    }
+}
+
+#[derive(new, DebugWith)]
+struct Pattern<'me, 'parse> {
+    scope: &'me mut ExpressionScope<'parse>,
+}
+
+impl Syntax<'parse> for Pattern<'me, 'parse> {
+    type Data = hir::Pattern;
+
+    fn test(&mut self, parser: &Parser<'parse>) -> bool {
+        parser.test(SpannedGlobalIdentifier)
+    }
+
+    fn expect(&mut self, parser: &mut Parser<'parse>) -> Result<Self::Data, ErrorReported> {
+        // Pattern = `_`
+        if let Some(underscore) = parser.parse_if_present(Underscore) {
+            let underscore = underscore?;
+            return Ok(self.scope.add(underscore.span, hir::PatternData::Wildcard));
+        }
+
+        // Pattern = Path [ "(" Comma(Identifier) ")" ]
+        let mut path = vec![parser.expect(SpannedGlobalIdentifier)?];
+        while let Some(colons) = parser.parse_if_present(DoubleColon) {
+            colons?;
+            path.push(parser.expect(SpannedGlobalIdentifier)?);
+        }
+        let path_span = path[0].span.extended_until_end_of(parser.last_span());
+
+        let names = match parser
+            .parse_if_present(Delimited(Parentheses, CommaList(SpannedGlobalIdentifier)))
+        {
+            Some(names) => names?,
+            None => Seq::default(),
+        };
+        let span = path_span.extended_until_end_of(parser.last_span());
+
+        let bindings: Vec<_> = names
+            .iter()
+            .map(|&name| {
+                let identifier = self
+                    .scope
+                    .add(name.span, hir::IdentifierData { text: name.value });
+                let variable = self
+                    .scope
+                    .add(name.span, hir::VariableData { name: identifier });
+                self.scope.introduce_variable(variable);
+                variable
+            })
+            .collect();
+        let bindings = hir::List::from_iterator(&mut self.scope.fn_body_tables, bindings);
+
+        let entity = match self.scope.db.resolve_path(self.scope.item_entity, &path) {
+            Ok(Some(entity)) => match entity.untern(&self.scope.db) {
+                EntityData::MemberName {
+                    kind: MemberKind::Variant,
+                    ..
+                } => Ok(entity),
+
+                // Some error was already reported about the path.
+                EntityData::Error(report) => Err(report),
+
+                _ => {
+                    let last_segment = path[path.len() - 1];
+                    let message = format!(
+                        "`{}` is not a variant",
+                        last_segment.value.untern(&self.scope.db)
+                    );
+                    Err(parser.report_error(message, path_span))
+                }
+            },
+
+            Ok(None) => {
+                let message = format!(
+                    "unknown identifier `{}`",
+                    path[0].value.untern(&self.scope.db)
+                );
+                Err(parser.report_error(message, path[0].span))
+            }
+
+            Err(diagnostic) => Err(parser.report_error(diagnostic.label, diagnostic.span)),
+        };
+
+        match entity {
+            Ok(entity) => Ok(self
+                .scope
+                .add(span, hir::PatternData::Variant { entity, bindings })),
+            Err(ErrorReported(_)) => {
+                let error = self.scope.add(span, hir::ErrorData::Misc);
+                Ok(self
+                    .scope
+                    .add(span, hir::PatternData::Error { error, bindings }))
+            }
+        }
+    }
+}
+
 #[derive(new, DebugWith)]
 struct Literal<'me, 'parse> {
     scope: &'me mut ExpressionScope<'parse>,
@@ -983,12 +1568,20 @@ impl Syntax<'parse> for Literal<'me, 'parse> {
     fn expect(&mut self, parser: &mut Parser<'parse>) -> Result<Self::Data, ErrorReported> {
         let text = parser.peek_str();
         let token = parser.shift();
-        let kind = match token.value {
-            LexToken::Integer => hir::LiteralKind::UnsignedInteger,
-            LexToken::String => hir::LiteralKind::String,
+        let (kind, value) = match token.value {
+            LexToken::Integer => (hir::LiteralKind::Integer, text.intern(parser)),
+            LexToken::String => match unescape_string_literal(text) {
+                Ok(contents) => (hir::LiteralKind::String, contents.intern(parser)),
+                Err(escape) => {
+                    parser
+                        .report_error(format!("unknown character escape `{}`", escape), token.span);
+                    return Ok(self
+                        .scope
+                        .already_reported_error_expression(token.span, hir::ErrorData::Misc));
+                }
+            },
             _ => return Err(parser.report_error("expected a literal", token.span)),
         };
-        let value = text.intern(parser);
         let data = hir::LiteralData { kind, value };
         Ok(self
             .scope
@@ -996,6 +1589,35 @@ impl Syntax<'parse> for Literal<'me, 'parse> {
     }
 }
 
+/// Converts the text of a string literal (including the surrounding
+/// quotes) into the string it represents. If the literal contains an
+/// unknown escape sequence, returns that escape sequence as the error.
+fn unescape_string_literal(text: &str) -> Result<String, String> {
+    assert!(text.len() >= 2 && text.starts_with('"') && text.ends_with('"'));
+
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text[1..text.len() - 1].chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some('0') => result.push('\0'),
+            Some('\\') => result.push('\\'),
+            Some('"') => result.push('"'),
+            Some(c) => return Err(format!("\\{}", c)),
+            None => return Err("\\".to_string()),
+        }
+    }
+
+    Ok(result)
+}
+
 #[derive(new, DebugWith)]
 struct Block<'me, 'parse> {
     scope: &'me mut ExpressionScope<'parse>,
@@ -1052,6 +1674,11 @@ impl Syntax<'parse> for Block<'me, 'parse> {
                     },
                 )
             }
+            ParsedStatement::LetTuple(span, tuple, initializer, elements) => {
+                let body = self.scope.unit_expression(parser.last_span());
+                self.scope
+                    .let_tuple_expression(span, tuple, initializer, elements, body)
+            }
         };
 
         while let Some(previous_statement) = statements_iter.next() {
@@ -1071,6 +1698,9 @@ impl Syntax<'parse> for Block<'me, 'parse> {
                         body: result,
                     },
                 ),
+                ParsedStatement::LetTuple(span, tuple, initializer, elements) => self
+                    .scope
+                    .let_tuple_expression(span, tuple, initializer, elements, result),
             };
         }
 
@@ -1098,8 +1728,33 @@ impl Syntax<'parse> for Statement<'me, 'parse> {
             return r;
         }
 
-        let expression = parser.expect(HirExpression::new(self.scope))?;
-        Ok(ParsedStatement::Expression(expression))
+        let expression = parser.expect(Expression::new(self.scope))?;
+
+        if let Some(equals) = parser.parse_if_present(Equals) {
+            equals?;
+            let value = parser.expect(SkipNewline(HirExpression::new(self.scope)))?;
+            let span = self
+                .scope
+                .span(expression)
+                .extended_until_end_of(parser.last_span());
+
+            let assignment = match expression {
+                ParsedExpression::Place(place) => self
+                    .scope
+                    .add(span, hir::ExpressionData::Assignment { place, value }),
+                ParsedExpression::Expression(_) => {
+                    let target_span = self.scope.span(expression);
+                    parser.report_error("cannot assign to this expression", target_span);
+                    self.scope
+                        .already_reported_error_expression(span, hir::ErrorData::Misc)
+                }
+            };
+            return Ok(ParsedStatement::Expression(assignment));
+        }
+
+        Ok(ParsedStatement::Expression(
+            expression.to_hir_expression(self.scope),
+        ))
     }
 }
 
@@ -1117,6 +1772,48 @@ impl Syntax<'parse> for LetStatement<'me, 'parse> {
 
     fn expect(&mut self, parser: &mut Parser<'parse>) -> Result<Self::Data, ErrorReported> {
         let let_keyword = parser.expect(Let)?;
+
+        // `let (a, b) = pair`
+        let pattern_start = parser.peek_span();
+        if let Some(names) = parser.parse_if_present(Delimited(
+            Parentheses,
+            CommaList(HirIdentifier::new(self.scope)),
+        )) {
+            let names = names?;
+            let pattern_span = pattern_start.extended_until_end_of(parser.last_span());
+            parser.expect(Equals)?;
+            let initializer = parser.expect(SkipNewline(HirExpression::new(self.scope)))?;
+
+            let span = let_keyword.span.extended_until_end_of(parser.peek_span());
+
+            // The tuple itself is stored in a variable that is never
+            // brought into scope, so only its elements can be named. It
+            // is a variable of its own even if the user has one called
+            // `_tuple` too: later stages tell variables apart by their
+            // index, never by their name.
+            let text = "_tuple".intern(&self.scope.db);
+            let name = self.scope.add(pattern_span, hir::IdentifierData { text });
+            let tuple = self.scope.add(pattern_span, hir::VariableData { name });
+
+            let elements: Vec<hir::Variable> = names
+                .iter()
+                .map(|&name| {
+                    let name_span = self.scope.span(name);
+                    let variable = self.scope.add(name_span, hir::VariableData { name });
+                    self.scope.introduce_variable(variable);
+                    variable
+                })
+                .collect();
+            let elements = hir::List::from_iterator(&mut self.scope.fn_body_tables, elements);
+
+            return Ok(ParsedStatement::LetTuple(
+                span,
+                tuple,
+                initializer,
+                elements,
+            ));
+        }
+
         let name = parser.expect(HirIdentifier::new(self.scope))?;
 
         let mut initializer = None;
@@ -0,0 +1,30 @@
+use crate::parser::Parser;
+use crate::syntax::delimited::Delimited;
+use crate::syntax::identifier::SpannedGlobalIdentifier;
+use crate::syntax::list::CommaList;
+use crate::syntax::sigil::{Angles, OpenAngle};
+use crate::syntax::{NonEmptySyntax, Syntax};
+use lark_debug_derive::DebugWith;
+use lark_error::ErrorReported;
+use lark_seq::Seq;
+use lark_span::{FileName, Spanned};
+use lark_string::GlobalIdentifier;
+
+/// The generic parameters of an item, like the `<A, B>` in `struct
+/// Pair<A, B>`. The data is the list of parameter names.
+#[derive(DebugWith)]
+pub struct GenericParameters;
+
+impl Syntax<'parse> for GenericParameters {
+    type Data = Seq<Spanned<GlobalIdentifier, FileName>>;
+
+    fn test(&mut self, parser: &Parser<'parse>) -> bool {
+        parser.test(OpenAngle)
+    }
+
+    fn expect(&mut self, parser: &mut Parser<'parse>) -> Result<Self::Data, ErrorReported> {
+        parser.expect(Delimited(Angles, CommaList(SpannedGlobalIdentifier)))
+    }
+}
+
+impl NonEmptySyntax<'parse> for GenericParameters {}
@@ -0,0 +1,34 @@
+use crate::parser::Parser;
+use crate::syntax::identifier::SpannedGlobalIdentifier;
+use crate::syntax::sigil::DoubleColon;
+use crate::syntax::{NonEmptySyntax, Syntax};
+use lark_debug_derive::DebugWith;
+use lark_error::ErrorReported;
+use lark_seq::Seq;
+use lark_span::{FileName, Spanned};
+use lark_string::GlobalIdentifier;
+
+/// A path to an item, like `Foo` or `geometry::Point`: one or more
+/// identifiers separated by `::`. The data is the list of those
+/// identifiers (never empty).
+#[derive(DebugWith)]
+pub struct ItemPath;
+
+impl Syntax<'parse> for ItemPath {
+    type Data = Seq<Spanned<GlobalIdentifier, FileName>>;
+
+    fn test(&mut self, parser: &Parser<'parse>) -> bool {
+        parser.test(SpannedGlobalIdentifier)
+    }
+
+    fn expect(&mut self, parser: &mut Parser<'parse>) -> Result<Self::Data, ErrorReported> {
+        let mut segments = vec![parser.expect(SpannedGlobalIdentifier)?];
+        while let Some(colons) = parser.parse_if_present(DoubleColon) {
+            colons?;
+            segments.push(parser.expect(SpannedGlobalIdentifier)?);
+        }
+        Ok(Seq::from(segments))
+    }
+}
+
+impl NonEmptySyntax<'parse> for ItemPath {}
@@ -48,12 +48,25 @@ sigil_type! {
     pub struct CloseParenthesis = (LexToken::Sigil, ")");
     pub struct OpenSquare = (LexToken::Sigil, "[");
     pub struct CloseSquare = (LexToken::Sigil, "]");
+    pub struct OpenAngle = (LexToken::Sigil, "<");
+    pub struct CloseAngle = (LexToken::Sigil, ">");
     pub struct Colon = (LexToken::Sigil, ":");
+    pub struct DoubleColon = (LexToken::Sigil, "::");
     pub struct Semicolon = (LexToken::Sigil, ";");
     pub struct Comma = (LexToken::Sigil, ",");
     pub struct RightArrow = (LexToken::Sigil, "->");
+    pub struct FatArrow = (LexToken::Sigil, "=>");
     pub struct Dot = (LexToken::Sigil, ".");
+    pub struct Def = (LexToken::Identifier, "def");
     pub struct Let = (LexToken::Identifier, "let");
+    pub struct If = (LexToken::Identifier, "if");
+    pub struct Else = (LexToken::Identifier, "else");
+    pub struct In = (LexToken::Identifier, "in");
+    pub struct Underscore = (LexToken::Identifier, "_");
+    pub struct Own = (LexToken::Identifier, "own");
+    pub struct Share = (LexToken::Identifier, "share");
+    pub struct Borrow = (LexToken::Identifier, "borrow");
+    pub struct SelfKeyword = (LexToken::Identifier, "self");
     pub struct ExclamationPoint = (LexToken::Sigil, "!");
     pub struct Plus = (LexToken::Sigil, "+");
     pub struct Minus = (LexToken::Sigil, "-");
@@ -93,3 +106,35 @@ impl Delimiter<'parse> for Parentheses {
         CloseParenthesis
     }
 }
+
+#[derive(DebugWith)]
+pub struct Squares;
+
+impl Delimiter<'parse> for Squares {
+    type Open = OpenSquare;
+    type Close = CloseSquare;
+
+    fn open_syntax(&self) -> Self::Open {
+        OpenSquare
+    }
+
+    fn close_syntax(&self) -> Self::Close {
+        CloseSquare
+    }
+}
+
+#[derive(DebugWith)]
+pub struct Angles;
+
+impl Delimiter<'parse> for Angles {
+    type Open = OpenAngle;
+    type Close = CloseAngle;
+
+    fn open_syntax(&self) -> Self::Open {
+        OpenAngle
+    }
+
+    fn close_syntax(&self) -> Self::Close {
+        CloseAngle
+    }
+}
@@ -1,15 +1,20 @@
 use crate::parser::Parser;
+use crate::syntax::delimited::Delimited;
 use crate::syntax::entity::LazyParsedEntityDatabase;
 use crate::syntax::identifier::SpannedGlobalIdentifier;
+use crate::syntax::list::CommaList;
+use crate::syntax::path::ItemPath;
+use crate::syntax::sigil::{Angles, Borrow, OpenParenthesis, Own, Parentheses, Share};
 use crate::syntax::Syntax;
 use lark_debug_derive::DebugWith;
-use lark_entity::Entity;
-use lark_error::{ErrorReported, ErrorSentinel, WithError};
-use lark_intern::Untern;
+use lark_entity::{Entity, EntityData, ItemKind, LangItem, MemberKind};
+use lark_error::{Diagnostic, ErrorReported, ErrorSentinel, WithError};
+use lark_intern::{Intern, Untern};
+use lark_seq::Seq;
 use lark_span::{FileName, Span, Spanned};
 use lark_string::GlobalIdentifier;
 use lark_ty as ty;
-use lark_ty::declaration::Declaration;
+use lark_ty::declaration::{Declaration, DeclaredPermKind};
 use lark_ty::TypeFamily;
 
 #[derive(DebugWith)]
@@ -19,24 +24,86 @@ impl Syntax<'parse> for TypeReference {
     type Data = ParsedTypeReference;
 
     fn test(&mut self, parser: &Parser<'parse>) -> bool {
-        parser.test(SpannedGlobalIdentifier)
+        parser.test(SpannedGlobalIdentifier) || parser.test(OpenParenthesis)
     }
 
     fn expect(
         &mut self,
         parser: &mut Parser<'parse>,
     ) -> Result<ParsedTypeReference, ErrorReported> {
-        let identifier = parser.expect(SpannedGlobalIdentifier)?;
+        let perm = match parser.parse_if_present(Permission) {
+            Some(perm) => {
+                let perm = perm?;
+                if let Some(second) = parser.parse_if_present(Permission) {
+                    let second = second?;
+                    let msg = format!(
+                        "a type can only have one permission, found `{}` and `{}`",
+                        perm.value, second.value,
+                    );
+                    parser.report_error(msg, second.span);
+                }
+                perm.value
+            }
+            None => DeclaredPermKind::Own,
+        };
+
+        // `(uint, bool)` is a tuple type, and `()` the unit type
+        if let Some(elements) =
+            parser.parse_if_present(Delimited(Parentheses, CommaList(TypeReference)))
+        {
+            return Ok(ParsedTypeReference::Tuple(TupleTypeReference {
+                perm,
+                elements: elements?,
+            }));
+        }
+
+        let path = parser.expect(ItemPath)?;
+        let generics = match parser.parse_if_present(Delimited(Angles, CommaList(TypeReference))) {
+            Some(generics) => generics?,
+            None => Seq::default(),
+        };
         Ok(ParsedTypeReference::Named(NamedTypeReference {
-            identifier,
+            perm,
+            path,
+            generics,
         }))
     }
 }
 
+/// A permission keyword, like the `share` in `share String`.
+#[derive(DebugWith)]
+pub struct Permission;
+
+impl Syntax<'parse> for Permission {
+    type Data = Spanned<DeclaredPermKind, FileName>;
+
+    fn test(&mut self, parser: &Parser<'parse>) -> bool {
+        parser.test(Own) || parser.test(Share) || parser.test(Borrow)
+    }
+
+    fn expect(&mut self, parser: &mut Parser<'parse>) -> Result<Self::Data, ErrorReported> {
+        let value = if parser.test(Own) {
+            DeclaredPermKind::Own
+        } else if parser.test(Share) {
+            DeclaredPermKind::Share
+        } else if parser.test(Borrow) {
+            DeclaredPermKind::Borrow
+        } else {
+            return Err(
+                parser.report_error("expected `own`, `share` or `borrow`", parser.peek_span())
+            );
+        };
+
+        let Spanned { span, .. } = parser.shift();
+        Ok(Spanned { value, span })
+    }
+}
+
 /// Parsed form of a type.
-#[derive(Copy, Clone, DebugWith)]
+#[derive(Clone, DebugWith)]
 pub enum ParsedTypeReference {
     Named(NamedTypeReference),
+    Tuple(TupleTypeReference),
     Elided(Span<FileName>),
     Error,
 }
@@ -49,6 +116,7 @@ impl ParsedTypeReference {
     ) -> WithError<ty::Ty<Declaration>> {
         match self {
             ParsedTypeReference::Named(named) => named.parse_type(entity, db),
+            ParsedTypeReference::Tuple(tuple) => tuple.parse_type(entity, db),
             ParsedTypeReference::Elided(_span) => {
                 WithError::ok(crate::type_conversion::unit_ty(db))
             }
@@ -63,10 +131,13 @@ impl<Cx> ErrorSentinel<Cx> for ParsedTypeReference {
     }
 }
 
-/// Named type like `String` or (eventually) `Vec<u32>`
-#[derive(Copy, Clone, DebugWith)]
+/// Named type like `String`, `geometry::Point` or `share Pair<uint, bool>`
+#[derive(Clone, DebugWith)]
 pub struct NamedTypeReference {
-    pub identifier: Spanned<GlobalIdentifier, FileName>,
+    /// The permission written before the type (`own` if none was written).
+    pub perm: DeclaredPermKind,
+    pub path: Seq<Spanned<GlobalIdentifier, FileName>>,
+    pub generics: Seq<ParsedTypeReference>,
 }
 
 impl NamedTypeReference {
@@ -75,27 +146,127 @@ impl NamedTypeReference {
         entity: Entity,
         db: &dyn LazyParsedEntityDatabase,
     ) -> WithError<ty::Ty<Declaration>> {
-        match db.resolve_name(entity, self.identifier.value) {
-            Some(entity) => {
+        // A plain name like `T` may refer to a generic parameter in scope.
+        if let ([name], true) = (&self.path[..], self.generics.is_empty()) {
+            if let Some(bound_var) =
+                crate::type_conversion::generic_parameter_named(db, entity, name.value)
+            {
+                let ty = crate::type_conversion::bound_var_ty(&db, bound_var);
+                return WithError::ok(ty::Ty {
+                    perm: self.perm.intern(&db),
+                    ..ty
+                });
+            }
+        }
+
+        let last_segment = &self.path[self.path.len() - 1];
+
+        match db.resolve_path(entity, &self.path) {
+            Ok(Some(item)) => {
+                if let EntityData::ItemName {
+                    kind: ItemKind::Module,
+                    ..
+                } = item.untern(&db)
+                {
+                    let msg = format!("`{}` is a module, not a type", last_segment.untern(&db));
+                    return WithError::report_error(&db, msg, last_segment.span);
+                }
+
+                if let EntityData::MemberName {
+                    kind: MemberKind::Variant,
+                    ..
+                } = item.untern(&db)
+                {
+                    let msg = format!("`{}` is a variant, not a type", last_segment.untern(&db));
+                    return WithError::report_error(&db, msg, last_segment.span);
+                }
+
+                let mut errors = vec![];
+                let generics: ty::Generics<Declaration> = self
+                    .generics
+                    .iter()
+                    .map(|generic| {
+                        ty::GenericKind::Ty(
+                            generic
+                                .parse_type(entity, db)
+                                .accumulate_errors_into(&mut errors),
+                        )
+                    })
+                    .collect();
+
+                let expected = crate::type_conversion::generic_count(db, item);
+                if generics.len() != expected {
+                    let msg = format!(
+                        "wrong number of type arguments: expected {}, found {}",
+                        expected,
+                        generics.len()
+                    );
+                    errors.push(Diagnostic::new(msg, last_segment.span));
+                    return WithError {
+                        value: Declaration::error_type(&db),
+                        errors,
+                    };
+                }
+
                 // FIXME(ndm) -- eventually, we will want some way to
-                // represent types with other permissions/reprs. We'll
-                // need fields on `NamedTypeReference`, and we'll need
-                // to have methods for helping us adjust them
-                // post-parse, or else distinct parsing combinators
-                // (the former might be more convenient).
+                // represent types with other reprs. We'll need fields
+                // on `NamedTypeReference`, as we have for permissions.
                 let ty = crate::type_conversion::declaration_ty_named(
                     &db,
-                    entity,
-                    ty::declaration::DeclaredPermKind::Own,
+                    item,
+                    self.perm,
                     ty::ReprKind::Direct,
-                    ty::Generics::empty(),
+                    generics,
                 );
-                WithError::ok(ty)
+                WithError { value: ty, errors }
             }
-            None => {
-                let msg = format!("unknown type: `{}`", self.identifier.untern(&db));
-                WithError::report_error(&db, msg, self.identifier.span)
+            Ok(None) => {
+                let msg = format!("unknown type: `{}`", self.path[0].untern(&db));
+                WithError::report_error(&db, msg, self.path[0].span)
             }
+            Err(diagnostic) => WithError::report_error(&db, diagnostic.label, diagnostic.span),
         }
     }
 }
+
+/// Tuple type like `(uint, bool)` or `share (String, uint)`
+#[derive(Clone, DebugWith)]
+pub struct TupleTypeReference {
+    /// The permission written before the type (`own` if none was written).
+    pub perm: DeclaredPermKind,
+    pub elements: Seq<ParsedTypeReference>,
+}
+
+impl TupleTypeReference {
+    pub fn parse_type(
+        &self,
+        entity: Entity,
+        db: &dyn LazyParsedEntityDatabase,
+    ) -> WithError<ty::Ty<Declaration>> {
+        let mut errors = vec![];
+
+        // The types of the elements are the generic arguments of the
+        // tuple.
+        let generics: ty::Generics<Declaration> = self
+            .elements
+            .iter()
+            .map(|element| {
+                ty::GenericKind::Ty(
+                    element
+                        .parse_type(entity, db)
+                        .accumulate_errors_into(&mut errors),
+                )
+            })
+            .collect();
+
+        let tuple_entity = EntityData::LangItem(LangItem::Tuple(self.elements.len())).intern(&db);
+        let ty = crate::type_conversion::declaration_ty_named(
+            &db,
+            tuple_entity,
+            self.perm,
+            ty::ReprKind::Direct,
+            generics,
+        );
+        WithError { value: ty, errors }
+    }
+}
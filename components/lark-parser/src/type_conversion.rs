@@ -2,8 +2,11 @@ use crate::syntax::entity::LazyParsedEntityDatabase;
 use crate::ParserDatabase;
 use lark_debug_with::DebugWith;
 use lark_entity::{Entity, EntityData, LangItem};
-use lark_error::{ErrorReported, ErrorSentinel, WithError};
+use lark_error::{Diagnostic, ErrorReported, ErrorSentinel, WithError};
+use lark_indices::IndexVec;
 use lark_intern::{Intern, Untern};
+use lark_span::{FileName, Spanned};
+use lark_string::GlobalIdentifier;
 use lark_ty as ty;
 use lark_ty::declaration::Declaration;
 use lark_ty::declaration::DeclarationTables;
@@ -23,15 +26,46 @@ crate fn generic_declarations(
         | EntityData::LangItem(LangItem::Uint)
         | EntityData::LangItem(LangItem::False)
         | EntityData::LangItem(LangItem::True)
-        | EntityData::LangItem(LangItem::Debug) => {
+        | EntityData::LangItem(LangItem::Debug)
+        | EntityData::LangItem(LangItem::StringLen)
+        | EntityData::LangItem(LangItem::StringSubstring)
+        | EntityData::LangItem(LangItem::Format) => {
             WithError::ok(Ok(ty::GenericDeclarations::empty(None)))
         }
 
+        // A tuple has one generic parameter for the type of each of its
+        // elements, named like the field that holds that element.
         EntityData::LangItem(LangItem::Tuple(arity)) => {
-            if arity != 0 {
-                unimplemented!("non-zero arity tuples");
-            }
-            WithError::ok(Ok(ty::GenericDeclarations::empty(None)))
+            let declarations = (0..arity)
+                .map(|index| {
+                    ty::GenericKind::Ty(ty::GenericTyDeclaration {
+                        def_id: entity,
+                        name: index.to_string().intern(db),
+                    })
+                })
+                .collect();
+            WithError::ok(Ok(Arc::new(ty::GenericDeclarations {
+                parent_item: None,
+                declarations,
+            })))
+        }
+
+        // `List<T>` has the type of its elements as its only generic
+        // parameter, which is also in scope in its methods.
+        EntityData::LangItem(LangItem::List) => {
+            let declarations = vec![ty::GenericKind::Ty(ty::GenericTyDeclaration {
+                def_id: entity,
+                name: "T".intern(db),
+            })];
+            WithError::ok(Ok(Arc::new(ty::GenericDeclarations {
+                parent_item: None,
+                declarations: declarations.into(),
+            })))
+        }
+
+        EntityData::LangItem(LangItem::ListLen) | EntityData::LangItem(LangItem::ListPush) => {
+            let list_entity = EntityData::LangItem(LangItem::List).intern(db);
+            WithError::ok(Ok(ty::GenericDeclarations::empty(Some(list_entity))))
         }
 
         EntityData::ItemName { .. } | EntityData::MemberName { .. } => db
@@ -54,7 +88,12 @@ crate fn ty(db: &impl ParserDatabase, entity: Entity) -> WithError<ty::Ty<Declar
         | EntityData::LangItem(LangItem::String)
         | EntityData::LangItem(LangItem::Int)
         | EntityData::LangItem(LangItem::Uint)
-        | EntityData::LangItem(LangItem::Debug) => WithError::ok(declaration_ty_named(
+        | EntityData::LangItem(LangItem::Debug)
+        | EntityData::LangItem(LangItem::ListLen)
+        | EntityData::LangItem(LangItem::ListPush)
+        | EntityData::LangItem(LangItem::StringLen)
+        | EntityData::LangItem(LangItem::StringSubstring)
+        | EntityData::LangItem(LangItem::Format) => WithError::ok(declaration_ty_named(
             db,
             entity,
             ty::declaration::DeclaredPermKind::Own,
@@ -67,24 +106,21 @@ crate fn ty(db: &impl ParserDatabase, entity: Entity) -> WithError<ty::Ty<Declar
             ty(db, boolean_entity)
         }
 
-        EntityData::LangItem(LangItem::Tuple(arity)) => {
-            let generics: ty::Generics<Declaration> = (0..arity)
-                .map(|i| ty::BoundVar::new(i))
-                .map(|bv| ty::Ty {
-                    base: Declaration::intern_bound_var(db, bv),
-                    repr: ty::ReprKind::Direct,
-                    perm: Declaration::own_perm(db),
-                })
-                .map(|ty| ty::GenericKind::Ty(ty))
-                .collect();
-            WithError::ok(declaration_ty_named(
-                db,
-                entity,
-                ty::declaration::DeclaredPermKind::Own,
-                ty::ReprKind::Direct,
-                generics,
-            ))
-        }
+        EntityData::LangItem(LangItem::Tuple(arity)) => WithError::ok(declaration_ty_named(
+            db,
+            entity,
+            ty::declaration::DeclaredPermKind::Own,
+            ty::ReprKind::Direct,
+            bound_var_generics(db, arity),
+        )),
+
+        EntityData::LangItem(LangItem::List) => WithError::ok(declaration_ty_named(
+            db,
+            entity,
+            ty::declaration::DeclaredPermKind::Own,
+            ty::ReprKind::Direct,
+            bound_var_generics(db, 1),
+        )),
 
         EntityData::ItemName { .. } | EntityData::MemberName { .. } => {
             db.parsed_entity(entity).thunk.parse_type(entity, db)
@@ -111,10 +147,52 @@ crate fn signature(
         | EntityData::LangItem(LangItem::False)
         | EntityData::LangItem(LangItem::Tuple(_))
         | EntityData::LangItem(LangItem::Debug)
+        | EntityData::LangItem(LangItem::List)
+        | EntityData::LangItem(LangItem::Format)
         | EntityData::LangItem(LangItem::True) => {
             panic!("cannot invoke `signature` of `{:?}`", entity.untern(db))
         }
 
+        // `def len(share self) -> uint`
+        EntityData::LangItem(LangItem::ListLen) => {
+            let uint_entity = EntityData::LangItem(LangItem::Uint).intern(db);
+            WithError::ok(Ok(ty::Signature {
+                inputs: vec![list_self_ty(db, ty::declaration::DeclaredPermKind::Share)].into(),
+                output: ty(db, uint_entity).into_value(),
+            }))
+        }
+
+        // `def push(borrow self, value: T)`
+        EntityData::LangItem(LangItem::ListPush) => WithError::ok(Ok(ty::Signature {
+            inputs: vec![
+                list_self_ty(db, ty::declaration::DeclaredPermKind::Borrow),
+                bound_var_ty(db, ty::BoundVar::new(0)),
+            ]
+            .into(),
+            output: unit_ty(db),
+        })),
+
+        // `def len(share self) -> uint`
+        EntityData::LangItem(LangItem::StringLen) => {
+            let uint_entity = EntityData::LangItem(LangItem::Uint).intern(db);
+            WithError::ok(Ok(ty::Signature {
+                inputs: vec![string_self_ty(db)].into(),
+                output: ty(db, uint_entity).into_value(),
+            }))
+        }
+
+        // `def substring(share self, start: uint, end: uint) -> String`,
+        // where `start` and `end` count characters
+        EntityData::LangItem(LangItem::StringSubstring) => {
+            let uint_entity = EntityData::LangItem(LangItem::Uint).intern(db);
+            let string_entity = EntityData::LangItem(LangItem::String).intern(db);
+            let uint_ty = ty(db, uint_entity).into_value();
+            WithError::ok(Ok(ty::Signature {
+                inputs: vec![string_self_ty(db), uint_ty, uint_ty].into(),
+                output: ty(db, string_entity).into_value(),
+            }))
+        }
+
         EntityData::ItemName { .. } | EntityData::MemberName { .. } => {
             db.parsed_entity(entity).thunk.parse_signature(entity, db)
         }
@@ -123,6 +201,32 @@ crate fn signature(
     }
 }
 
+/// The type of `self` in the methods of `List<T>`, accessed with
+/// permission `perm`.
+fn list_self_ty(
+    db: &impl ParserDatabase,
+    perm: ty::declaration::DeclaredPermKind,
+) -> ty::Ty<Declaration> {
+    declaration_ty_named(
+        db,
+        EntityData::LangItem(LangItem::List).intern(db),
+        perm,
+        ty::ReprKind::Direct,
+        bound_var_generics(db, 1),
+    )
+}
+
+/// The type of `self` in the methods of `String`, which only inspect it.
+fn string_self_ty(db: &impl ParserDatabase) -> ty::Ty<Declaration> {
+    declaration_ty_named(
+        db,
+        EntityData::LangItem(LangItem::String).intern(db),
+        ty::declaration::DeclaredPermKind::Share,
+        ty::ReprKind::Direct,
+        ty::Generics::empty(),
+    )
+}
+
 crate fn unit_ty(db: &dyn LazyParsedEntityDatabase) -> ty::Ty<Declaration> {
     declaration_ty_named(
         &db,
@@ -133,6 +237,115 @@ crate fn unit_ty(db: &dyn LazyParsedEntityDatabase) -> ty::Ty<Declaration> {
     )
 }
 
+/// Creates the generic declarations of an item `entity` that declares
+/// the generic parameters `parameters` (e.g., `A` and `B` in `struct
+/// Pair<A, B>`).
+crate fn generic_declarations_from_parameters(
+    db: &dyn LazyParsedEntityDatabase,
+    entity: Entity,
+    parameters: &[Spanned<GlobalIdentifier, FileName>],
+) -> WithError<Result<Arc<ty::GenericDeclarations>, ErrorReported>> {
+    let mut errors = vec![];
+    let mut declarations = IndexVec::default();
+
+    for (index, parameter) in parameters.iter().enumerate() {
+        if parameters[..index]
+            .iter()
+            .any(|p| p.value == parameter.value)
+        {
+            let msg = format!(
+                "generic parameter `{}` is declared twice",
+                parameter.untern(&db)
+            );
+            errors.push(Diagnostic::new(msg, parameter.span));
+            continue;
+        }
+
+        // Generic parameters do not have entities of their own, so we
+        // identify them by the item that declares them.
+        declarations.push(ty::GenericKind::Ty(ty::GenericTyDeclaration {
+            def_id: entity,
+            name: parameter.value,
+        }));
+    }
+
+    WithError {
+        value: Ok(Arc::new(ty::GenericDeclarations {
+            parent_item: None,
+            declarations,
+        })),
+        errors,
+    }
+}
+
+/// The total number of generic parameters in scope within `entity`,
+/// including those inherited from its parent item.
+crate fn generic_count(db: &dyn LazyParsedEntityDatabase, entity: Entity) -> usize {
+    match db.generic_declarations(entity).into_value() {
+        Ok(generic_declarations) => {
+            let parent_count = match generic_declarations.parent_item {
+                Some(parent_item) => generic_count(db, parent_item),
+                None => 0,
+            };
+            parent_count + generic_declarations.declarations.len()
+        }
+        Err(ErrorReported(_)) => 0,
+    }
+}
+
+/// If `name` refers to a generic parameter that is in scope within
+/// `scope` (declared either by `scope` itself or by one of its parent
+/// items), returns the bound variable that represents it.
+crate fn generic_parameter_named(
+    db: &dyn LazyParsedEntityDatabase,
+    scope: Entity,
+    name: GlobalIdentifier,
+) -> Option<ty::BoundVar> {
+    let generic_declarations = db.generic_declarations(scope).into_value().ok()?;
+
+    let own_index = generic_declarations
+        .declarations
+        .iter()
+        .position(|declaration| match declaration {
+            ty::GenericKind::Ty(declaration) => declaration.name == name,
+        });
+
+    match (own_index, generic_declarations.parent_item) {
+        // The generics of the parent come first.
+        (Some(index), parent_item) => {
+            let parent_count = parent_item.map_or(0, |parent_item| generic_count(db, parent_item));
+            Some(ty::BoundVar::new(parent_count + index))
+        }
+        (None, Some(parent_item)) => generic_parameter_named(db, parent_item, name),
+        (None, None) => None,
+    }
+}
+
+/// The type of a generic parameter, referenced from within the item
+/// that declares it.
+crate fn bound_var_ty(
+    db: &dyn AsRef<DeclarationTables>,
+    bound_var: ty::BoundVar,
+) -> ty::Ty<Declaration> {
+    ty::Ty {
+        base: Declaration::intern_bound_var(db, bound_var),
+        repr: ty::ReprKind::Direct,
+        perm: Declaration::own_perm(db),
+    }
+}
+
+/// Generics that map each of the first `count` generic parameters
+/// to itself; used for the declared type of a generic item like `struct
+/// Pair<A, B>`, which is `Pair<A, B>`.
+crate fn bound_var_generics(
+    db: &dyn AsRef<DeclarationTables>,
+    count: usize,
+) -> ty::Generics<Declaration> {
+    (0..count)
+        .map(|i| ty::GenericKind::Ty(bound_var_ty(db, ty::BoundVar::new(i))))
+        .collect()
+}
+
 crate fn declaration_ty_named(
     db: &dyn AsRef<DeclarationTables>,
     entity: Entity,
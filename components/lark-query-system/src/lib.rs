@@ -20,6 +20,7 @@ pub struct LarkDatabase {
     global_id_tables: Arc<GlobalIdentifierTables>,
     declaration_tables: Arc<lark_ty::declaration::DeclarationTables>,
     base_inferred_tables: Arc<lark_ty::base_inferred::BaseInferredTables>,
+    full_inferred_tables: Arc<lark_ty::full_inferred::FullInferredTables>,
 }
 
 impl std::fmt::Debug for LarkDatabase {
@@ -48,6 +49,7 @@ impl Default for LarkDatabase {
             global_id_tables: Default::default(),
             declaration_tables: Default::default(),
             base_inferred_tables: Default::default(),
+            full_inferred_tables: Default::default(),
         };
         db.init_parser_db();
         db
@@ -68,6 +70,7 @@ impl ParallelDatabase for LarkDatabase {
             global_id_tables: self.global_id_tables.clone(),
             declaration_tables: self.declaration_tables.clone(),
             base_inferred_tables: self.base_inferred_tables.clone(),
+            full_inferred_tables: self.full_inferred_tables.clone(),
         })
     }
 }
@@ -79,6 +82,7 @@ salsa::database_storage! {
         impl lark_parser::ParserDatabase {
             fn file_names() for lark_parser::FileNamesQuery;
             fn file_text() for lark_parser::FileTextQuery;
+            fn registered_entity_macros() for lark_parser::RegisteredEntityMacrosQuery;
             fn line_offsets() for lark_parser::LineOffsetsQuery;
             fn location() for lark_parser::LocationQuery;
             fn byte_index() for lark_parser::ByteIndexQuery;
@@ -96,12 +100,17 @@ salsa::database_storage! {
             fn signature() for lark_parser::SignatureQuery;
             fn generic_declarations() for lark_parser::GenericDeclarationsQuery;
             fn resolve_name() for lark_parser::ResolveNameQuery;
+            fn project_items() for lark_parser::ProjectItemsQuery;
+            fn use_target() for lark_parser::UseTargetQuery;
         }
         impl lark_type_check::TypeCheckDatabase {
             fn base_type_check() for lark_type_check::BaseTypeCheckQuery;
+            fn full_type_check() for lark_type_check::FullTypeCheckQuery;
         }
         impl mir::MirDatabase {
             fn fn_bytecode() for mir::FnBytecodeQuery;
+            fn ownership_check() for mir::OwnershipCheckQuery;
+            fn const_value() for mir::ConstValueQuery;
         }
     }
 }
@@ -130,6 +139,12 @@ impl AsRef<lark_ty::base_inferred::BaseInferredTables> for LarkDatabase {
     }
 }
 
+impl AsRef<lark_ty::full_inferred::FullInferredTables> for LarkDatabase {
+    fn as_ref(&self) -> &lark_ty::full_inferred::FullInferredTables {
+        &self.full_inferred_tables
+    }
+}
+
 impl l_r::ReportingFiles for &LarkDatabase {
     type Span = Span<FileName>;
     type FileId = FileName;
@@ -6,10 +6,13 @@
 
 use languageserver_types::{Position, Range};
 use lark_debug_with::DebugWith;
-use lark_entity::{Entity, EntityData, ItemKind, MemberKind};
+use lark_entity::{Entity, EntityData, ItemKind, LangItem, MemberKind};
 use lark_error::Diagnostic;
 use lark_intern::{Intern, Untern};
 use lark_span::{ByteIndex, FileName, IntoFileName, Span};
+use lark_string::GlobalIdentifier;
+use lark_ty::declaration::{Declaration, DeclaredPermKind};
+use lark_ty::{BaseData, BaseKind, BoundVarOr, GenericKind, Ty};
 use std::collections::HashMap;
 
 #[derive(Debug)]
@@ -28,7 +31,7 @@ pub struct Cancelled;
 
 pub type Cancelable<T> = Result<T, Cancelled>;
 
-pub trait LsDatabase: lark_type_check::TypeCheckDatabase {
+pub trait LsDatabase: lark_mir::MirDatabase {
     fn check_for_cancellation(&self) -> Cancelable<()> {
         if self.salsa_runtime().is_current_revision_canceled() {
             Err(Cancelled)
@@ -50,6 +53,14 @@ pub trait LsDatabase: lark_type_check::TypeCheckDatabase {
                 .parsed_file(input_file)
                 .accumulate_errors_into(&mut errors);
 
+            // Check for names that some other file already defines
+            errors.extend(
+                self.project_items()
+                    .errors
+                    .into_iter()
+                    .filter(|error| error.span.file() == input_file),
+            );
+
             // Next, check entities in file for type-safety
             let file_entity = EntityData::InputFile { file: input_file }.intern(self);
             for &entity in self.descendant_entities(file_entity).iter() {
@@ -87,6 +98,14 @@ pub trait LsDatabase: lark_type_check::TypeCheckDatabase {
             EntityData::ItemName {
                 kind: ItemKind::Struct,
                 ..
+            }
+            | EntityData::ItemName {
+                kind: ItemKind::Enum,
+                ..
+            }
+            | EntityData::MemberName {
+                kind: MemberKind::Variant,
+                ..
             } => {
                 let _ = self
                     .generic_declarations(entity)
@@ -111,8 +130,7 @@ pub trait LsDatabase: lark_type_check::TypeCheckDatabase {
                     .accumulate_errors_into(errors);
                 let _ = self.ty(entity).accumulate_errors_into(errors);
                 let _ = self.signature(entity).accumulate_errors_into(errors);
-                let _ = self.fn_body(entity).accumulate_errors_into(errors);
-                let _ = self.base_type_check(entity).accumulate_errors_into(errors);
+                self.accumulate_fn_body_errors(entity, errors);
             }
             EntityData::MemberName {
                 kind: MemberKind::Method,
@@ -123,14 +141,65 @@ pub trait LsDatabase: lark_type_check::TypeCheckDatabase {
                     .accumulate_errors_into(errors);
                 let _ = self.ty(entity).accumulate_errors_into(errors);
                 let _ = self.signature(entity).accumulate_errors_into(errors);
-                let _ = self.fn_body(entity).accumulate_errors_into(errors);
-                let _ = self.base_type_check(entity).accumulate_errors_into(errors);
+                self.accumulate_fn_body_errors(entity, errors);
+            }
+            EntityData::ItemName {
+                kind: ItemKind::Module,
+                ..
+            } => {}
+            EntityData::ItemName {
+                kind: ItemKind::Use,
+                ..
+            } => {
+                let _ = self.use_target(entity).accumulate_errors_into(errors);
+            }
+            EntityData::ItemName {
+                kind: ItemKind::Const,
+                ..
+            } => {
+                let _ = self.ty(entity).accumulate_errors_into(errors);
+                self.accumulate_const_errors(entity, errors);
             }
         }
 
         Ok(())
     }
 
+    /// Accumulates the errors from the body of the function or method
+    /// `entity`. We only check permissions, and then look at the MIR
+    /// and check it for uses of moved values, if everything before
+    /// was error-free; later passes tend to report the same problems
+    /// a second time.
+    fn accumulate_fn_body_errors(&self, entity: Entity, errors: &mut Vec<Diagnostic>) {
+        let errors_before = errors.len();
+        let _ = self.fn_body(entity).accumulate_errors_into(errors);
+        let _ = self.base_type_check(entity).accumulate_errors_into(errors);
+        if errors.len() == errors_before {
+            let _ = self.full_type_check(entity).accumulate_errors_into(errors);
+        }
+        if errors.len() == errors_before {
+            let _ = self.fn_bytecode(entity).accumulate_errors_into(errors);
+        }
+        if errors.len() == errors_before {
+            self.ownership_check(entity).accumulate_errors_into(errors);
+        }
+    }
+
+    /// Accumulates the errors from the initializer of the `const`
+    /// item `entity`. As for function bodies, we only evaluate the
+    /// initializer if everything before was error-free.
+    fn accumulate_const_errors(&self, entity: Entity, errors: &mut Vec<Diagnostic>) {
+        let errors_before = errors.len();
+        let _ = self.fn_body(entity).accumulate_errors_into(errors);
+        let _ = self.base_type_check(entity).accumulate_errors_into(errors);
+        if errors.len() == errors_before {
+            let _ = self.full_type_check(entity).accumulate_errors_into(errors);
+        }
+        if errors.len() == errors_before {
+            let _ = self.const_value(entity).accumulate_errors_into(errors);
+        }
+    }
+
     /// Returns the hover text to display for a given position (if
     /// any).
     fn hover_text_at_position(&self, url: &str, position: Position) -> Cancelable<Option<String>> {
@@ -145,13 +214,44 @@ pub trait LsDatabase: lark_type_check::TypeCheckDatabase {
                 ..
             } => Ok(Some(format!("struct {}", id.untern(self)))),
 
+            EntityData::ItemName {
+                kind: ItemKind::Enum,
+                id,
+                ..
+            } => Ok(Some(format!("enum {}", id.untern(self)))),
+
+            EntityData::MemberName {
+                kind: MemberKind::Variant,
+                base,
+                id,
+            } => match base.untern(self) {
+                EntityData::ItemName { id: enum_id, .. } => Ok(Some(format!(
+                    "{}::{}",
+                    enum_id.untern(self),
+                    id.untern(self)
+                ))),
+                _ => Ok(None),
+            },
+
             EntityData::MemberName {
                 kind: MemberKind::Field,
                 ..
             } => {
                 let field_ty = self.ty(entity).into_value();
-                // FIXME should not use "debug" but display to format the type
-                Ok(Some(format!("{}", field_ty.debug_with(self))))
+                Ok(Some(self.pretty_print_declared_ty(entity, field_ty)))
+            }
+
+            EntityData::ItemName {
+                kind: ItemKind::Const,
+                id,
+                ..
+            } => {
+                let const_ty = self.ty(entity).into_value();
+                Ok(Some(format!(
+                    "const {}: {}",
+                    id.untern(self),
+                    self.pretty_print_declared_ty(entity, const_ty)
+                )))
             }
 
             EntityData::ItemName {
@@ -166,12 +266,101 @@ pub trait LsDatabase: lark_type_check::TypeCheckDatabase {
                 Ok(None)
             }
 
+            EntityData::ItemName {
+                kind: ItemKind::Module,
+                ..
+            }
+            | EntityData::ItemName {
+                kind: ItemKind::Use,
+                ..
+            } => Ok(None),
+
             EntityData::InputFile { .. } | EntityData::LangItem(_) | EntityData::Error(_) => {
                 Ok(None)
             }
         }
     }
 
+    /// Formats a declared type the way it would be written in Lark
+    /// code, e.g. `share Pair<uint, A>`. Generic parameters are named
+    /// according to the generic declarations in scope within `scope`.
+    fn pretty_print_declared_ty(&self, scope: Entity, ty: Ty<Declaration>) -> String {
+        let base = match ty.base.untern(self) {
+            BoundVarOr::BoundVar(bound_var) => {
+                let names = self.generic_parameter_names(scope);
+                match names.get(bound_var.as_usize()) {
+                    Some(name) => name.untern(self).to_string(),
+                    None => format!("{:?}", bound_var.debug_with(self)),
+                }
+            }
+
+            BoundVarOr::Known(BaseData { kind, generics }) => {
+                let generics: Vec<String> = generics
+                    .iter()
+                    .map(|generic| match generic {
+                        GenericKind::Ty(ty) => self.pretty_print_declared_ty(scope, ty),
+                    })
+                    .collect();
+
+                let name = match kind {
+                    BaseKind::Named(entity) => match entity.untern(self) {
+                        EntityData::LangItem(LangItem::Tuple(arity)) => {
+                            // A tuple with one element needs a trailing
+                            // comma, as in `(uint,)`, to tell it apart
+                            // from a type in parentheses.
+                            let trailing_comma = if arity == 1 { "," } else { "" };
+                            return format!("({}{})", generics.join(", "), trailing_comma);
+                        }
+                        EntityData::LangItem(LangItem::Boolean) => "bool".to_string(),
+                        EntityData::LangItem(LangItem::Int) => "int".to_string(),
+                        EntityData::LangItem(LangItem::Uint) => "uint".to_string(),
+                        EntityData::LangItem(LangItem::String) => "String".to_string(),
+                        EntityData::LangItem(LangItem::List) => "List".to_string(),
+                        EntityData::ItemName { id, .. } | EntityData::MemberName { id, .. } => {
+                            id.untern(self).to_string()
+                        }
+                        _ => format!("{:?}", entity.debug_with(self)),
+                    },
+                    BaseKind::Placeholder(placeholder) => match placeholder {},
+                    BaseKind::Error => "{error}".to_string(),
+                };
+
+                if generics.is_empty() {
+                    name
+                } else {
+                    format!("{}<{}>", name, generics.join(", "))
+                }
+            }
+        };
+
+        // Types written without a permission are `own`, so we only
+        // mention the other permissions.
+        match ty.perm.untern(self) {
+            DeclaredPermKind::Own => base,
+            perm => format!("{} {}", perm, base),
+        }
+    }
+
+    /// The names of the generic parameters in scope within `entity`,
+    /// indexed by their bound variables.
+    fn generic_parameter_names(&self, entity: Entity) -> Vec<GlobalIdentifier> {
+        match self.generic_declarations(entity).into_value() {
+            Ok(generic_declarations) => {
+                let mut names = match generic_declarations.parent_item {
+                    Some(parent_item) => self.generic_parameter_names(parent_item),
+                    None => vec![],
+                };
+                names.extend(generic_declarations.declarations.iter().map(|declaration| {
+                    match declaration {
+                        GenericKind::Ty(declaration) => declaration.name,
+                    }
+                }));
+                names
+            }
+            Err(_) => vec![],
+        }
+    }
+
     fn position_to_byte_index(&self, url: &str, position: Position) -> ByteIndex {
         let url_id = url.intern(self);
         self.byte_index(FileName { id: url_id }, position.line, position.character)
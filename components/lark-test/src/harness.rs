@@ -74,6 +74,19 @@ pub fn search_files(root_path: impl AsRef<Path>) -> Vec<TestPath> {
     test_paths
 }
 
+/// Returns the `.lark` files within the test directory `dir`, sorted by path.
+fn test_directory_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "lark"))
+        .collect();
+    files.sort();
+    files
+}
+
 /// Runs the test harness against a given test file. The first few arguments
 /// are the fields from `TestPath`.
 pub fn run_test_harness(
@@ -85,19 +98,35 @@ pub fn run_test_harness(
     let relative_test_path: &Path = relative_test_path.as_ref();
     let test_path: &Path = test_path.as_ref();
 
-    if is_dir {
-        // this is meant to be a manifest tes
-        unimplemented!("test directories");
-    }
-
     eprintln!("Test file: `{}`", test_path.display());
 
     let test_name = relative_test_path.with_extension("").display().to_string();
 
-    let file_contents = fs::read_to_string(&test_path)
-        .unwrap_or_else(|err| panic!("error reading `{}`: {}", test_path.display(), err));
+    // A test directory (e.g., `foo.lark/`) is a multi-file test: each
+    // `.lark` file within is loaded (in order of their paths) under the
+    // name `foo/<path>`, and each may contain `//~` comments.
+    let files = if is_dir {
+        test_directory_files(test_path)
+            .into_iter()
+            .map(|path| {
+                let relative_path = path.strip_prefix(test_path).unwrap().with_extension("");
+                let file_name = format!("{}/{}", test_name, relative_path.display());
+                (path, file_name)
+            })
+            .collect()
+    } else {
+        vec![(test_path.to_owned(), test_name.clone())]
+    };
+
+    let mut options = TestOptions::default();
+    let mut db = LarkDatabase::default();
+    for (path, file_name) in &files {
+        let file_contents = fs::read_to_string(path)
+            .unwrap_or_else(|err| panic!("error reading `{}`: {}", path.display(), err));
 
-    let options = TestOptions::from_source_text(&test_path, &file_contents);
+        options.add_source_text(path, file_name, &file_contents);
+        db.add_file(file_name, &file_contents);
+    }
 
     eprintln!("Options: {:?}", options);
 
@@ -106,9 +135,6 @@ pub fn run_test_harness(
         return;
     }
 
-    let mut db = LarkDatabase::default();
-    db.add_file(&test_name, &file_contents);
-
     TestContext {
         bless_mode,
         test_name,
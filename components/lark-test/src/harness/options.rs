@@ -13,6 +13,11 @@ crate struct TestOptions {
     // Checked by code in `test::ls_test`.
     crate expected_hovers: Vec<ExpectedHover>,
 
+    // `//~ unsupported_by_c: <regex>` marks a test whose program the C
+    // backend reports as unsupported, with an error matching the regex,
+    // rather than building it.
+    crate unsupported_by_c: Option<Regex>,
+
     // Execution mode: do we run this code and -- if so -- how?
     //
     // Default: if there are errors, no. Otherwise, mode must be explicitly specified.
@@ -29,6 +34,8 @@ crate enum ExecutionMode {
 
 #[derive(Clone, Debug)]
 crate struct ExpectedError {
+    /// Name of the file (in the database) that the error is expected in.
+    crate file_name: String,
     crate line_num: u64,
     crate message: Regex,
 }
@@ -46,18 +53,26 @@ lazy_static::lazy_static! {
 }
 
 impl TestOptions {
-    /// Parse the `//~` options from the source text `text`.
+    /// Adds the `//~` options from the source text `text`, which is
+    /// loaded into the database as `file_name` (one file of a multi-file
+    /// test, or the only file), to these options.
     ///
     /// Panic if something is wrong.
-    crate fn from_source_text(path: &Path, text: &str) -> Self {
-        let mut result = TestOptions::default();
+    crate fn add_source_text(&mut self, path: &Path, file_name: &str, text: &str) {
+        let result = self;
         let mut last_non_comment_line = None;
 
         for (line, line_num) in text.lines().zip(0..) {
             let error = if let Some(cap) = WITH_OPTION.captures(line) {
-                result.apply_comment(&cap[1], &cap[2], cap[3].trim(), last_non_comment_line)
+                result.apply_comment(
+                    file_name,
+                    &cap[1],
+                    &cap[2],
+                    cap[3].trim(),
+                    last_non_comment_line,
+                )
             } else if let Some(cap) = NO_OPTION.captures(line) {
-                result.apply_comment(&cap[1], &cap[2], "", last_non_comment_line)
+                result.apply_comment(file_name, &cap[1], &cap[2], "", last_non_comment_line)
             } else if line.contains("//~") {
                 Err("`//~` comments must appear alone".to_string())
             } else {
@@ -77,8 +92,6 @@ impl TestOptions {
                 }
             }
         }
-
-        result
     }
 
     // Applies a comment `// key: value` found in a lark test file.
@@ -86,6 +99,7 @@ impl TestOptions {
     // Returns false if the comment was not recognized.
     crate fn apply_comment(
         &mut self,
+        file_name: &str,
         prefix: &str,
         key: &str,
         value: &str,
@@ -112,6 +126,14 @@ impl TestOptions {
                 Ok(())
             }
 
+            "unsupported_by_c" => match Regex::new(value.trim()) {
+                Ok(message) => {
+                    self.unsupported_by_c = Some(message);
+                    Ok(())
+                }
+                Err(error) => Err(format!("illegal regular expression `{}`", error)),
+            },
+
             // `//~ HOVER` puts a hover at the same column as starting `/`
             "HOVER" => match last_non_comment_line {
                 None => Err("cannot find line that hover applies to".to_string()),
@@ -133,8 +155,11 @@ impl TestOptions {
                 None => Err("cannot find line that error applies to".to_string()),
                 Some(line_num) => match Regex::new(value.trim()) {
                     Ok(message) => {
-                        self.expected_errors
-                            .push(ExpectedError { line_num, message });
+                        self.expected_errors.push(ExpectedError {
+                            file_name: file_name.to_string(),
+                            line_num,
+                            message,
+                        });
                         Ok(())
                     }
                     Err(error) => Err(format!("illegal regular expression `{}`", error)),
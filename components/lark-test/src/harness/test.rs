@@ -50,13 +50,13 @@ impl TestContext<'_> {
             }
             Some(ExecutionMode::No) => {}
             Some(ExecutionMode::Build) => {
-                self.build_and_run_executable();
+                self.build_and_run_executables();
             }
             Some(ExecutionMode::Eval) => {
                 self.run_eval();
             }
             Some(ExecutionMode::All) => {
-                self.build_and_run_executable();
+                self.build_and_run_executables();
                 self.run_eval();
             }
         }
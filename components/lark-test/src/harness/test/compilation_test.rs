@@ -1,5 +1,5 @@
 use crate::harness::test::TestContext;
-use lark_cli::build::LarkDatabaseExt;
+use lark_cli::build::{LarkDatabaseExt, MessageFormat};
 use lark_query_system::ls_ops::Cancelled;
 use lark_query_system::ls_ops::RangedDiagnostic;
 use std::collections::HashMap;
@@ -13,14 +13,11 @@ impl TestContext<'_> {
         let mut expected_errors: Vec<_> = self.options.expected_errors.iter().collect();
         let mut unexpected_errors = vec![];
         for (file_name, errors) in errors {
-            if file_name != self.test_name {
-                unexpected_errors.extend(errors);
-                continue;
-            }
-
             for error in errors {
                 let matching_expected_error = expected_errors.iter().position(|ee| {
-                    ee.line_num == error.range.start.line && ee.message.is_match(&error.label)
+                    ee.file_name == file_name
+                        && ee.line_num == error.range.start.line
+                        && ee.message.is_match(&error.label)
                 });
 
                 if let Some(i) = matching_expected_error {
@@ -64,7 +61,7 @@ impl TestContext<'_> {
     crate fn compare_stderr_against_expected(&self) {
         let mut buffer = Vec::new();
         self.db
-            .display_errors(NoColor::new(&mut buffer))
+            .display_errors(NoColor::new(&mut buffer), MessageFormat::Human)
             .unwrap_or_else(|Cancelled| panic!("cancelled?"));
 
         self.compare_reference_contents("stderr", &buffer, true);
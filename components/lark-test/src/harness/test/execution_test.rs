@@ -1,28 +1,82 @@
 use crate::harness::test::TestContext;
-use lark_cli::build::LarkDatabaseExt;
+use lark_build::CodegenType;
+use lark_cli::build::{LarkDatabaseExt, MessageFormat};
 use lark_query_system::ls_ops::Cancelled;
 use std::process::Command;
+use termcolor::NoColor;
 
 impl TestContext<'_> {
-    crate fn build_and_run_executable(&self) {
+    /// Builds the test with every compiled backend, checking that each
+    /// executable produces the expected output (or, for programs that
+    /// the C backend does not support, that it says so).
+    crate fn build_and_run_executables(&self) {
+        self.build_and_run_executable(CodegenType::Rust);
+
+        match &self.options.unsupported_by_c {
+            Some(message) => {
+                let (error_count, errors) = self.build_executable(CodegenType::C);
+                assert!(error_count > 0, "C backend accepted an unsupported program");
+                assert!(
+                    message.is_match(&errors),
+                    "C backend errors do not match `{}`:\n{}",
+                    message,
+                    errors
+                );
+            }
+            None => self.build_and_run_executable(CodegenType::C),
+        }
+    }
+
+    /// Builds the executable for the test, returning the number of
+    /// errors along with their text.
+    fn build_executable(&self, codegen_type: CodegenType) -> (usize, String) {
         let exe_path = self.executable_path();
-        self.db
-            .build(exe_path.to_str().unwrap())
+        let mut buffer = Vec::new();
+        let error_count = self
+            .db
+            .build(
+                exe_path.to_str().unwrap(),
+                codegen_type,
+                "main",
+                NoColor::new(&mut buffer),
+                MessageFormat::Human,
+            )
             .unwrap_or_else(|Cancelled| panic!("cancelled"));
 
+        (error_count, String::from_utf8_lossy(&buffer).to_string())
+    }
+
+    crate fn build_and_run_executable(&self, codegen_type: CodegenType) {
+        let exe_path = self.executable_path();
+        let (error_count, errors) = self.build_executable(codegen_type);
+        if error_count > 0 {
+            panic!("build failed:\n{}", errors);
+        }
+
         let cmd = Command::new(exe_path)
             .output()
             .expect("Failed to run compile test");
-        let test_output = String::from_utf8(cmd.stdout).unwrap();
+        let mut test_output = String::from_utf8(cmd.stdout).unwrap();
+
+        // Runtime errors are reported on stderr, and become part of the
+        // expected output (just like they do for the evaluator)
+        if !cmd.status.success() {
+            test_output.push_str(&String::from_utf8(cmd.stderr).unwrap());
+        }
 
         self.compare_reference_contents("output", test_output.as_bytes(), false);
     }
 
     crate fn run_eval(&self) {
         let mut handler = lark_eval::IOHandler::new(true);
-        lark_eval::eval(&self.db, &mut handler);
+        let result = lark_eval::eval(&self.db, &mut handler);
         let lark_eval::IOHandler { redirect: output } = handler;
-        let output = output.unwrap();
+        let mut output = output.unwrap();
+
+        // Runtime errors become part of the expected output
+        if let Err(error) = result {
+            output.push_str(&format!("error: {}\n", error.label));
+        }
         self.compare_reference_contents("output", output.as_bytes(), false);
     }
 }
@@ -16,6 +16,10 @@ impl TestContext<'_> {
             return Ok(());
         }
 
+        if self.test_path.is_dir() {
+            panic!("`//~ HOVER` is not supported in multi-file tests");
+        }
+
         let mut child_session = ChildSession::spawn();
 
         // Child that we are initialized
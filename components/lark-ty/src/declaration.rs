@@ -75,10 +75,23 @@ where
     }
 }
 
-/// For now, we only support `own T` in declarations.
+/// The permission written in a declared type, like the `share` in
+/// `share String`. A type written without a permission is `own`.
 #[derive(Copy, Clone, Debug, DebugWith, PartialEq, Eq, Hash)]
 pub enum DeclaredPermKind {
     Own,
+    Share,
+    Borrow,
+}
+
+impl fmt::Display for DeclaredPermKind {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeclaredPermKind::Own => write!(fmt, "own"),
+            DeclaredPermKind::Share => write!(fmt, "share"),
+            DeclaredPermKind::Borrow => write!(fmt, "borrow"),
+        }
+    }
 }
 
 lark_intern::intern_tables! {
@@ -8,9 +8,13 @@
 use lark_debug_derive::DebugWith;
 use lark_debug_with::DebugWith;
 use lark_entity::Entity;
+use lark_entity::EntityData;
+use lark_entity::EntityTables;
+use lark_entity::LangItem;
 use lark_error::ErrorReported;
 use lark_error::ErrorSentinel;
 use lark_indices::IndexVec;
+use lark_intern::Untern;
 use lark_seq::Seq;
 use lark_string::GlobalIdentifier;
 use lark_unify::InferVar;
@@ -21,6 +25,7 @@ use std::sync::Arc;
 
 pub mod base_inferred;
 pub mod declaration;
+pub mod full_inferred;
 pub mod identity;
 pub mod map_family;
 
@@ -117,6 +122,26 @@ pub enum BaseKind<F: TypeFamily> {
     Error,
 }
 
+impl<F: TypeFamily> BaseKind<F> {
+    /// True if owned values of this kind are copied, rather than
+    /// moved, when they are used. Errors count as copy so that they
+    /// don't cause further errors about moves.
+    pub fn is_copy(self, db: &dyn AsRef<EntityTables>) -> bool {
+        match self {
+            BaseKind::Named(entity) => match entity.untern(db) {
+                EntityData::LangItem(LangItem::Boolean)
+                | EntityData::LangItem(LangItem::Int)
+                | EntityData::LangItem(LangItem::Uint)
+                | EntityData::LangItem(LangItem::Tuple(0))
+                | EntityData::Error(_) => true,
+                _ => false,
+            },
+            BaseKind::Placeholder(_) => false,
+            BaseKind::Error => true,
+        }
+    }
+}
+
 /// Used as the value for inferable things during inference -- either
 /// a given `Base` (etc) maps to an inference variable or to some
 /// known value.
@@ -358,6 +383,16 @@ pub enum PermKind {
     Borrow,
 }
 
+impl fmt::Display for PermKind {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PermKind::Own => write!(fmt, "own"),
+            PermKind::Share => write!(fmt, "share"),
+            PermKind::Borrow => write!(fmt, "borrow"),
+        }
+    }
+}
+
 /// Encodes whether we reach the data through pointer indirection or not.
 #[derive(Copy, Clone, Debug, DebugWith, PartialEq, Eq, Hash)]
 pub enum ReprKind {
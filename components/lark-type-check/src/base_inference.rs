@@ -177,14 +177,14 @@ where
 
     fn substitute<M>(
         &mut self,
-        _location: impl Into<hir::MetaIndex>,
+        location: impl Into<hir::MetaIndex>,
         generics: &Generics<BaseInference>,
         value: M,
     ) -> M::Output
     where
         M: Map<Declaration, BaseInference>,
     {
-        value.map(&mut Substitution::new(self, generics))
+        value.map(&mut Substitution::new(self, location.into(), generics))
     }
 
     fn apply_owner_perm<M>(
@@ -266,6 +266,7 @@ where
 
     fn apply_repr_perm(
         &mut self,
+        _location: hir::MetaIndex,
         _repr: ReprKind,
         _perm: declaration::Perm,
         ty: Ty<BaseInference>,
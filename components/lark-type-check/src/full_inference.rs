@@ -25,6 +25,11 @@ use perm::PermData;
 
 /// Implements the `TypeCheckerFamilyDependentExt` methods along with substitution.
 mod type_checker;
+crate use type_checker::FullInferenceStorage;
+
+/// Maps the results of full inference into the `FullInferred` family.
+mod resolve_to_full_inferred;
+crate use resolve_to_full_inferred::ResolveToFullInferred;
 
 /// Type family for "base inference" -- inferring just the base types.
 #[derive(Copy, Clone, Debug, DebugWith, PartialEq, Eq, Hash)]
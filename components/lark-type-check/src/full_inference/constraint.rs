@@ -6,6 +6,11 @@ use lark_hir as hir;
 crate enum Constraint {
     /// Perm `a` must be **equivalent** to permission `b`.
     PermEquate { a: Perm, b: Perm },
+
+    /// Perm `a` must be **less than or equal to** permission `b`
+    /// (`permLess` in `notes/permissions.prolog`); e.g., a value
+    /// with permission `b` can be given where `a` is expected.
+    PermLess { a: Perm, b: Perm },
 }
 
 #[derive(Copy, Clone, Hash, Debug, DebugWith, PartialEq, Eq)]
@@ -0,0 +1,72 @@
+use crate::full_inference::perm::PermData;
+use crate::full_inference::perm::PermVar;
+use crate::full_inference::FullInference;
+use crate::full_inference::FullInferenceTables;
+use derive_new::new;
+use lark_hir as hir;
+use lark_indices::IndexVec;
+use lark_intern::Intern;
+use lark_intern::Untern;
+use lark_ty::full_inferred::{FullInferred, FullInferredTables};
+use lark_ty::map_family::FamilyMapper;
+use lark_ty::map_family::Map;
+use lark_ty::BaseData;
+use lark_ty::Erased;
+use lark_ty::PermKind;
+use lark_ty::Placeholder;
+use lark_ty::Ty;
+use lark_ty::TypeFamily;
+use lark_unify::UnificationTable;
+
+/// Maps the results of full inference to the `FullInferred` family,
+/// using the solution computed for the permission variables.
+///
+/// Unlike `ResolveToBaseInferred`, we do not report unresolved base
+/// type variables: the `base_type_check` query already reports those
+/// (and the two inferences agree on the base types), so they are
+/// just mapped to the error type here.
+#[derive(new)]
+crate struct ResolveToFullInferred<'me> {
+    unify: &'me mut UnificationTable<FullInferenceTables, hir::MetaIndex>,
+    tables: &'me FullInferenceTables,
+    perm_solution: &'me IndexVec<PermVar, PermKind>,
+    output_tables: &'me FullInferredTables,
+}
+
+impl FamilyMapper<FullInference, FullInferred> for ResolveToFullInferred<'me> {
+    fn map_ty(&mut self, ty: Ty<FullInference>) -> Ty<FullInferred> {
+        let Ty {
+            repr: Erased,
+            perm,
+            base,
+        } = ty;
+
+        let perm = match perm.untern(self.tables) {
+            PermData::Known(kind) => kind,
+            PermData::Inferred(var) => self.perm_solution[var],
+
+            // FIXME -- the `FullInferred` family cannot yet represent
+            // a permission that is a generic parameter.
+            PermData::Placeholder(_) => PermKind::Own,
+        };
+
+        match self.unify.shallow_resolve_data(base) {
+            Ok(BaseData { kind, generics }) => {
+                let kind = kind.map(self);
+                let generics = generics.map(self);
+                let base = BaseData { kind, generics }.intern(self.output_tables);
+                Ty {
+                    repr: Erased,
+                    perm,
+                    base,
+                }
+            }
+
+            Err(_) => FullInferred::error_type(self.output_tables),
+        }
+    }
+
+    fn map_placeholder(&mut self, placeholder: Placeholder) -> Placeholder {
+        placeholder
+    }
+}
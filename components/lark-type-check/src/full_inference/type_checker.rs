@@ -3,6 +3,7 @@ use crate::full_inference::constraint::ConstraintAt;
 use crate::full_inference::perm::Perm;
 use crate::full_inference::perm::PermData;
 use crate::full_inference::perm::PermVar;
+use crate::full_inference::Base;
 use crate::full_inference::FullInference;
 use crate::full_inference::FullInferenceTables;
 use crate::substitute::Substitution;
@@ -20,13 +21,14 @@ use lark_intern::Untern;
 use lark_ty::declaration;
 use lark_ty::declaration::Declaration;
 use lark_ty::declaration::DeclaredPermKind;
-use lark_ty::identity::Identity;
-use lark_ty::map_family::Map;
+use lark_ty::map_family::{FamilyMapper, Map};
+use lark_ty::BaseData;
 use lark_ty::BaseKind;
 use lark_ty::Erased;
 use lark_ty::GenericKind;
 use lark_ty::Generics;
 use lark_ty::PermKind;
+use lark_ty::Placeholder;
 use lark_ty::ReprKind;
 use lark_ty::Ty;
 
@@ -41,10 +43,18 @@ crate struct FullInferenceStorage {
     constraints: FxIndexSet<ConstraintAt>,
 
     /// Results we have generated thus far.
-    results: TypeCheckResults<FullInference>,
+    crate results: TypeCheckResults<FullInference>,
 }
 
 impl FullInferenceStorage {
+    crate fn new() -> Self {
+        FullInferenceStorage {
+            perm_vars: IndexVec::default(),
+            constraints: FxIndexSet::default(),
+            results: TypeCheckResults::default(),
+        }
+    }
+
     fn new_inferred_perm(&mut self, tables: &dyn AsRef<FullInferenceTables>) -> Perm {
         PermData::Inferred(self.perm_vars.push(())).intern(tables)
     }
@@ -92,65 +102,42 @@ where
         self.storage
             .add_constraint(cause, Constraint::PermEquate { a: perm1, b: perm2 });
 
-        match self.unify.unify(cause, base1, base2) {
-            Ok(()) => {}
-
-            Err((data1, data2)) => {
-                match (data1.kind, data2.kind) {
-                    (BaseKind::Error, _) => {
-                        self.propagate_error(cause, &data2.generics);
-                        return;
-                    }
-                    (_, BaseKind::Error) => {
-                        self.propagate_error(cause, &data1.generics);
-                        return;
-                    }
-                    _ => {}
-                }
-
-                if data1.kind != data2.kind {
-                    self.record_error("Mismatched types", cause);
-                    return;
-                }
-
-                for (generic1, generic2) in data1.generics.iter().zip(&data2.generics) {
-                    match (generic1, generic2) {
-                        (GenericKind::Ty(g1), GenericKind::Ty(g2)) => {
-                            self.equate_types(cause, g1, g2);
-                        }
-                    }
-                }
-            }
-        }
+        self.equate_bases(cause, base1, base2);
     }
 
     fn require_assignable(&mut self, expression: hir::Expression, place_ty: Ty<FullInference>) {
         let value_ty = self.storage.results.ty(expression);
-        self.equate_types(expression, value_ty, place_ty)
+        self.equate_bases(expression.into(), value_ty.base, place_ty.base);
+        self.require_perm_assignable(expression.into(), value_ty, place_ty);
     }
 
     fn substitute<M>(
         &mut self,
-        _location: impl Into<hir::MetaIndex>,
+        location: impl Into<hir::MetaIndex>,
         generics: &Generics<FullInference>,
         value: M,
     ) -> M::Output
     where
         M: Map<Declaration, FullInference>,
     {
-        value.map(&mut Substitution::new(self, generics))
+        value.map(&mut Substitution::new(self, location.into(), generics))
     }
 
     fn apply_owner_perm<M>(
         &mut self,
-        _location: impl Into<hir::MetaIndex>,
-        _owner_perm: Perm,
+        location: impl Into<hir::MetaIndex>,
+        owner_perm: Perm,
         value: M,
     ) -> M::Output
     where
         M: Map<FullInference, FullInference>,
     {
-        value.map(&mut Identity::new(self))
+        value.map(&mut ApplyOwnerPerm {
+            storage: &mut self.storage,
+            tables: &self.f_tables,
+            cause: location.into(),
+            owner_perm,
+        })
     }
 
     fn record_variable_ty(&mut self, var: hir::Variable, ty: Ty<FullInference>) {
@@ -196,6 +183,230 @@ where
     }
 }
 
+impl<DB> TypeChecker<'me, DB, FullInference, FullInferenceStorage>
+where
+    DB: TypeCheckDatabase,
+{
+    /// Unifies two base types; any generic arguments must have equal
+    /// permissions.
+    fn equate_bases(&mut self, cause: hir::MetaIndex, base1: Base, base2: Base) {
+        match self.unify.unify(cause, base1, base2) {
+            Ok(()) => {}
+
+            Err((data1, data2)) => {
+                match (data1.kind, data2.kind) {
+                    (BaseKind::Error, _) => {
+                        self.propagate_error(cause, &data2.generics);
+                        return;
+                    }
+                    (_, BaseKind::Error) => {
+                        self.propagate_error(cause, &data1.generics);
+                        return;
+                    }
+                    _ => {}
+                }
+
+                if data1.kind != data2.kind {
+                    self.record_error("Mismatched types", cause);
+                    return;
+                }
+
+                for (generic1, generic2) in data1.generics.iter().zip(&data2.generics) {
+                    match (generic1, generic2) {
+                        (GenericKind::Ty(g1), GenericKind::Ty(g2)) => {
+                            self.equate_types(cause, g1, g2);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Requires that a value of type `value_ty` can be stored into a
+    /// place of type `place_ty`, as far as their permissions go.
+    /// Values of primitive types like `uint` can always be copied, so
+    /// that requirement only applies to other types -- which means we
+    /// may have to wait until the base type is known.
+    fn require_perm_assignable(
+        &mut self,
+        cause: hir::MetaIndex,
+        value_ty: Ty<FullInference>,
+        place_ty: Ty<FullInference>,
+    ) {
+        match self.unify.shallow_resolve_data(value_ty.base) {
+            Ok(data) => {
+                if !self.is_copy(&data) {
+                    self.storage.add_constraint(
+                        cause,
+                        Constraint::PermLess {
+                            a: place_ty.perm,
+                            b: value_ty.perm,
+                        },
+                    );
+                }
+            }
+
+            Err(_) => self.enqueue_op(Some(value_ty.base), move |this| {
+                this.require_perm_assignable(cause, value_ty, place_ty)
+            }),
+        }
+    }
+
+    /// True for the types whose values can be freely copied, whatever
+    /// the permission they are accessed with. We also include the
+    /// error type, since errors were already reported for it.
+    fn is_copy(&self, data: &BaseData<FullInference>) -> bool {
+        data.kind.is_copy(self)
+    }
+
+    /// Solves the permission constraints gathered during the
+    /// type-check, reporting an error for each one that cannot be
+    /// satisfied. Each inferred permission gets the least permission
+    /// that satisfies all the constraints which require it to be at
+    /// least as big as something else (in the `share <= borrow <=
+    /// own` order of `permLess`), which is then checked against the
+    /// remaining constraints.
+    crate fn solve_perm_constraints(&mut self) -> IndexVec<PermVar, PermKind> {
+        let mut solution: IndexVec<PermVar, PermKind> =
+            IndexVec::from_elem(PermKind::Share, &self.storage.perm_vars);
+
+        let constraints: Vec<ConstraintAt> = self.storage.constraints.iter().cloned().collect();
+
+        // Raise the inferred permissions until we reach a fixed point.
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for constraint_at in &constraints {
+                let (lesser, greater) = match constraint_at.constraint {
+                    Constraint::PermLess { a, b } => (a, b),
+                    Constraint::PermEquate { a, b } => {
+                        changed |= self.raise_perm(&mut solution, b, a);
+                        (a, b)
+                    }
+                };
+                changed |= self.raise_perm(&mut solution, lesser, greater);
+            }
+        }
+
+        // Check that all the constraints hold.
+        for constraint_at in &constraints {
+            let ConstraintAt { cause, constraint } = *constraint_at;
+            match constraint {
+                Constraint::PermLess { a, b } => {
+                    if let (Some(a), Some(b)) =
+                        (self.perm_value(&solution, a), self.perm_value(&solution, b))
+                    {
+                        if !perm_less(a, b) {
+                            let message = match a {
+                                PermKind::Own => format!("cannot move out of a `{}` value", b),
+                                _ => format!("expected a `{}` value, found a `{}` value", a, b),
+                            };
+                            self.record_error(message, cause);
+                        }
+                    }
+                }
+
+                Constraint::PermEquate { a, b } => {
+                    if let (Some(a), Some(b)) =
+                        (self.perm_value(&solution, a), self.perm_value(&solution, b))
+                    {
+                        if a != b {
+                            self.record_error(
+                                format!("mismatched permissions: `{}` and `{}`", a, b),
+                                cause,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        solution
+    }
+
+    /// Raises the permission `greater` (if it is inferred) so that
+    /// `lesser <= greater` holds. Returns true if anything changed.
+    fn raise_perm(
+        &self,
+        solution: &mut IndexVec<PermVar, PermKind>,
+        lesser: Perm,
+        greater: Perm,
+    ) -> bool {
+        let lesser = match self.perm_value(solution, lesser) {
+            Some(lesser) => lesser,
+            None => return false,
+        };
+
+        match greater.untern(self) {
+            PermData::Inferred(var) if !perm_less(lesser, solution[var]) => {
+                solution[var] = lesser;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The value of `perm` under the given solution. Returns `None`
+    /// for placeholders, which are not yet related to anything.
+    fn perm_value(&self, solution: &IndexVec<PermVar, PermKind>, perm: Perm) -> Option<PermKind> {
+        match perm.untern(self) {
+            PermData::Known(kind) => Some(kind),
+            PermData::Inferred(var) => Some(solution[var]),
+            PermData::Placeholder(_) => None,
+        }
+    }
+}
+
+/// The `permLess` relation from `notes/permissions.prolog`, which (as
+/// we do not yet track regions) orders the permissions as `share <=
+/// borrow <= own`.
+crate fn perm_less(a: PermKind, b: PermKind) -> bool {
+    fn rank(perm: PermKind) -> u8 {
+        match perm {
+            PermKind::Share => 0,
+            PermKind::Borrow => 1,
+            PermKind::Own => 2,
+        }
+    }
+
+    rank(a) <= rank(b)
+}
+
+/// Adjusts types accessed through an owner with the permission
+/// `owner_perm` (e.g., the type of a field): the result can have at
+/// most the permission of the owner and at most its declared one.
+struct ApplyOwnerPerm<'me> {
+    storage: &'me mut FullInferenceStorage,
+    tables: &'me FullInferenceTables,
+    cause: hir::MetaIndex,
+    owner_perm: Perm,
+}
+
+impl FamilyMapper<FullInference, FullInference> for ApplyOwnerPerm<'_> {
+    fn map_ty(&mut self, ty: Ty<FullInference>) -> Ty<FullInference> {
+        let perm = self.storage.new_inferred_perm(self.tables);
+        self.storage.add_constraint(
+            self.cause,
+            Constraint::PermLess {
+                a: perm,
+                b: self.owner_perm,
+            },
+        );
+        self.storage.add_constraint(
+            self.cause,
+            Constraint::PermLess {
+                a: perm,
+                b: ty.perm,
+            },
+        );
+        Ty { perm, ..ty }
+    }
+
+    fn map_placeholder(&mut self, placeholder: Placeholder) -> Placeholder {
+        placeholder
+    }
+}
+
 impl<DB, S> AsRef<FullInferenceTables> for TypeChecker<'_, DB, FullInference, S>
 where
     DB: TypeCheckDatabase,
@@ -217,6 +428,8 @@ where
     fn map_repr_perm(&mut self, _repr: ReprKind, perm: declaration::Perm) -> (Erased, Perm) {
         let perm = match perm.untern(self) {
             DeclaredPermKind::Own => PermData::Known(PermKind::Own).intern(self),
+            DeclaredPermKind::Share => PermData::Known(PermKind::Share).intern(self),
+            DeclaredPermKind::Borrow => PermData::Known(PermKind::Borrow).intern(self),
         };
 
         (Erased, perm)
@@ -224,7 +437,8 @@ where
 
     fn apply_repr_perm(
         &mut self,
-        _repr: ReprKind,
+        location: hir::MetaIndex,
+        repr: ReprKind,
         perm: declaration::Perm,
         ty: Ty<FullInference>,
     ) -> Ty<FullInference> {
@@ -234,6 +448,45 @@ where
                 // the result is just `U`.
                 ty
             }
+
+            DeclaredPermKind::Share | DeclaredPermKind::Borrow => {
+                // If you have `share T` and you substitute `P U` for
+                // `T`, the result has the lesser of the two
+                // permissions (see `permMin` in
+                // `notes/permissions.prolog`). When `P` is not yet
+                // known, we create a new permission that is at most
+                // both of them.
+                let (_, declared_perm) = self.map_repr_perm(repr, perm);
+                let perm = match (declared_perm.untern(self), ty.perm.untern(self)) {
+                    (PermData::Known(declared), PermData::Known(actual)) => {
+                        if perm_less(declared, actual) {
+                            declared_perm
+                        } else {
+                            ty.perm
+                        }
+                    }
+
+                    _ => {
+                        let perm = self.storage.new_inferred_perm(&self.f_tables);
+                        self.storage.add_constraint(
+                            location,
+                            Constraint::PermLess {
+                                a: perm,
+                                b: declared_perm,
+                            },
+                        );
+                        self.storage.add_constraint(
+                            location,
+                            Constraint::PermLess {
+                                a: perm,
+                                b: ty.perm,
+                            },
+                        );
+                        perm
+                    }
+                };
+                Ty { perm, ..ty }
+            }
         }
     }
 }
@@ -9,8 +9,10 @@ use lark_entity::{Entity, EntityData, ItemKind, LangItem, MemberKind};
 use lark_error::ErrorReported;
 use lark_error::ErrorSentinel;
 use lark_hir as hir;
+use lark_intern::Intern;
 use lark_intern::Untern;
 use lark_ty::declaration::Declaration;
+use lark_ty::Generics;
 use lark_ty::Signature;
 use lark_ty::Ty;
 use lark_ty::{BaseData, BaseKind};
@@ -52,6 +54,7 @@ where
                 self.record_variable_ty(argument, input);
             }
         }
+        self.return_ty = Some(signature.output);
         self.check_expression(CheckType(signature.output), self.hir.root_expression);
     }
 
@@ -70,6 +73,15 @@ where
         }
     }
 
+    /// The type of an expression that never produces a value, like
+    /// `break`: it can stand in for whatever type is expected.
+    fn diverging_type(&mut self, mode: Mode<F>) -> Ty<F> {
+        match mode {
+            Synthesize => self.unit_type(),
+            CheckType(expected_ty) => expected_ty,
+        }
+    }
+
     fn type_or_infer_variable(&mut self, mode: Mode<F>) -> Ty<F> {
         match mode {
             Synthesize => self.new_infer_ty(),
@@ -145,11 +157,102 @@ where
                 ty
             }
 
+            hir::ExpressionData::Match { scrutinee, arms } => {
+                let scrutinee_ty = self.check_expression(Synthesize, scrutinee);
+
+                let ty = self.type_or_infer_variable(mode);
+                let hir = &self.hir.clone();
+                for arm_data in arms.iter_data(hir) {
+                    self.check_expression(CheckType(ty), arm_data.body);
+                }
+
+                // The types of the variables bound by each pattern
+                // depend on which enum we are matching on, so wait
+                // until that is known.
+                self.with_base_data(expression, scrutinee_ty.base, move |this, base_data| {
+                    this.check_match_arms(expression, scrutinee, scrutinee_ty, arms, base_data)
+                });
+
+                ty
+            }
+
+            hir::ExpressionData::While { condition, body } => {
+                self.check_expression(CheckType(self.boolean_type()), condition);
+                self.check_expression(CheckType(self.unit_type()), body);
+                self.unit_type()
+            }
+
+            hir::ExpressionData::For {
+                variable,
+                list,
+                body,
+            } => {
+                let list_ty = self.check_expression(Synthesize, list);
+                let element_ty = self.with_base_data(list, list_ty.base, move |this, base_data| {
+                    let BaseData { kind, generics } = base_data;
+                    match kind {
+                        BaseKind::Named(def_id) => {
+                            if let EntityData::LangItem(LangItem::List) = def_id.untern(this) {
+                                let element_ty = generics.iter().next().unwrap().assert_ty();
+                                return this.apply_owner_perm(list, list_ty.perm, element_ty);
+                            }
+
+                            this.record_error("cannot iterate over a value of this type", list);
+                            this.error_type()
+                        }
+
+                        BaseKind::Placeholder(_placeholder) => {
+                            this.record_error(
+                                "cannot iterate over a value of a generic type",
+                                list,
+                            );
+                            this.error_type()
+                        }
+
+                        BaseKind::Error => this.error_type(),
+                    }
+                });
+                let variable_ty = self.request_variable_ty(variable);
+                self.equate_types(variable, variable_ty, element_ty);
+                self.check_expression(CheckType(self.unit_type()), body);
+                self.unit_type()
+            }
+
+            hir::ExpressionData::Break {} | hir::ExpressionData::Continue {} => {
+                self.diverging_type(mode)
+            }
+
+            hir::ExpressionData::Return { value } => {
+                let return_ty = self.return_ty.unwrap();
+                self.check_expression(CheckType(return_ty), value);
+                self.diverging_type(mode)
+            }
+
             hir::ExpressionData::Literal { data } => match data.kind {
                 hir::LiteralKind::String => self.string_type(),
-                hir::LiteralKind::UnsignedInteger => self.uint_type(),
+                hir::LiteralKind::Integer => {
+                    let hint = match mode {
+                        Synthesize => None,
+                        CheckType(expected_ty) => Some(expected_ty),
+                    };
+                    self.integer_literal_ty(expression, data, hint)
+                }
             },
 
+            hir::ExpressionData::ListLiteral { elements } => {
+                let list_entity = EntityData::LangItem(LangItem::List).intern(self);
+                let generics = self.record_entity_and_get_generics(expression, list_entity);
+                let element_ty = generics.iter().next().unwrap().assert_ty();
+
+                let hir = &self.hir.clone();
+                for element in elements.iter(hir) {
+                    self.check_expression(CheckType(element_ty), element);
+                }
+
+                let list_ty = self.db.ty(list_entity).into_value();
+                self.substitute(expression, &generics, list_ty)
+            }
+
             hir::ExpressionData::Unit {} => self.unit_type(),
 
             hir::ExpressionData::Error { error: _ } => self.error_type(),
@@ -199,6 +302,24 @@ where
                     let BaseData { kind, generics } = base_data;
                     match kind {
                         BaseKind::Named(def_id) => {
+                            // The fields of a tuple are named by the
+                            // index of each element, whose type is the
+                            // corresponding generic argument.
+                            if let EntityData::LangItem(LangItem::Tuple(_)) = def_id.untern(this) {
+                                let index: Option<usize> = text.untern(this.db).parse().ok();
+                                return match index.and_then(|index| generics.iter().nth(index)) {
+                                    Some(element_ty) => {
+                                        let element_ty = element_ty.assert_ty();
+                                        this.apply_owner_perm(place, owner_ty.perm, element_ty)
+                                    }
+
+                                    None => {
+                                        this.record_error("field not found", name);
+                                        this.error_type()
+                                    }
+                                };
+                            }
+
                             match this.db.member_entity(def_id, MemberKind::Field, text) {
                                 Some(field_entity) => {
                                     this.record_entity(name, field_entity);
@@ -216,8 +337,37 @@ where
                         }
 
                         BaseKind::Placeholder(_placeholder) => {
-                            // Cannot presently access fields from generic types.
-                            this.record_error("cannot access fields from generic types(yet)", name);
+                            // A generic parameter could be instantiated with
+                            // any type, so we know nothing about its fields.
+                            this.record_error("values of a generic type have no fields", name);
+                            this.error_type()
+                        }
+
+                        BaseKind::Error => this.error_type(),
+                    }
+                })
+            }
+
+            hir::PlaceData::Index { owner, index } => {
+                let owner_ty = self.check_place(owner);
+                self.check_expression(CheckType(self.uint_type()), index);
+                self.with_base_data(place, owner_ty.base, move |this, base_data| {
+                    let BaseData { kind, generics } = base_data;
+                    match kind {
+                        BaseKind::Named(def_id) => {
+                            if let EntityData::LangItem(LangItem::List) = def_id.untern(this) {
+                                // The only generic argument of a list
+                                // is the type of its elements.
+                                let element_ty = generics.iter().next().unwrap().assert_ty();
+                                return this.apply_owner_perm(place, owner_ty.perm, element_ty);
+                            }
+
+                            this.record_error("cannot index into a value of this type", place);
+                            this.error_type()
+                        }
+
+                        BaseKind::Placeholder(_placeholder) => {
+                            this.record_error("cannot index into a value of a generic type", place);
                             this.error_type()
                         }
 
@@ -263,6 +413,16 @@ where
                         return self.check_arguments_in_case_of_error(arguments);
                     }
 
+                    EntityData::LangItem(LangItem::Format) => {
+                        // `format` accepts any number of values, of
+                        // any type, and produces a string
+                        let hir = &self.hir.clone();
+                        for argument_expr in arguments.iter(hir) {
+                            self.check_expression(Synthesize, argument_expr);
+                        }
+                        return self.string_type();
+                    }
+
                     _ => {
                         self.record_error("cannot call value of this type", expression);
                         return self.check_arguments_in_case_of_error(arguments);
@@ -275,20 +435,19 @@ where
                         <Signature<Declaration>>::error_sentinel(self, arguments.len())
                     }
                 };
-                let signature = self.substitute(expression, &generics, signature_decl);
-
                 self.check_arguments_against_signature(
                     expression,
-                    &signature.inputs[..],
-                    signature.output,
+                    &generics,
+                    &signature_decl,
                     arguments,
                     0,
                 )
             }
 
             BaseKind::Placeholder(_placeholder) => {
-                // Cannot presently invoke generic types.
-                self.record_error("cannot call a generic type (yet)", expression);
+                // A generic parameter could be instantiated with any
+                // type, including ones that are not functions.
+                self.record_error("cannot call a value of a generic type", expression);
                 return self.check_arguments_in_case_of_error(arguments);
             }
 
@@ -336,16 +495,16 @@ where
                         <Signature<Declaration>>::error_sentinel(self, arguments.len())
                     }
                 };
-                let signature = self.substitute(expression, &generics, signature_decl);
-
                 // The 0th item in the signature is the self type, so check that
                 let owner_expression = arguments.first(&self.hir).unwrap();
-                self.require_assignable(owner_expression, signature.inputs[0]);
+                let self_ty =
+                    self.substitute(owner_expression, &generics, signature_decl.inputs[0]);
+                self.require_assignable(owner_expression, self_ty);
 
                 self.check_arguments_against_signature(
                     method_name,
-                    &signature.inputs,
-                    signature.output,
+                    &generics,
+                    &signature_decl,
                     arguments,
                     1,
                 )
@@ -361,31 +520,36 @@ where
         }
     }
 
+    /// Checks the arguments of a call against the declared `signature`
+    /// of the callee, into which `generics` are substituted. Returns
+    /// the type of the result.
     fn check_arguments_against_signature(
         &mut self,
-        error_location: impl Into<hir::MetaIndex>,
-        inputs: &[Ty<F>],
-        output: Ty<F>,
+        error_location: impl Into<hir::MetaIndex> + Copy,
+        generics: &Generics<F>,
+        signature: &Signature<Declaration>,
         arguments: hir::List<hir::Expression>,
         skip: usize,
     ) -> Ty<F> {
         log::debug!(
-            "check_arguments_against_signature(inputs={:?}, output={:?}, arguments={:?})",
-            inputs.debug_with(self),
-            output.debug_with(self),
+            "check_arguments_against_signature(signature={:?}, arguments={:?})",
+            signature,
             arguments.debug_with(self),
         );
-        if inputs.len() != arguments.len() {
+        if signature.inputs.len() != arguments.len() {
             self.record_error("mismatched argument count", error_location);
             return self.check_arguments_in_case_of_error(arguments);
         }
 
+        // Each input is substituted on its own, so that any errors
+        // about its permissions point at the argument.
         let hir = &self.hir.clone();
-        for (&expected_ty, argument_expr) in inputs.iter().zip(arguments.iter(hir)).skip(skip) {
+        for (&input, argument_expr) in signature.inputs.iter().zip(arguments.iter(hir)).skip(skip) {
+            let expected_ty = self.substitute(argument_expr, generics, input);
             self.check_expression(CheckType(expected_ty), argument_expr);
         }
 
-        output
+        self.substitute(error_location, generics, signature.output)
     }
 
     fn check_arguments_in_case_of_error(&mut self, arguments: hir::List<hir::Expression>) -> Ty<F> {
@@ -408,10 +572,27 @@ where
             EntityData::ItemName {
                 kind: ItemKind::Struct,
                 ..
+            }
+            | EntityData::MemberName {
+                kind: MemberKind::Variant,
+                ..
             } => {
                 // see code below
             }
 
+            // The parser creates one field per element, named by its
+            // index, and each element has the type of the corresponding
+            // generic argument.
+            EntityData::LangItem(LangItem::Tuple(_)) => {
+                let hir = &self.hir.clone();
+                for (field_data, element_ty) in fields.iter_data(hir).zip(generics.iter()) {
+                    self.check_expression(CheckType(element_ty.assert_ty()), field_data.expression);
+                }
+
+                let entity_ty = self.db.ty(entity).into_value();
+                return self.substitute(expression, &generics, entity_ty);
+            }
+
             EntityData::Error(_) => {
                 // If we can't resolve the type of the struct, then just
                 // check the inner expressions. Resolve all the identifiers
@@ -440,7 +621,11 @@ where
 
         // Get a vector of **all** the fields.
         let mut missing_members: FxIndexSet<Entity> = match self.db.members(entity) {
-            Ok(members) => members.iter().map(|m| m.entity).collect(),
+            Ok(members) => members
+                .iter()
+                .filter(|m| m.kind == MemberKind::Field)
+                .map(|m| m.entity)
+                .collect(),
             Err(err) => return Ty::error_sentinel(self, err),
         };
 
@@ -483,6 +668,189 @@ where
         self.substitute(expression, &generics, entity_ty)
     }
 
+    /// Invoked to check the patterns of a `match` expression once
+    /// the base-data for the type of its scrutinee is known. Checks
+    /// that each pattern names a variant of the right enum, gives
+    /// types to the variables that the patterns bind, and checks
+    /// that every variant is covered.
+    fn check_match_arms(
+        &mut self,
+        expression: hir::Expression,
+        scrutinee: hir::Expression,
+        scrutinee_ty: Ty<F>,
+        arms: hir::List<hir::MatchArm>,
+        base_data: BaseData<F>,
+    ) -> Ty<F> {
+        let BaseData { kind, generics } = base_data;
+        let hir = &self.hir.clone();
+
+        let enum_entity = match kind {
+            BaseKind::Named(entity) => match entity.untern(self) {
+                EntityData::ItemName {
+                    kind: ItemKind::Enum,
+                    ..
+                } => Some(entity),
+
+                EntityData::Error(_) => None,
+
+                _ => {
+                    self.record_error("cannot match on a value of this type", scrutinee);
+                    None
+                }
+            },
+
+            BaseKind::Placeholder(_placeholder) => {
+                self.record_error("cannot match on a value of a generic type", scrutinee);
+                None
+            }
+
+            BaseKind::Error => None,
+        };
+
+        let enum_entity = match enum_entity {
+            Some(enum_entity) => enum_entity,
+            None => {
+                for arm_data in arms.iter_data(hir) {
+                    let bindings = self.pattern_bindings(arm_data.pattern);
+                    self.bind_to_error_type(bindings);
+                }
+                return self.unit_type();
+            }
+        };
+
+        // The variants that no arm has covered so far.
+        let mut missing_variants: FxIndexSet<Entity> = match self.db.members(enum_entity) {
+            Ok(members) => members
+                .iter()
+                .filter(|m| m.kind == MemberKind::Variant)
+                .map(|m| m.entity)
+                .collect(),
+            Err(err) => return Ty::error_sentinel(self, err),
+        };
+
+        // Once we have seen a `_` (or a pattern with errors), every
+        // value is covered.
+        let mut covers_everything = false;
+
+        for arm_data in arms.iter_data(hir) {
+            let pattern = arm_data.pattern;
+            let reachable = !covers_everything;
+            if !reachable {
+                self.record_error("unreachable match arm", pattern);
+            }
+
+            match hir[pattern] {
+                hir::PatternData::Wildcard => covers_everything = true,
+
+                hir::PatternData::Error { error: _, bindings } => {
+                    self.bind_to_error_type(bindings);
+                    covers_everything = true;
+                }
+
+                hir::PatternData::Variant { entity, bindings } => {
+                    let in_enum = match entity.untern(self) {
+                        EntityData::MemberName { base, .. } => base == enum_entity,
+                        _ => false,
+                    };
+                    if !in_enum {
+                        self.record_error("mismatched types", pattern);
+                        self.bind_to_error_type(bindings);
+                        continue;
+                    }
+
+                    if !missing_variants.remove(&entity) && reachable {
+                        self.record_error("unreachable match arm", pattern);
+                    }
+
+                    self.check_pattern_bindings(pattern, entity, bindings, scrutinee_ty, &generics);
+                }
+            }
+        }
+
+        if !covers_everything && !missing_variants.is_empty() {
+            let missing_names: Vec<String> = missing_variants
+                .iter()
+                .map(|&variant| match variant.untern(self) {
+                    EntityData::MemberName { base, id, .. } => match base.untern(self) {
+                        EntityData::ItemName { id: enum_id, .. } => {
+                            format!("`{}::{}`", enum_id.untern(self.db), id.untern(self.db))
+                        }
+                        _ => unreachable!("variant of non-enum"),
+                    },
+                    _ => unreachable!("variant is not a member"),
+                })
+                .collect();
+            self.record_error(
+                format!(
+                    "match is not exhaustive: missing {}",
+                    missing_names.join(", ")
+                ),
+                expression,
+            );
+        }
+
+        self.unit_type()
+    }
+
+    /// Gives the variables bound by a pattern for the variant
+    /// `variant` the types of the fields of that variant, in order.
+    fn check_pattern_bindings(
+        &mut self,
+        pattern: hir::Pattern,
+        variant: Entity,
+        bindings: hir::List<hir::Variable>,
+        scrutinee_ty: Ty<F>,
+        generics: &Generics<F>,
+    ) {
+        let fields: Vec<Entity> = match self.db.members(variant) {
+            Ok(members) => members
+                .iter()
+                .filter(|m| m.kind == MemberKind::Field)
+                .map(|m| m.entity)
+                .collect(),
+            Err(ErrorReported(_)) => vec![],
+        };
+
+        if fields.len() != bindings.len() {
+            self.record_error(
+                format!(
+                    "wrong number of bindings: expected {}, found {}",
+                    fields.len(),
+                    bindings.len()
+                ),
+                pattern,
+            );
+            self.bind_to_error_type(bindings);
+            return;
+        }
+
+        let hir = &self.hir.clone();
+        for (variable, field_entity) in bindings.iter(hir).zip(fields) {
+            let field_decl_ty = self.db.ty(field_entity).into_value();
+            let field_ty = self.substitute(pattern, generics, field_decl_ty);
+            let field_ty = self.apply_owner_perm(pattern, scrutinee_ty.perm, field_ty);
+            let variable_ty = self.request_variable_ty(variable);
+            self.equate_types(variable, variable_ty, field_ty);
+        }
+    }
+
+    fn pattern_bindings(&self, pattern: hir::Pattern) -> hir::List<hir::Variable> {
+        match self.hir[pattern] {
+            hir::PatternData::Variant { bindings, .. }
+            | hir::PatternData::Error { bindings, .. } => bindings,
+            hir::PatternData::Wildcard => hir::List::default(),
+        }
+    }
+
+    fn bind_to_error_type(&mut self, bindings: hir::List<hir::Variable>) {
+        let hir = &self.hir.clone();
+        for variable in bindings.iter(hir) {
+            let variable_ty = self.request_variable_ty(variable);
+            let error_type = self.error_type();
+            self.equate_types(variable, variable_ty, error_type);
+        }
+    }
+
     fn check_binary(
         &mut self,
         expression: hir::Expression,
@@ -494,8 +862,13 @@ where
         // left + right before we can say anything about the result
         // type. So use `with_base_data` to get a callback once that is
         // known.
-        let left_ty = self.check_expression(Synthesize, left);
-        let right_ty = self.check_expression(Synthesize, right);
+        let (left_ty, right_ty) = if self.is_integer_literal(left) {
+            let right_ty = self.check_expression(Synthesize, right);
+            (self.check_operand(left, right_ty), right_ty)
+        } else {
+            let left_ty = self.check_expression(Synthesize, left);
+            (left_ty, self.check_operand(right, left_ty))
+        };
         let result_ty =
             self.with_base_data(expression, left_ty.base, move |this, left_base_data| {
                 this.with_base_data(expression, right_ty.base, move |this, right_base_data| {
@@ -509,9 +882,16 @@ where
             });
 
         match operator {
-            hir::BinaryOperator::Equals | hir::BinaryOperator::NotEquals => {
-                // For the `==` and `!=` operators, we know the result
-                // will be boolean, so even if `result_ty` is an
+            hir::BinaryOperator::Equals
+            | hir::BinaryOperator::NotEquals
+            | hir::BinaryOperator::LessThan
+            | hir::BinaryOperator::LessThanOrEqual
+            | hir::BinaryOperator::GreaterThan
+            | hir::BinaryOperator::GreaterThanOrEqual
+            | hir::BinaryOperator::And
+            | hir::BinaryOperator::Or => {
+                // For comparisons and boolean operators, we know the
+                // result will be boolean, so even if `result_ty` is an
                 // inference variable, we can unify it *now* rather
                 // than wait until the input types are known.
                 let boolean_type = self.boolean_type();
@@ -550,6 +930,14 @@ where
                     match entity.untern(self) {
                         EntityData::LangItem(LangItem::Int) => int_type,
                         EntityData::LangItem(LangItem::Uint) => uint_type,
+
+                        // `+` concatenates strings
+                        EntityData::LangItem(LangItem::String)
+                            if operator == hir::BinaryOperator::Add =>
+                        {
+                            self.string_type()
+                        }
+
                         EntityData::Error(_) => self.error_type(),
                         _ => {
                             self.record_error(
@@ -582,6 +970,61 @@ where
                 // Either way, yields a boolean
                 boolean_type
             }
+
+            hir::BinaryOperator::LessThan
+            | hir::BinaryOperator::LessThanOrEqual
+            | hir::BinaryOperator::GreaterThan
+            | hir::BinaryOperator::GreaterThanOrEqual => {
+                match (&left_base_data.kind, &right_base_data.kind) {
+                    (BaseKind::Named(entity), BaseKind::Named(right_entity))
+                        if entity == right_entity =>
+                    {
+                        match entity.untern(self) {
+                            EntityData::LangItem(LangItem::Int)
+                            | EntityData::LangItem(LangItem::Uint)
+                            | EntityData::Error(_) => {}
+                            _ => {
+                                self.record_error("cannot compare values of this type", expression);
+                            }
+                        }
+                    }
+
+                    (BaseKind::Error, _) | (_, BaseKind::Error) => {}
+
+                    (BaseKind::Named(_), _) | (BaseKind::Placeholder(_), _) => {
+                        self.record_error("mismatched types", expression);
+                    }
+                }
+
+                // Either way, yields a boolean
+                boolean_type
+            }
+
+            hir::BinaryOperator::And | hir::BinaryOperator::Or => {
+                for base_data in vec![left_base_data, right_base_data] {
+                    match base_data.kind {
+                        BaseKind::Named(entity) => match entity.untern(self) {
+                            EntityData::LangItem(LangItem::Boolean) | EntityData::Error(_) => {}
+                            _ => {
+                                self.record_error(
+                                    "incompatible type for boolean operator",
+                                    expression,
+                                );
+                                break;
+                            }
+                        },
+
+                        BaseKind::Error => {}
+
+                        BaseKind::Placeholder(_) => {
+                            self.record_error("incompatible type for boolean operator", expression);
+                            break;
+                        }
+                    }
+                }
+
+                boolean_type
+            }
         }
     }
 
@@ -594,7 +1037,15 @@ where
         // We may want to add overloading later. So make sure we know
         // the type of the expression before we determine the type of
         // the output.
-        let value_ty = self.check_expression(Synthesize, value);
+        let value_ty = match operator {
+            hir::UnaryOperator::Not => self.check_expression(Synthesize, value),
+
+            // Only an `int` can be negated, so `-x` needs `x` to be one.
+            hir::UnaryOperator::Negate => {
+                let int_type = self.int_type();
+                self.check_operand(value, int_type)
+            }
+        };
         self.with_base_data(expression, value_ty.base, move |this, value_base_data| {
             this.check_unary_with_input_known(expression, operator, value_base_data)
         })
@@ -626,6 +1077,82 @@ where
                     self.error_type()
                 }
             },
+
+            hir::UnaryOperator::Negate => match &value_base_data.kind {
+                BaseKind::Named(entity) => match entity.untern(self) {
+                    EntityData::LangItem(LangItem::Int) => self.int_type(),
+
+                    EntityData::Error(_) => self.error_type(),
+
+                    _ => {
+                        self.record_error("incompatible type for '-' operator", expression);
+                        self.error_type()
+                    }
+                },
+
+                BaseKind::Error => self.error_type(),
+
+                BaseKind::Placeholder(_) => {
+                    self.record_error("unknown expression for operator", expression);
+                    self.error_type()
+                }
+            },
+        }
+    }
+
+    fn is_integer_literal(&self, expression: hir::Expression) -> bool {
+        match self.hir[expression] {
+            hir::ExpressionData::Literal { data } => data.kind == hir::LiteralKind::Integer,
+            _ => false,
+        }
+    }
+
+    /// Type-checks `operand`, whose type should match `other_ty`
+    /// (e.g., the other side of a binary operator). This is just
+    /// like synthesizing its type, except that an integer literal
+    /// becomes an `int` if `other_ty` is one, so that `x + 1` works
+    /// for an `int` `x`. If the types differ after all, the caller
+    /// reports that.
+    fn check_operand(&mut self, operand: hir::Expression, other_ty: Ty<F>) -> Ty<F> {
+        match self.hir[operand] {
+            hir::ExpressionData::Literal { data } if data.kind == hir::LiteralKind::Integer => {
+                let ty = self.integer_literal_ty(operand, data, Some(other_ty));
+                self.record_expression_ty(operand, ty);
+                ty
+            }
+
+            _ => self.check_expression(Synthesize, operand),
+        }
+    }
+
+    /// The type of the integer literal `expression`: an `int` if it is
+    /// negative or if `hint` is already known to be an `int`, and a
+    /// `uint` otherwise. Reports an error if the value does not fit.
+    fn integer_literal_ty(
+        &mut self,
+        expression: hir::Expression,
+        data: hir::LiteralData,
+        hint: Option<Ty<F>>,
+    ) -> Ty<F> {
+        let is_int = data.is_negative(self.db)
+            || match hint.map(|ty| self.unify.shallow_resolve_data(ty.base)) {
+                Some(Ok(BaseData {
+                    kind: BaseKind::Named(entity),
+                    ..
+                })) => entity.untern(self) == EntityData::LangItem(LangItem::Int),
+                _ => false,
+            };
+
+        if is_int {
+            if data.int_value(self.db).is_none() {
+                self.record_error("integer literal is too large for `int`", expression);
+            }
+            self.int_type()
+        } else {
+            if data.uint_value(self.db).is_none() {
+                self.record_error("integer literal is too large for `uint`", expression);
+            }
+            self.uint_type()
         }
     }
 }
@@ -19,6 +19,8 @@ use lark_ty::base_inferred::BaseInferred;
 use lark_ty::base_inferred::BaseInferredTables;
 use lark_ty::declaration::Declaration;
 use lark_ty::declaration::DeclarationTables;
+use lark_ty::full_inferred::FullInferred;
+use lark_ty::full_inferred::FullInferredTables;
 use lark_ty::map_family::{FamilyMapper, Map};
 use lark_ty::BaseData;
 use lark_ty::Generics;
@@ -40,13 +42,21 @@ mod resolve_to_base_inferred;
 mod substitute;
 
 salsa::query_group! {
-    pub trait TypeCheckDatabase: ParserDatabase + AsRef<BaseInferredTables> {
+    pub trait TypeCheckDatabase: ParserDatabase + AsRef<BaseInferredTables> + AsRef<FullInferredTables> {
         /// Compute the "base type information" for a given fn body.
         /// This is the type information excluding permissions.
         fn base_type_check(key: Entity) -> WithError<Arc<TypeCheckResults<BaseInferred>>> {
             type BaseTypeCheckQuery;
             use fn query_definitions::base_type_check;
         }
+
+        /// Compute the full type information for a given fn body,
+        /// including permissions; reports an error for each place
+        /// where a value is used with more permissions than it has.
+        fn full_type_check(key: Entity) -> WithError<Arc<TypeCheckResults<FullInferred>>> {
+            type FullTypeCheckQuery;
+            use fn query_definitions::full_type_check;
+        }
     }
 }
 
@@ -82,6 +92,10 @@ struct TypeChecker<'me, DB: TypeCheckDatabase, F: TypeCheckerFamily, S> {
     /// Information about each universe that we have created.
     universe_binders: IndexVec<Universe, UniverseBinder>,
 
+    /// The output type of `fn_entity`, which `return` expressions
+    /// must produce. Set by `check_fn_body`.
+    return_ty: Option<Ty<F>>,
+
     /// Errors that we encountered during the type-check.
     errors: Vec<Diagnostic>,
 }
@@ -241,6 +255,12 @@ impl<F: TypeFamily> TypeCheckResults<F> {
     pub fn has_recorded_ty(&self, index: impl Into<hir::MetaIndex>) -> bool {
         self.types.contains_key(&index.into())
     }
+
+    /// Load the entity that `index` (e.g., the identifier of a field
+    /// or method) resolved to, if any.
+    pub fn opt_entity(&self, index: impl Into<hir::MetaIndex>) -> Option<Entity> {
+        self.entities.get(&index.into()).cloned()
+    }
 }
 
 impl<F: TypeFamily> Default for TypeCheckResults<F> {
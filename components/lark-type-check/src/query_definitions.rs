@@ -1,4 +1,6 @@
 use crate::base_inference::{BaseInference, BaseInferenceTables};
+use crate::full_inference::{FullInference, FullInferenceTables};
+use crate::full_inference::{FullInferenceStorage, ResolveToFullInferred};
 use crate::resolve_to_base_inferred::ResolveToBaseInferred;
 use crate::TypeCheckDatabase;
 use crate::TypeCheckResults;
@@ -10,6 +12,7 @@ use lark_entity::Entity;
 use lark_error::{Diagnostic, WithError};
 use lark_indices::IndexVec;
 use lark_ty::base_inferred::BaseInferred;
+use lark_ty::full_inferred::FullInferred;
 use lark_ty::map_family::Map;
 use lark_unify::InferVar;
 use lark_unify::UnificationTable;
@@ -31,6 +34,7 @@ crate fn base_type_check(
         unify: UnificationTable::new(interners.clone()),
         storage: TypeCheckResults::default(),
         universe_binders: IndexVec::from(vec![UniverseBinder::Root]),
+        return_ty: None,
         errors: vec![],
     };
 
@@ -80,3 +84,62 @@ crate fn base_type_check(
         errors,
     }
 }
+
+crate fn full_type_check(
+    db: &impl TypeCheckDatabase,
+    fn_entity: Entity,
+) -> WithError<Arc<TypeCheckResults<FullInferred>>> {
+    let fn_body = db.fn_body(fn_entity).into_value();
+    let interners = FullInferenceTables::default();
+    let mut full_type_checker: TypeChecker<'_, _, FullInference, _> = TypeChecker {
+        db,
+        fn_entity,
+        f_tables: interners.clone(),
+        hir: fn_body.clone(),
+        ops_arena: Arena::new(),
+        ops_blocked: FxIndexMap::default(),
+        unify: UnificationTable::new(interners.clone()),
+        storage: FullInferenceStorage::new(),
+        universe_binders: IndexVec::from(vec![UniverseBinder::Root]),
+        return_ty: None,
+        errors: vec![],
+    };
+
+    // Run the full type-check, which (in addition to the base types)
+    // gathers up the constraints on permissions.
+    full_type_checker.check_fn_body();
+
+    // Complete all deferred type operations; run to steady state.
+    loop {
+        let vars: Vec<InferVar> = full_type_checker.unify.drain_events().collect();
+        if vars.is_empty() {
+            break;
+        }
+        for var in vars {
+            full_type_checker.trigger_ops(var);
+        }
+    }
+
+    // Any operations that never executed are blocked on unresolved
+    // variables; `base_type_check` already reports those as errors.
+
+    // Solve the permission constraints, reporting those that cannot
+    // be satisfied.
+    let perm_solution = full_type_checker.solve_perm_constraints();
+
+    // Record the final results.
+    let inferred_results = full_type_checker
+        .storage
+        .results
+        .map(&mut ResolveToFullInferred::new(
+            &mut full_type_checker.unify,
+            &full_type_checker.f_tables,
+            &perm_solution,
+            db.as_ref(),
+        ));
+
+    WithError {
+        value: Arc::new(inferred_results),
+        errors: full_type_checker.errors,
+    }
+}
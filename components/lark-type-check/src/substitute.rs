@@ -1,3 +1,4 @@
+use lark_hir as hir;
 use lark_intern::Untern;
 use lark_ty::declaration;
 use lark_ty::declaration::Declaration;
@@ -17,6 +18,11 @@ where
     V: std::ops::Index<BoundVar, Output = Generic<F>>,
 {
     delegate: &'me mut dyn SubstitutionDelegate<F>,
+
+    /// The code that the substituted value is used for, which is
+    /// blamed if the permissions of a generic argument don't fit.
+    location: hir::MetaIndex,
+
     values: &'me V,
 }
 
@@ -32,8 +38,15 @@ crate trait SubstitutionDelegate<F: TypeFamily>: AsRef<DeclarationTables> {
     /// Map the repr/perm from a case where the "base type" is
     /// substituted to `ty`; e.g. if the user declared `own T`, and
     /// `T` maps to `ty`, then this function applies the `own` from
-    /// that declaration to `ty`.
-    fn apply_repr_perm(&mut self, repr: ReprKind, perm: declaration::Perm, ty: Ty<F>) -> Ty<F>;
+    /// that declaration to `ty`. Any constraints this requires are
+    /// attributed to `location`.
+    fn apply_repr_perm(
+        &mut self,
+        location: hir::MetaIndex,
+        repr: ReprKind,
+        perm: declaration::Perm,
+        ty: Ty<F>,
+    ) -> Ty<F>;
 }
 
 impl<F, V> Substitution<'me, F, V>
@@ -41,8 +54,16 @@ where
     F: TypeFamily,
     V: std::ops::Index<BoundVar, Output = Generic<F>>,
 {
-    crate fn new(delegate: &'me mut dyn SubstitutionDelegate<F>, values: &'me V) -> Self {
-        Substitution { delegate, values }
+    crate fn new(
+        delegate: &'me mut dyn SubstitutionDelegate<F>,
+        location: hir::MetaIndex,
+        values: &'me V,
+    ) -> Self {
+        Substitution {
+            delegate,
+            location,
+            values,
+        }
     }
 }
 
@@ -68,7 +89,7 @@ where
             BoundVarOr::BoundVar(var) => {
                 // This corresponds to something like `own T`.
                 let g = self.values[var].assert_ty();
-                self.delegate.apply_repr_perm(repr, perm, g)
+                self.delegate.apply_repr_perm(self.location, repr, perm, g)
             }
 
             BoundVarOr::Known(base_data) => {
@@ -0,0 +1,232 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// A program without errors.
+const VALID: &str = "tests/test_files/call.lark";
+
+/// A program with a single type error.
+const INVALID: &str = "tests/test_files/error_type_mismatch.lark";
+
+/// Runs `lark` with the given arguments.
+fn lark(args: &[&str]) -> Output {
+    Command::new("cargo")
+        .arg("run")
+        .arg("--quiet")
+        .arg("--")
+        .args(args)
+        .output()
+        .expect("Failed to spawn child process")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+fn check_without_errors() {
+    let output = lark(&["check", VALID]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn check_with_errors() {
+    let output = lark(&["check", INVALID]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("error: Mismatched types"));
+}
+
+#[test]
+fn check_quiet() {
+    let output = lark(&["check", INVALID, "--quiet"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "1\n");
+    assert!(!stderr(&output).contains("error:"));
+
+    let output = lark(&["check", VALID, "--quiet"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "0\n");
+}
+
+#[test]
+fn build_with_errors() {
+    let target = std::env::temp_dir().join("lark_cli_build_with_errors");
+    let output = lark(&["build", INVALID, target.to_str().unwrap()]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(!target.exists());
+}
+
+#[test]
+fn build_and_run() {
+    let target = std::env::temp_dir().join("lark_cli_build_and_run");
+    let output = lark(&["build", VALID, target.to_str().unwrap()]);
+
+    assert_eq!(output.status.code(), Some(0));
+
+    let run = Command::new(&target).output().unwrap();
+    assert_eq!(stdout(&run), "false\n");
+}
+
+#[test]
+fn missing_file() {
+    let output = lark(&["check", "tests/test_files/does_not_exist.lark"]);
+
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).contains("failed to open"));
+}
+
+#[test]
+fn usage_errors() {
+    assert_eq!(lark(&[]).status.code(), Some(2));
+    assert_eq!(lark(&["frobnicate", VALID]).status.code(), Some(2));
+    assert_eq!(
+        lark(&["build", VALID, "--backend", "cobol"]).status.code(),
+        Some(2)
+    );
+}
+
+#[test]
+fn check_with_json_errors() {
+    let output = lark(&["check", INVALID, "--message-format=json"]);
+
+    assert_eq!(output.status.code(), Some(1));
+
+    let stdout = stdout(&output);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 1);
+
+    let error: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(error["severity"], "error");
+    assert_eq!(error["label"], "Mismatched types");
+    assert_eq!(error["span"]["file"], INVALID);
+    assert_eq!(error["span"]["line_start"], 1);
+    assert_eq!(error["span"]["column_start"], 2);
+    assert_eq!(error["span"]["line_end"], 1);
+    assert_eq!(error["span"]["column_end"], 3);
+}
+
+#[test]
+fn check_with_json_and_no_errors() {
+    let output = lark(&["check", VALID, "--message-format=json"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn unknown_message_format() {
+    let output = lark(&["check", VALID, "--message-format=xml"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("unknown message format `xml`"));
+}
+
+/// Creates a fresh directory holding the given files, for tests of
+/// projects with a manifest.
+fn project_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(name);
+    let _ = fs::remove_dir_all(&dir);
+
+    for (path, contents) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    dir
+}
+
+/// Runs `lark` with the given arguments in the directory `dir`.
+fn lark_in(dir: &Path, args: &[&str]) -> Output {
+    Command::new("cargo")
+        .arg("run")
+        .arg("--quiet")
+        .arg("--manifest-path")
+        .arg(Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml"))
+        .arg("--")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("Failed to spawn child process")
+}
+
+#[test]
+fn build_project_with_entry() {
+    let dir = project_dir(
+        "lark_cli_project_with_entry",
+        &[
+            (
+                "Lark.toml",
+                "[project]\nname = \"hello\"\nsources = [\"src\"]\nentry = \"start\"\n",
+            ),
+            ("src/start.lark", "def start() {\n    debug(greeting())\n}\n"),
+            (
+                "src/util.lark",
+                "def greeting() -> String {\n    \"hello\"\n}\n\ndef main() {\n    debug(\"not the entry\")\n}\n",
+            ),
+        ],
+    );
+
+    let output = lark_in(&dir, &["build"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+
+    let run = Command::new(dir.join("hello")).output().unwrap();
+    assert_eq!(stdout(&run), "hello\n");
+}
+
+#[test]
+fn project_with_missing_source() {
+    let dir = project_dir(
+        "lark_cli_project_with_missing_source",
+        &[(
+            "Lark.toml",
+            "[project]\nname = \"hello\"\nsources = [\"src/missing.lark\"]\n",
+        )],
+    );
+
+    let output = lark_in(&dir, &["check"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        stderr(&output).contains("source `src/missing.lark` listed in `Lark.toml` does not exist")
+    );
+}
+
+#[test]
+fn project_with_invalid_manifest() {
+    let dir = project_dir(
+        "lark_cli_project_with_invalid_manifest",
+        &[("Lark.toml", "[project]\nsources = [\"src\"]\n")],
+    );
+
+    let output = lark_in(&dir, &["check"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("failed to parse `Lark.toml`"));
+}
+
+#[test]
+fn project_without_entry() {
+    let dir = project_dir(
+        "lark_cli_project_without_entry",
+        &[
+            (
+                "Lark.toml",
+                "[project]\nname = \"hello\"\nsources = [\"src\"]\n",
+            ),
+            ("src/lib.lark", "def helper() {\n    debug(1)\n}\n"),
+        ],
+    );
+
+    let output = lark_in(&dir, &["build"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("no function named `main` found"));
+}
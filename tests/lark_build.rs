@@ -0,0 +1,70 @@
+use lark_build::{BuildError, CodegenType, GeneratedSource};
+use lark_test::*;
+
+const SOURCE: &str = "def main() {\n    debug(11)\n    debug(22)\n}\n";
+
+/// Generates Rust for `SOURCE`, returning the generated code along with
+/// the (zero-based) line that the second `debug` call became.
+fn generate() -> (GeneratedSource, usize) {
+    let db = db_with_test("build.lark", SOURCE);
+    let generated = lark_build::codegen(&db, CodegenType::Rust, "main");
+    assert!(generated.errors.is_empty());
+
+    let line = generated
+        .value
+        .text
+        .lines()
+        .position(|line| line.contains("22"))
+        .expect("no code generated for `debug(22)`");
+
+    (generated.value, line)
+}
+
+fn spanned_text(span: lark_span::Span<lark_span::FileName>) -> &'static str {
+    &SOURCE[span.start().to_usize()..span.end().to_usize()]
+}
+
+#[test]
+fn source_map_points_at_lark_code() {
+    let (generated, line) = generate();
+
+    let span = generated.source_map.span_for_line(line).unwrap();
+    assert_eq!(spanned_text(span), "debug(22)");
+}
+
+#[test]
+fn unmapped_source_has_no_spans() {
+    let generated = GeneratedSource::unmapped("fn main() {}\n".to_string());
+
+    assert!(generated.source_map.span_for_line(0).is_none());
+}
+
+#[test]
+fn rustc_errors_point_at_lark_code() {
+    let (mut generated, line) = generate();
+
+    // Break the Rust code generated for `debug(22)`, keeping every
+    // other line where it was.
+    let lines: Vec<&str> = generated.text.lines().collect();
+    let broken: Vec<&str> = lines
+        .iter()
+        .enumerate()
+        .map(|(index, &text)| if index == line { "let = ;" } else { text })
+        .collect();
+    generated.text = broken.join("\n");
+
+    let target = std::env::temp_dir().join("lark_build_rustc_errors");
+    match lark_build::build(target.to_str().unwrap(), &generated, CodegenType::Rust) {
+        Err(BuildError::Diagnostics(diagnostics)) => {
+            assert!(!diagnostics.is_empty());
+            for diagnostic in diagnostics {
+                assert!(diagnostic
+                    .label
+                    .starts_with("generated Rust code failed to compile: "));
+                assert_eq!(spanned_text(diagnostic.span), "debug(22)");
+            }
+        }
+        Ok(()) => panic!("broken Rust code compiled"),
+        Err(BuildError::Io(err)) => panic!("rustc errors were not mapped: {}", err),
+    }
+}
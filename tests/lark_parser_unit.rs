@@ -1,6 +1,22 @@
+use lark_entity::Entity;
+use lark_entity::EntityData;
+use lark_entity::ItemKind;
+use lark_error::ErrorReported;
+use lark_intern::Intern;
+use lark_parser::macros::EntityMacroDefinition;
+use lark_parser::parser::Parser;
+use lark_parser::syntax::entity::InvalidParsedEntity;
+use lark_parser::syntax::entity::ParsedEntity;
+use lark_parser::syntax::entity::ParsedEntityThunk;
+use lark_parser::syntax::identifier::SpannedGlobalIdentifier;
 use lark_parser::ParserDatabase;
+use lark_parser::ParserDatabaseExt;
 use lark_span::ByteIndex;
+use lark_span::FileName;
+use lark_span::Spanned;
+use lark_string::GlobalIdentifier;
 use lark_test::*;
+use std::sync::Arc;
 
 #[test]
 fn location() {
@@ -42,3 +58,52 @@ fn location() {
         &loc_4,
     );
 }
+
+/// `test <id>`: a macro defined outside of the parser, declaring an
+/// item with no contents.
+struct TestDeclaration;
+
+impl EntityMacroDefinition for TestDeclaration {
+    fn expect(
+        &self,
+        parser: &mut Parser<'_>,
+        base: Entity,
+        macro_name: Spanned<GlobalIdentifier, FileName>,
+    ) -> Result<ParsedEntity, ErrorReported> {
+        let name = parser.expect(SpannedGlobalIdentifier)?;
+        let entity = EntityData::ItemName {
+            base,
+            kind: ItemKind::Struct,
+            id: name.value,
+        }
+        .intern(parser);
+
+        Ok(ParsedEntity::new(
+            entity,
+            macro_name.span.extended_until_end_of(parser.last_span()),
+            name.span,
+            ParsedEntityThunk::new(InvalidParsedEntity),
+        ))
+    }
+}
+
+#[test]
+fn registered_entity_macro_is_scoped() {
+    let mut db = db_with_test("a.lark", "test foo\n");
+    db.add_file("b.lark", "test bar\n");
+
+    let a = "a.lark".into_file_name(&db);
+    let b = "b.lark".into_file_name(&db);
+    let a_entity = EntityData::InputFile { file: a }.intern(&db);
+    db.register_entity_macro(Some(a_entity), "test", Arc::new(TestDeclaration));
+
+    // Within `a.lark`, `test` declares an item:
+    let parsed_a = db.parsed_file(a);
+    assert!(parsed_a.errors.is_empty(), "{:#?}", parsed_a.errors);
+    assert_eq!(parsed_a.value.entities().len(), 1);
+
+    // ...but it is not in scope in `b.lark`:
+    let parsed_b = db.parsed_file(b);
+    assert!(!parsed_b.errors.is_empty());
+    assert_eq!(parsed_b.errors[0].label, "no macro with this name");
+}